@@ -0,0 +1,380 @@
+//! Concurrent register and login throughput against a SQLite file, before
+//! and after the connection pool. The baseline reproduces the earlier
+//! adapter, one mutex-guarded connection queried on the async executor, and
+//! runs next to the pooled adapter at several pool sizes under the same
+//! concurrency. Only the user repository differs between them: the other
+//! ports are in memory, and passwords are hashed on blocking threads in both.
+//! Run with `cargo bench --bench register_login`.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use kata_hexagonal::{
+    application::{
+        dtos::{UserLoginRequest, UserRegisterRequest},
+        email_verification_sender::EmailVerificationSender,
+        user_login_service::UserLoginService,
+        user_register_service::UserRegisterService,
+    },
+    domain::{
+        entities::user::User,
+        repositories::{
+            repository_error::RepositoryError,
+            user_query::{UserPage, UserQuery},
+            user_repository::UserRepository,
+        },
+        value_objects::{
+            email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password, role::Role,
+        },
+    },
+    infrastructure::{
+        in_memory_audit_log::InMemoryAuditLog,
+        in_memory_email_verification_token_repository::InMemoryEmailVerificationTokenRepository,
+        in_memory_event_bus::InMemoryEventBus,
+        in_memory_login_challenge_repository::InMemoryLoginChallengeRepository,
+        in_memory_mailer::InMemoryMailer,
+        in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+        in_memory_two_factor_repository::InMemoryTwoFactorRepository,
+        jwt_token_issuer::JwtTokenIssuer,
+        sqlite_migrations,
+        sqlite_user_repository::{PoolOptions, Sqlite},
+    },
+};
+use rusqlite::{named_params, Connection, OptionalExtension};
+use tempfile::TempDir;
+
+const CONCURRENCY: usize = 32;
+const POOL_SIZES: [u32; 4] = [1, 2, 4, 8];
+const PASSWORD: &str = "BenchPass123_";
+
+#[derive(Clone, Copy)]
+enum Adapter {
+    /// One connection behind a mutex, queried on the executor.
+    MutexConnection,
+    /// Pooled connections in WAL mode, queried on blocking threads.
+    Pool(u32),
+}
+
+impl Adapter {
+    fn all() -> impl Iterator<Item = Adapter> {
+        std::iter::once(Adapter::MutexConnection).chain(POOL_SIZES.map(Adapter::Pool))
+    }
+
+    fn id(&self) -> BenchmarkId {
+        match self {
+            Adapter::MutexConnection => BenchmarkId::new("mutex_connection", 1),
+            Adapter::Pool(size) => BenchmarkId::new("pool_size", size),
+        }
+    }
+}
+
+/// The user repository as it was before the pool: every query locks the one
+/// connection and runs on the calling task. Only what registering and
+/// logging in use is implemented.
+struct MutexSqlite {
+    connection: Mutex<Connection>,
+}
+
+impl MutexSqlite {
+    fn open(path: &str) -> MutexSqlite {
+        let mut connection = Connection::open(path).unwrap();
+        sqlite_migrations::migrate(&mut connection).unwrap();
+        MutexSqlite {
+            connection: Mutex::new(connection),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, RepositoryError> {
+        self.connection
+            .lock()
+            .map_err(|e| RepositoryError::Unavailable(e.to_string()))
+    }
+}
+
+fn unavailable(error: rusqlite::Error) -> RepositoryError {
+    RepositoryError::Unavailable(error.to_string())
+}
+
+#[async_trait]
+impl UserRepository for MutexSqlite {
+    async fn save(&self, user: User) -> Result<(), RepositoryError> {
+        self.lock()?
+            .execute(
+                "INSERT INTO users
+                (id, email, password, email_verified, failed_login_attempts, locked_until, deleted_at,
+                created_at, role)
+                VALUES (:id, :email, :password, :email_verified, :failed_login_attempts,
+                :locked_until, :deleted_at, :created_at, :role)
+                ON CONFLICT (id) DO UPDATE SET email = excluded.email, password = excluded.password,
+                email_verified = excluded.email_verified,
+                failed_login_attempts = excluded.failed_login_attempts,
+                locked_until = excluded.locked_until, deleted_at = excluded.deleted_at,
+                role = excluded.role",
+                named_params! {
+                    ":id": user.id(),
+                    ":email": user.email(),
+                    ":password": user.password(),
+                    ":email_verified": user.is_email_verified(),
+                    ":failed_login_attempts": user.failed_login_attempts(),
+                    ":locked_until": user.locked_until(),
+                    ":deleted_at": user.deleted_at(),
+                    ":created_at": user.created_at(),
+                    ":role": user.role().as_str(),
+                },
+            )
+            .map_err(unavailable)?;
+
+        Ok(())
+    }
+
+    async fn find_by_id(&self, _id: Id) -> Result<Option<User>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn find_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError> {
+        self.lock()?
+            .query_row(
+                "SELECT id, email, password, failed_login_attempts, locked_until, created_at
+                FROM users WHERE email = :email AND deleted_at IS NULL",
+                named_params! { ":email": email.to_string() },
+                |row| {
+                    let id: String = row.get("id")?;
+                    let email: String = row.get("email")?;
+                    let password: String = row.get("password")?;
+                    Ok(User::new(
+                        Id::from(id).unwrap(),
+                        Email::new(email).unwrap(),
+                        Password::from_hash(password),
+                    )
+                    .with_login_attempts(
+                        row.get("failed_login_attempts")?,
+                        row.get("locked_until")?,
+                    )
+                    .with_created_at(row.get("created_at")?))
+                },
+            )
+            .optional()
+            .map_err(unavailable)
+    }
+
+    async fn find_deleted_by_email(&self, _email: Email) -> Result<Option<User>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn find_page(&self, _query: UserQuery) -> Result<UserPage, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn remove(&self, _user: User) -> Result<(), RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn register_failed_login(
+        &self,
+        _id: Id,
+        _policy: LockoutPolicy,
+        _now: u64,
+    ) -> Result<(), RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn register_successful_login(&self, user: User) -> Result<bool, RepositoryError> {
+        let updated = self
+            .lock()?
+            .execute(
+                "UPDATE users SET failed_login_attempts = 0, locked_until = NULL
+                WHERE id = :id AND password = :password AND deleted_at IS NULL",
+                named_params! { ":id": user.id(), ":password": user.password() },
+            )
+            .map_err(unavailable)?;
+
+        Ok(updated > 0)
+    }
+
+    async fn upgrade_password_hash(
+        &self,
+        _id: Id,
+        _previous_hash: String,
+        _upgraded_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn replace_password(
+        &self,
+        _user: User,
+        _previous_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn mark_deleted(&self, _user: User) -> Result<bool, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn assign_role(&self, _id: Id, _role: Role) -> Result<bool, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn purge_deleted_before(&self, _cutoff: u64) -> Result<u64, RepositoryError> {
+        unimplemented!()
+    }
+}
+
+/// Keep the directory alive for as long as the database is used.
+fn open_users(
+    runtime: &tokio::runtime::Runtime,
+    adapter: Adapter,
+) -> (TempDir, Arc<dyn UserRepository>) {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("bench.db");
+    let path = path.to_str().unwrap();
+    let users: Arc<dyn UserRepository> = match adapter {
+        Adapter::MutexConnection => Arc::new(MutexSqlite::open(path)),
+        Adapter::Pool(max_connections) => {
+            let options = PoolOptions {
+                max_connections,
+                ..PoolOptions::default()
+            };
+            Arc::new(runtime.block_on(Sqlite::with_pool(path, options)).unwrap())
+        }
+    };
+    (dir, users)
+}
+
+fn create_register_service(users: &Arc<dyn UserRepository>) -> Arc<UserRegisterService> {
+    Arc::new(UserRegisterService::new(
+        users.clone(),
+        EmailVerificationSender::new(
+            Arc::new(InMemoryEmailVerificationTokenRepository::new()),
+            Arc::new(InMemoryMailer::new()),
+            "http://localhost/verify".to_string(),
+        ),
+        Arc::new(InMemoryEventBus::new()),
+        Arc::new(InMemoryAuditLog::new()),
+    ))
+}
+
+fn create_login_service(users: &Arc<dyn UserRepository>) -> Arc<UserLoginService> {
+    Arc::new(UserLoginService::new(
+        users.clone(),
+        Arc::new(JwtTokenIssuer::new(&[7; 32], Duration::from_secs(60))),
+        Arc::new(InMemoryRefreshTokenRepository::new()),
+        Arc::new(InMemoryTwoFactorRepository::new()),
+        Arc::new(InMemoryLoginChallengeRepository::new()),
+        Arc::new(InMemoryEventBus::new()),
+        Arc::new(InMemoryAuditLog::new()),
+    ))
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+fn register_login(c: &mut Criterion) {
+    let runtime = runtime();
+    let mut group = c.benchmark_group("register_login");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(CONCURRENCY as u64));
+
+    for adapter in Adapter::all() {
+        let (_dir, users) = open_users(&runtime, adapter);
+        let register = create_register_service(&users);
+        let login = create_login_service(&users);
+        let next = AtomicUsize::new(0);
+
+        group.bench_with_input(adapter.id(), &adapter, |b, _| {
+            b.to_async(&runtime).iter(|| {
+                let users = (0..CONCURRENCY).map(|_| {
+                    let email = format!("user{}@example.com", next.fetch_add(1, Ordering::Relaxed));
+                    let register = register.clone();
+                    let login = login.clone();
+                    tokio::spawn(async move {
+                        register
+                            .register(UserRegisterRequest {
+                                email: email.clone(),
+                                password: PASSWORD.to_string(),
+                                client_ip: None,
+                            })
+                            .await
+                            .unwrap();
+                        login
+                            .login(UserLoginRequest {
+                                email,
+                                password: PASSWORD.to_string(),
+                                client_ip: None,
+                            })
+                            .await
+                            .unwrap();
+                    })
+                });
+                let users: Vec<_> = users.collect();
+                async move {
+                    for user in users {
+                        user.await.unwrap();
+                    }
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+/// The same comparison without password hashing, which otherwise dominates.
+fn save_and_find(c: &mut Criterion) {
+    let runtime = runtime();
+    let password = Password::new(PASSWORD.to_string()).unwrap();
+    let mut group = c.benchmark_group("save_and_find");
+    group.throughput(Throughput::Elements(CONCURRENCY as u64));
+
+    for adapter in Adapter::all() {
+        let (_dir, users) = open_users(&runtime, adapter);
+        let next = AtomicUsize::new(0);
+
+        group.bench_with_input(adapter.id(), &adapter, |b, _| {
+            b.to_async(&runtime).iter(|| {
+                let tasks = (0..CONCURRENCY).map(|_| {
+                    let email = Email::new(format!(
+                        "user{}@example.com",
+                        next.fetch_add(1, Ordering::Relaxed)
+                    ))
+                    .unwrap();
+                    let user = User::new(
+                        Id::generate_unique_identifier(),
+                        email.clone(),
+                        password.clone(),
+                    );
+                    let users = users.clone();
+                    tokio::spawn(async move {
+                        users.save(user).await.unwrap();
+                        users.find_by_email(email).await.unwrap().unwrap();
+                    })
+                });
+                let tasks: Vec<_> = tasks.collect();
+                async move {
+                    for task in tasks {
+                        task.await.unwrap();
+                    }
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, register_login, save_and_find);
+criterion_main!(benches);
@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    entities::user::ACCOUNT_RESTORE_WINDOW,
+    repositories::{
+        refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+    },
+    value_objects::{id::Id, lockout_policy::LockoutPolicy},
+};
+
+use super::{
+    application_error::ApplicationError,
+    blocking::run_blocking,
+    dtos::{AccountDeleteRequest, MessageResponse},
+    ports::event_publisher::EventPublisher,
+    user_change_password_service::InvalidCurrentPasswordError,
+    user_find_service::UserNotFoundError,
+    user_login_service::AccountLockedError,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Lets users delete their own account. The account is only soft-deleted so
+/// it can be restored until it is purged; its sessions end right away.
+pub struct AccountDeleteService {
+    user_repository: Arc<dyn UserRepository>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    event_publisher: Arc<dyn EventPublisher>,
+    lockout_policy: LockoutPolicy,
+}
+
+impl AccountDeleteService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        event_publisher: Arc<dyn EventPublisher>,
+    ) -> Self {
+        AccountDeleteService {
+            user_repository,
+            refresh_token_repository,
+            event_publisher,
+            lockout_policy: LockoutPolicy::default(),
+        }
+    }
+
+    pub fn with_lockout_policy(mut self, lockout_policy: LockoutPolicy) -> Self {
+        self.lockout_policy = lockout_policy;
+        self
+    }
+
+    pub async fn delete(
+        &self,
+        request: AccountDeleteRequest,
+    ) -> Result<MessageResponse, ApplicationError> {
+        let user_id = Id::from(request.user_id)?;
+        let user = self
+            .user_repository
+            .find_by_id(user_id.clone())
+            .await?
+            .ok_or(UserNotFoundError {})?;
+
+        let now = time::now();
+        if user.is_locked(now) {
+            return Err(AccountLockedError {}.into());
+        }
+
+        let password = request.password;
+        let (mut user, matching) = run_blocking(move || {
+            let matching = user.is_matching_password(&password);
+            (user, matching)
+        })
+        .await;
+        if !matching {
+            self.user_repository
+                .register_failed_login(user_id, self.lockout_policy, now)
+                .await?;
+            return Err(InvalidCurrentPasswordError {}.into());
+        }
+
+        user.delete(now);
+        // Only the deletion is written, so a role or password change made
+        // while hashing is kept.
+        if !self.user_repository.mark_deleted(user.clone()).await? {
+            return Err(UserNotFoundError {}.into());
+        }
+        // A restored account starts without the sessions it had.
+        self.refresh_token_repository
+            .revoke_all_for_user(&user_id)
+            .await?;
+        if let Err(error) = self.event_publisher.publish(user.pull_events()).await {
+            log::error!("could not publish user events: {}", error);
+        }
+
+        Ok(MessageResponse::new(&format!(
+            "Account deleted, it can be restored within the next {} days",
+            ACCOUNT_RESTORE_WINDOW / SECONDS_PER_DAY
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            account_delete_service::AccountDeleteService, application_error::ApplicationError,
+            dtos::AccountDeleteRequest,
+        },
+        domain::{
+            common::time,
+            entities::{refresh_token::RefreshToken, user::User},
+            repositories::{
+                refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+            },
+            value_objects::{
+                email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password, role::Role,
+            },
+        },
+        infrastructure::{
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+        test_support::StaleUserRepository,
+    };
+
+    fn create_service(users: Arc<dyn UserRepository>) -> AccountDeleteService {
+        AccountDeleteService::new(
+            users,
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+        )
+    }
+
+    async fn create_user(repo: &InMemoryUserRepository) -> User {
+        let user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        let _ = repo.save(user.clone()).await;
+        user
+    }
+
+    fn request(user: &User, password: &str) -> AccountDeleteRequest {
+        AccountDeleteRequest {
+            user_id: user.id(),
+            password: password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn soft_deletes_the_account() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = create_user(&repo).await;
+
+        let response = create_service(repo.clone())
+            .delete(request(&user, "TestPass123_"))
+            .await;
+
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        assert!(response.is_ok());
+        assert_eq!(repo.find_by_email(email.clone()).await, Ok(None));
+        assert!(repo
+            .find_deleted_by_email(email)
+            .await
+            .unwrap()
+            .is_some_and(|u| u.is_deleted()));
+    }
+
+    #[tokio::test]
+    async fn tells_how_long_the_account_can_be_restored() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = create_user(&repo).await;
+
+        let response = create_service(repo)
+            .delete(request(&user, "TestPass123_"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.message,
+            "Account deleted, it can be restored within the next 30 days"
+        );
+    }
+
+    #[tokio::test]
+    async fn signs_out_every_session() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let sessions = Arc::new(InMemoryRefreshTokenRepository::new());
+        let user = create_user(&repo).await;
+        let (session, session_plaintext) =
+            RefreshToken::issue(Id::from(user.id()).unwrap(), 60, time::now());
+        let _ = sessions.save(session).await;
+        let service = AccountDeleteService::new(
+            repo.clone(),
+            sessions.clone(),
+            Arc::new(InMemoryEventBus::new()),
+        );
+
+        let response = service.delete(request(&user, "TestPass123_")).await;
+
+        let session = sessions
+            .find_by_hash(&RefreshToken::hash(&session_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.is_ok());
+        assert!(session.is_revoked());
+    }
+
+    #[tokio::test]
+    async fn keeps_a_role_change_made_while_hashing() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = create_user(&repo).await;
+        let service = create_service(Arc::new(StaleUserRepository {
+            users: repo.clone(),
+            stale: user.clone(),
+        }));
+        repo.assign_role(Id::from(user.id()).unwrap(), Role::Admin)
+            .await
+            .unwrap();
+
+        let response = service.delete(request(&user, "TestPass123_")).await;
+
+        let email = Email::new(user.email()).unwrap();
+        let stored = repo.find_deleted_by_email(email).await.unwrap().unwrap();
+        assert!(response.is_ok());
+        assert!(stored.is_deleted());
+        assert_eq!(stored.role(), Role::Admin);
+    }
+
+    #[tokio::test]
+    async fn requires_the_current_password() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = create_user(&repo).await;
+
+        let response = create_service(repo.clone())
+            .delete(request(&user, "WrongPass123_"))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCurrentPassword(_)
+        ));
+        assert!(repo
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn locks_the_account_after_too_many_wrong_passwords() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = create_user(&repo).await;
+        let service = create_service(repo.clone()).with_lockout_policy(LockoutPolicy {
+            max_attempts: 2,
+            ..LockoutPolicy::default()
+        });
+
+        for _ in 0..2 {
+            let _ = service.delete(request(&user, "WrongPass123_")).await;
+        }
+        let response = service.delete(request(&user, "TestPass123_")).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::AccountLocked(_)
+        ));
+        assert!(repo
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
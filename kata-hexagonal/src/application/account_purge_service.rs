@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time, entities::user::ACCOUNT_RESTORE_WINDOW,
+    repositories::user_repository::UserRepository,
+};
+
+use super::{application_error::ApplicationError, dtos::AccountPurgeResponse};
+
+/// Permanently removes accounts whose restore window has ended.
+pub struct AccountPurgeService {
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl AccountPurgeService {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        AccountPurgeService { user_repository }
+    }
+
+    pub async fn purge(&self) -> Result<AccountPurgeResponse, ApplicationError> {
+        let cutoff = time::now().saturating_sub(ACCOUNT_RESTORE_WINDOW);
+        let purged = self.user_repository.purge_deleted_before(cutoff).await?;
+        if purged > 0 {
+            log::info!("purged {} deleted accounts", purged);
+        }
+
+        Ok(AccountPurgeResponse { purged })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::account_purge_service::AccountPurgeService,
+        domain::{
+            common::time,
+            entities::user::{User, ACCOUNT_RESTORE_WINDOW},
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::in_memory_user_repository::InMemoryUserRepository,
+    };
+
+    fn deleted_user(email: &str, deleted_at: u64) -> User {
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new(email.to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.delete(deleted_at);
+        user
+    }
+
+    #[tokio::test]
+    async fn purges_accounts_past_the_restore_window() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let now = time::now();
+        let _ = repo
+            .save(deleted_user(
+                "expired@example.com",
+                now - ACCOUNT_RESTORE_WINDOW - 1,
+            ))
+            .await;
+        let _ = repo.save(deleted_user("recent@example.com", now)).await;
+
+        let response = AccountPurgeService::new(repo.clone())
+            .purge()
+            .await
+            .unwrap();
+
+        assert_eq!(response.purged, 1);
+        assert!(repo
+            .find_deleted_by_email(Email::new("recent@example.com".to_string()).unwrap())
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
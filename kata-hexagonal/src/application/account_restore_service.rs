@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time, repositories::user_repository::UserRepository, value_objects::email::Email,
+};
+
+use super::{
+    application_error::ApplicationError,
+    blocking::run_blocking,
+    dtos::{AccountRestoreRequest, MessageResponse},
+    user_login_service::{dummy_password, InvalidCredentialsError},
+};
+
+/// Brings back a soft-deleted account within the restore window. Fails like a
+/// bad login otherwise, so it reveals nothing about deleted accounts.
+pub struct AccountRestoreService {
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl AccountRestoreService {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        AccountRestoreService { user_repository }
+    }
+
+    pub async fn restore(
+        &self,
+        request: AccountRestoreRequest,
+    ) -> Result<MessageResponse, ApplicationError> {
+        let password = request.password;
+        let Some(user) = self
+            .user_repository
+            .find_deleted_by_email(Email::new(request.email)?)
+            .await?
+            .filter(|user| user.can_be_restored(time::now()))
+        else {
+            // Spend the same hashing time as for a restorable account.
+            run_blocking(move || dummy_password().verify(&password)).await;
+            return Err(InvalidCredentialsError {}.into());
+        };
+
+        let (mut user, matching) = run_blocking(move || {
+            let matching = user.is_matching_password(&password);
+            (user, matching)
+        })
+        .await;
+        if !matching {
+            return Err(InvalidCredentialsError {}.into());
+        }
+
+        user.restore();
+        self.user_repository.save(user).await?;
+
+        Ok(MessageResponse::new("Account restored"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            account_restore_service::AccountRestoreService, application_error::ApplicationError,
+            dtos::AccountRestoreRequest,
+        },
+        domain::{
+            common::time,
+            entities::user::{User, ACCOUNT_RESTORE_WINDOW},
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::in_memory_user_repository::InMemoryUserRepository,
+    };
+
+    async fn create_deleted_user(repo: &InMemoryUserRepository, deleted_at: u64) {
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.delete(deleted_at);
+        let _ = repo.save(user).await;
+    }
+
+    fn request(password: &str) -> AccountRestoreRequest {
+        AccountRestoreRequest {
+            email: "test@example.com".to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn restores_a_recently_deleted_account() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        create_deleted_user(&repo, time::now()).await;
+
+        let response = AccountRestoreService::new(repo.clone())
+            .restore(request("TestPass123_"))
+            .await;
+
+        assert!(response.is_ok());
+        assert!(repo
+            .find_by_email(Email::new("test@example.com".to_string()).unwrap())
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn requires_the_password() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        create_deleted_user(&repo, time::now()).await;
+
+        let response = AccountRestoreService::new(repo)
+            .restore(request("WrongPass123_"))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCredentials(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn refuses_once_the_window_has_passed() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        create_deleted_user(&repo, time::now() - ACCOUNT_RESTORE_WINDOW).await;
+
+        let response = AccountRestoreService::new(repo)
+            .restore(request("TestPass123_"))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCredentials(_)
+        ));
+    }
+}
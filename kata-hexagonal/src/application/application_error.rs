@@ -0,0 +1,98 @@
+use crate::domain::{
+    entities::user::{ChangePasswordError, EqualPasswordError},
+    repositories::repository_error::RepositoryError,
+    value_objects::{
+        email::EmailError, id::InvalidIdError, password::PasswordError, role::InvalidRoleError,
+    },
+};
+
+use super::{
+    audit_log_query_service::InvalidAuditQueryError,
+    authorizer::ForbiddenError,
+    email_verify_service::InvalidVerificationTokenError,
+    password_reset_service::InvalidResetTokenError,
+    ports::{audit_log::AuditLogError, token_issuer::TokenError},
+    token_refresh_service::{InvalidRefreshTokenError, RefreshTokenReuseError},
+    two_factor_confirm_service::TwoFactorNotEnrolledError,
+    two_factor_enroll_service::TwoFactorAlreadyEnabledError,
+    user_change_password_service::{ForbiddenPasswordChangeError, InvalidCurrentPasswordError},
+    user_find_service::UserNotFoundError,
+    user_list_service::InvalidUserQueryError,
+    user_login_service::{
+        AccountLockedError, InvalidCredentialsError, InvalidLoginChallengeError,
+        InvalidTwoFactorCodeError, UnverifiedEmailError,
+    },
+    user_register_service::{AccountPendingDeletionError, ExistingUserError},
+    user_role_assign_service::LastAdminError,
+};
+
+/// Every way a use case can fail. Adapters decide how each one is presented,
+/// the services only say what went wrong.
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ApplicationError {
+    #[error(transparent)]
+    InvalidEmail(#[from] EmailError),
+    #[error(transparent)]
+    InvalidPassword(#[from] PasswordError),
+    #[error(transparent)]
+    InvalidId(#[from] InvalidIdError),
+    #[error(transparent)]
+    InvalidRole(#[from] InvalidRoleError),
+    #[error(transparent)]
+    EqualPassword(#[from] EqualPasswordError),
+    #[error(transparent)]
+    ExistingUser(#[from] ExistingUserError),
+    #[error(transparent)]
+    AccountPendingDeletion(#[from] AccountPendingDeletionError),
+    #[error(transparent)]
+    UserNotFound(#[from] UserNotFoundError),
+    #[error(transparent)]
+    InvalidCredentials(#[from] InvalidCredentialsError),
+    #[error(transparent)]
+    InvalidCurrentPassword(#[from] InvalidCurrentPasswordError),
+    #[error(transparent)]
+    UnverifiedEmail(#[from] UnverifiedEmailError),
+    #[error(transparent)]
+    AccountLocked(#[from] AccountLockedError),
+    #[error(transparent)]
+    InvalidLoginChallenge(#[from] InvalidLoginChallengeError),
+    #[error(transparent)]
+    InvalidTwoFactorCode(#[from] InvalidTwoFactorCodeError),
+    #[error(transparent)]
+    TwoFactorAlreadyEnabled(#[from] TwoFactorAlreadyEnabledError),
+    #[error(transparent)]
+    TwoFactorNotEnrolled(#[from] TwoFactorNotEnrolledError),
+    #[error(transparent)]
+    LastAdmin(#[from] LastAdminError),
+    #[error(transparent)]
+    ForbiddenPasswordChange(#[from] ForbiddenPasswordChangeError),
+    #[error(transparent)]
+    Forbidden(#[from] ForbiddenError),
+    #[error(transparent)]
+    InvalidUserQuery(#[from] InvalidUserQueryError),
+    #[error(transparent)]
+    InvalidAuditQuery(#[from] InvalidAuditQueryError),
+    #[error(transparent)]
+    InvalidRefreshToken(#[from] InvalidRefreshTokenError),
+    #[error(transparent)]
+    RefreshTokenReuse(#[from] RefreshTokenReuseError),
+    #[error(transparent)]
+    InvalidResetToken(#[from] InvalidResetTokenError),
+    #[error(transparent)]
+    InvalidVerificationToken(#[from] InvalidVerificationTokenError),
+    #[error(transparent)]
+    Token(#[from] TokenError),
+    #[error(transparent)]
+    AuditLog(#[from] AuditLogError),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+impl From<ChangePasswordError> for ApplicationError {
+    fn from(error: ChangePasswordError) -> Self {
+        match error {
+            ChangePasswordError::EqualPassword(e) => e.into(),
+            ChangePasswordError::InvalidPassword(e) => e.into(),
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    repositories::user_repository::UserRepository, value_objects::role::Permission,
+};
+
+use super::{
+    application_error::ApplicationError,
+    authorizer::Authorizer,
+    dtos::{AuditLogQueryRequest, AuditLogResponse},
+    ports::audit_log::{AuditLog, AuditQuery},
+};
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid audit log query: {0}")]
+pub struct InvalidAuditQueryError(pub String);
+
+pub struct AuditLogQueryService {
+    audit_log: Arc<dyn AuditLog>,
+    authorizer: Authorizer,
+}
+
+impl AuditLogQueryService {
+    pub fn new(user_repository: Arc<dyn UserRepository>, audit_log: Arc<dyn AuditLog>) -> Self {
+        AuditLogQueryService {
+            audit_log,
+            authorizer: Authorizer::new(user_repository),
+        }
+    }
+
+    pub async fn query(
+        &self,
+        request: AuditLogQueryRequest,
+    ) -> Result<AuditLogResponse, ApplicationError> {
+        self.authorizer
+            .authorize(&request.requester, Permission::ViewAuditLog)
+            .await?;
+
+        let entries = self.audit_log.query(to_query(request)?).await?;
+
+        Ok(AuditLogResponse {
+            entries: entries.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+fn to_query(request: AuditLogQueryRequest) -> Result<AuditQuery, InvalidAuditQueryError> {
+    let event_type = request
+        .event_type
+        .map(|event_type| event_type.parse())
+        .transpose()
+        .map_err(|_| {
+            InvalidAuditQueryError(
+                "event_type must be registered, login_succeeded, login_failed or password_changed"
+                    .to_string(),
+            )
+        })?;
+    if let (Some(from), Some(until)) = (request.from, request.until) {
+        if from > until {
+            return Err(InvalidAuditQueryError(
+                "from must not be after until".to_string(),
+            ));
+        }
+    }
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(InvalidAuditQueryError(format!(
+            "limit must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+
+    Ok(AuditQuery {
+        user_id: request.user_id.filter(|user_id| !user_id.is_empty()),
+        event_type,
+        from: request.from,
+        until: request.until,
+        limit,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            audit_log_query_service::AuditLogQueryService,
+            authorizer::Requester,
+            dtos::AuditLogQueryRequest,
+            ports::audit_log::{AuditEntry, AuditEventType, AuditLog},
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::{
+            in_memory_audit_log::InMemoryAuditLog,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    async fn create_service(role: Role) -> (AuditLogQueryService, User) {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.assign_role(role);
+        let _ = repo.save(user.clone()).await;
+
+        let audit_log = Arc::new(InMemoryAuditLog::new());
+        for (event_type, occurred_at) in [
+            (AuditEventType::Registered, 10),
+            (AuditEventType::LoginFailed, 20),
+            (AuditEventType::LoginSucceeded, 30),
+        ] {
+            let _ = audit_log
+                .record(
+                    AuditEntry::new(event_type, user.email(), None, occurred_at)
+                        .for_user(user.id()),
+                )
+                .await;
+        }
+
+        (AuditLogQueryService::new(repo, audit_log), user)
+    }
+
+    #[tokio::test]
+    async fn returns_matching_entries_to_admins() {
+        let (service, admin) = create_service(Role::Admin).await;
+
+        let response = service
+            .query(AuditLogQueryRequest {
+                user_id: Some(admin.id()),
+                from: Some(20),
+                ..AuditLogQueryRequest::new(Requester::User(admin.id()))
+            })
+            .await
+            .unwrap();
+
+        let event_types: Vec<&str> = response
+            .entries
+            .iter()
+            .map(|entry| entry.event_type.as_str())
+            .collect();
+        assert_eq!(event_types, vec!["login_succeeded", "login_failed"]);
+    }
+
+    #[tokio::test]
+    async fn filters_by_event_type() {
+        let (service, admin) = create_service(Role::Admin).await;
+
+        let response = service
+            .query(AuditLogQueryRequest {
+                event_type: Some("login_failed".to_string()),
+                ..AuditLogQueryRequest::new(Requester::User(admin.id()))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.entries.len(), 1);
+        assert_eq!(response.entries[0].occurred_at, 20);
+    }
+
+    #[tokio::test]
+    async fn forbids_other_users() {
+        let (service, user) = create_service(Role::User).await;
+
+        let response = service
+            .query(AuditLogQueryRequest::new(Requester::User(user.id())))
+            .await;
+
+        assert!(matches!(response, Err(ApplicationError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_queries() {
+        let (service, admin) = create_service(Role::Admin).await;
+        let requester = || AuditLogQueryRequest::new(Requester::User(admin.id()));
+
+        for request in [
+            AuditLogQueryRequest {
+                event_type: Some("logout".to_string()),
+                ..requester()
+            },
+            AuditLogQueryRequest {
+                from: Some(30),
+                until: Some(10),
+                ..requester()
+            },
+            AuditLogQueryRequest {
+                limit: Some(0),
+                ..requester()
+            },
+        ] {
+            let response = service.query(request).await;
+
+            assert!(matches!(
+                response,
+                Err(ApplicationError::InvalidAuditQuery(_))
+            ));
+        }
+    }
+}
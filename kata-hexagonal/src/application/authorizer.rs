@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    repositories::user_repository::UserRepository,
+    value_objects::{id::Id, role::Permission},
+};
+
+use super::application_error::ApplicationError;
+
+/// Who a use case runs on behalf of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Requester {
+    /// Operator tooling such as the admin CLI, which has direct access to the
+    /// storage anyway.
+    System,
+    User(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Not allowed to {0}")]
+pub struct ForbiddenError(pub Permission);
+
+/// Checks permissions against the requester's current role, so role changes
+/// apply without waiting for tokens to expire.
+pub struct Authorizer {
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl Authorizer {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        Authorizer { user_repository }
+    }
+
+    pub async fn authorize(
+        &self,
+        requester: &Requester,
+        permission: Permission,
+    ) -> Result<(), ApplicationError> {
+        let user_id = match requester {
+            Requester::System => return Ok(()),
+            Requester::User(user_id) => user_id,
+        };
+
+        let user = match Id::from(user_id.clone()) {
+            Ok(id) => self.user_repository.find_by_id(id).await?,
+            Err(_) => None,
+        };
+        match user {
+            Some(user) if user.can(permission) => Ok(()),
+            _ => Err(ForbiddenError(permission).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            authorizer::{Authorizer, ForbiddenError, Requester},
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{
+                email::Email,
+                id::Id,
+                password::Password,
+                role::{Permission, Role},
+            },
+        },
+        infrastructure::in_memory_user_repository::InMemoryUserRepository,
+    };
+
+    async fn authorize(role: Role) -> Result<(), ApplicationError> {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.assign_role(role);
+        let _ = repo.save(user.clone()).await;
+
+        Authorizer::new(repo)
+            .authorize(&Requester::User(user.id()), Permission::ListUsers)
+            .await
+    }
+
+    #[tokio::test]
+    async fn allows_a_role_holding_the_permission() {
+        assert_eq!(authorize(Role::Admin).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn forbids_a_role_without_the_permission() {
+        assert_eq!(
+            authorize(Role::User).await,
+            Err(ForbiddenError(Permission::ListUsers).into())
+        );
+    }
+
+    #[tokio::test]
+    async fn forbids_unknown_requesters_and_trusts_the_system() {
+        let authorizer = Authorizer::new(Arc::new(InMemoryUserRepository::new()));
+
+        assert!(authorizer
+            .authorize(
+                &Requester::User(Id::generate_unique_identifier().to_string()),
+                Permission::ManageRoles
+            )
+            .await
+            .is_err());
+        assert!(authorizer
+            .authorize(&Requester::System, Permission::ManageRoles)
+            .await
+            .is_ok());
+    }
+}
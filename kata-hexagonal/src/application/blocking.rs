@@ -0,0 +1,29 @@
+/// Runs CPU-heavy work, such as Argon2 hashing, on tokio's blocking threads
+/// so it does not hold up the async workers serving other requests.
+pub async fn run_blocking<T, F>(work: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    match tokio::task::spawn_blocking(work).await {
+        Ok(result) => result,
+        // Surface a panic in `work` as if it had run inline.
+        Err(error) => std::panic::resume_unwind(error.into_panic()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::run_blocking;
+
+    #[tokio::test]
+    async fn returns_the_result_of_the_work() {
+        assert_eq!(run_blocking(|| 6 * 7).await, 42);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "hashing failed")]
+    async fn propagates_a_panic_in_the_work() {
+        run_blocking(|| panic!("hashing failed")).await
+    }
+}
@@ -1,25 +1,28 @@
-use std::fmt::Display;
+use serde::Serialize;
+use utoipa::ToSchema;
 
-use crate::domain::entities::user::UserDto;
+use crate::{
+    application::{
+        authorizer::Requester,
+        ports::{audit_log::AuditEntry, token_issuer::AccessToken},
+    },
+    domain::entities::user::UserDto,
+};
 
 #[derive(Clone)]
 pub struct UserRegisterRequest {
     pub email: String,
     pub password: String,
+    /// Address the request came from, for the audit log.
+    pub client_ip: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserRegisterResponse {
     pub id: String,
     pub email: String,
 }
 
-impl Display for UserRegisterResponse {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "id: {}, email: {}", self.id, self.email)
-    }
-}
-
 impl From<UserDto> for UserRegisterResponse {
     fn from(user: UserDto) -> Self {
         UserRegisterResponse {
@@ -33,25 +36,339 @@ impl From<UserDto> for UserRegisterResponse {
 pub struct UserLoginRequest {
     pub email: String,
     pub password: String,
+    /// Address the request came from, for the audit log.
+    pub client_ip: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserLoginResponse {
     pub id: String,
     pub email: String,
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: u64,
+    pub refresh_token: String,
 }
 
-impl Display for UserLoginResponse {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "id: {}, email: {}", self.id, self.email)
+impl UserLoginResponse {
+    pub fn new(user: UserDto, token: AccessToken, refresh_token: String) -> Self {
+        UserLoginResponse {
+            id: user.id,
+            email: user.email,
+            access_token: token.token,
+            token_type: "Bearer".to_string(),
+            expires_in: token.expires_in,
+            refresh_token,
+        }
     }
 }
 
-impl From<UserDto> for UserLoginResponse {
+/// Issued instead of tokens when the user has two-factor authentication
+/// enabled; exchanged for them at `POST /login/2fa`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginChallengeResponse {
+    pub second_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_in: u64,
+}
+
+impl LoginChallengeResponse {
+    pub fn new(challenge_token: String, expires_in: u64) -> Self {
+        LoginChallengeResponse {
+            second_factor_required: true,
+            challenge_token,
+            expires_in,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum UserLoginOutcome {
+    Authenticated(UserLoginResponse),
+    SecondFactorRequired(LoginChallengeResponse),
+}
+
+#[derive(Clone)]
+pub struct TwoFactorLoginRequest {
+    pub challenge_token: String,
+    /// A TOTP code or one of the recovery codes.
+    pub code: String,
+    /// Address the request came from, for the audit log.
+    pub client_ip: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct TwoFactorEnrollRequest {
+    pub user_id: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorEnrollResponse {
+    /// Base32, for authenticators that cannot scan the URI.
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Clone)]
+pub struct TwoFactorConfirmRequest {
+    pub user_id: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorConfirmResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct UserFindRequest {
+    pub requester: Requester,
+    pub id: String,
+}
+
+#[derive(Clone)]
+pub struct UserFindByEmailRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct UserFindResponse {
+    pub id: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub created_at: u64,
+    pub role: String,
+}
+
+impl From<UserDto> for UserFindResponse {
     fn from(user: UserDto) -> Self {
-        UserLoginResponse {
+        UserFindResponse {
+            id: user.id,
+            email: user.email,
+            email_verified: user.email_verified,
+            created_at: user.created_at,
+            role: user.role,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserListResponse {
+    pub users: Vec<UserFindResponse>,
+}
+
+/// Filters and paging for the user listing. Every field but the requester
+/// is optional.
+#[derive(Clone)]
+pub struct UserSearchRequest {
+    pub requester: Requester,
+    pub email_contains: Option<String>,
+    pub email_domain: Option<String>,
+    /// `verified`, `unverified` or `locked`.
+    pub status: Option<String>,
+    /// `created_at` (the default) or `email`.
+    pub sort: Option<String>,
+    /// `asc` (the default) or `desc`.
+    pub order: Option<String>,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl UserSearchRequest {
+    /// The first page with the default sort and size.
+    pub fn new(requester: Requester) -> Self {
+        UserSearchRequest {
+            requester,
+            email_contains: None,
+            email_domain: None,
+            status: None,
+            sort: None,
+            order: None,
+            cursor: None,
+            limit: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserPageResponse {
+    pub users: Vec<UserFindResponse>,
+    /// Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct UserDeleteRequest {
+    pub requester: Requester,
+    pub id: String,
+}
+
+#[derive(Clone)]
+pub struct UserRoleAssignRequest {
+    pub requester: Requester,
+    pub user_id: String,
+    pub role: String,
+}
+
+#[derive(Clone)]
+pub struct TokenRefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenRefreshResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: u64,
+    pub refresh_token: String,
+}
+
+impl TokenRefreshResponse {
+    pub fn new(token: AccessToken, refresh_token: String) -> Self {
+        TokenRefreshResponse {
+            access_token: token.token,
+            token_type: "Bearer".to_string(),
+            expires_in: token.expires_in,
+            refresh_token,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct UserChangePasswordRequest {
+    pub requester_id: String,
+    pub user_id: String,
+    pub current_password: String,
+    pub new_password: String,
+    /// Address the request came from, for the audit log.
+    pub client_ip: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserChangePasswordResponse {
+    pub id: String,
+    pub email: String,
+}
+
+impl From<UserDto> for UserChangePasswordResponse {
+    fn from(user: UserDto) -> Self {
+        UserChangePasswordResponse {
             id: user.id,
             email: user.email,
         }
     }
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MessageResponse {
+    pub message: String,
+}
+
+impl MessageResponse {
+    pub fn new(message: &str) -> Self {
+        MessageResponse {
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PasswordForgotRequest {
+    pub email: String,
+}
+
+#[derive(Clone)]
+pub struct PasswordForceResetRequest {
+    pub id: String,
+}
+
+#[derive(Clone)]
+pub struct PasswordResetRequest {
+    pub token: String,
+    pub new_password: String,
+    /// Address the request came from, for the audit log.
+    pub client_ip: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct EmailVerifyRequest {
+    pub token: String,
+}
+
+#[derive(Clone)]
+pub struct EmailVerificationResendRequest {
+    pub email: String,
+}
+
+#[derive(Clone)]
+pub struct AccountDeleteRequest {
+    pub user_id: String,
+    pub password: String,
+}
+
+#[derive(Clone)]
+pub struct AccountRestoreRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountPurgeResponse {
+    pub purged: u64,
+}
+
+/// Filters for reading the audit log. Times are seconds since the Unix epoch,
+/// `from` inclusive and `until` exclusive.
+#[derive(Clone)]
+pub struct AuditLogQueryRequest {
+    pub requester: Requester,
+    pub user_id: Option<String>,
+    /// `registered`, `login_succeeded`, `login_failed` or `password_changed`.
+    pub event_type: Option<String>,
+    pub from: Option<u64>,
+    pub until: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+impl AuditLogQueryRequest {
+    /// The latest entries of any kind.
+    pub fn new(requester: Requester) -> Self {
+        AuditLogQueryRequest {
+            requester,
+            user_id: None,
+            event_type: None,
+            from: None,
+            until: None,
+            limit: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct AuditEntryResponse {
+    pub event_type: String,
+    pub user_id: Option<String>,
+    pub email: String,
+    pub ip: Option<String>,
+    pub occurred_at: u64,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(entry: AuditEntry) -> Self {
+        AuditEntryResponse {
+            event_type: entry.event_type.to_string(),
+            user_id: entry.user_id,
+            email: entry.email,
+            ip: entry.ip,
+            occurred_at: entry.occurred_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditEntryResponse>,
+}
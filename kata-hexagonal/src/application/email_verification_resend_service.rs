@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    repositories::user_repository::UserRepository,
+    value_objects::{email::Email, id::Id},
+};
+
+use super::{
+    application_error::ApplicationError,
+    dtos::{EmailVerificationResendRequest, MessageResponse},
+    email_verification_sender::EmailVerificationSender,
+};
+
+pub const EMAIL_VERIFICATION_RESEND_MESSAGE: &str =
+    "If the email is registered and not yet verified, a new verification link has been sent";
+
+pub struct EmailVerificationResendService {
+    user_repository: Arc<dyn UserRepository>,
+    verification_sender: EmailVerificationSender,
+}
+
+impl EmailVerificationResendService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        verification_sender: EmailVerificationSender,
+    ) -> Self {
+        EmailVerificationResendService {
+            user_repository,
+            verification_sender,
+        }
+    }
+
+    /// Sends a fresh verification link to an unverified user. The response is
+    /// identical whether or not anything was sent, including when throttled,
+    /// so callers cannot probe for accounts.
+    pub async fn resend(
+        &self,
+        request: EmailVerificationResendRequest,
+    ) -> Result<MessageResponse, ApplicationError> {
+        let email = Email::new(request.email)?;
+
+        if let Some(user) = self.user_repository.find_by_email(email).await? {
+            if user.is_email_verified() {
+                return Ok(MessageResponse::new(EMAIL_VERIFICATION_RESEND_MESSAGE));
+            }
+
+            if self
+                .verification_sender
+                .is_throttled(&Id::from(user.id())?, time::now())
+                .await?
+            {
+                log::info!("verification email to {} throttled", user.id());
+            } else {
+                self.verification_sender.send(&user).await?;
+            }
+        }
+
+        Ok(MessageResponse::new(EMAIL_VERIFICATION_RESEND_MESSAGE))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            dtos::EmailVerificationResendRequest,
+            email_verification_resend_service::{
+                EmailVerificationResendService, EMAIL_VERIFICATION_RESEND_MESSAGE,
+            },
+            email_verification_sender::EmailVerificationSender,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            in_memory_email_verification_token_repository::InMemoryEmailVerificationTokenRepository,
+            in_memory_mailer::InMemoryMailer, in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    async fn create_service(
+        verified: bool,
+    ) -> (EmailVerificationResendService, Arc<InMemoryMailer>) {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let mailer = Arc::new(InMemoryMailer::new());
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::from_hash("hash".to_string()),
+        );
+        if verified {
+            user.verify_email();
+        }
+        let _ = users.save(user).await;
+
+        let sender = EmailVerificationSender::new(
+            Arc::new(InMemoryEmailVerificationTokenRepository::new()),
+            mailer.clone(),
+            "http://localhost/verify".to_string(),
+        );
+        (EmailVerificationResendService::new(users, sender), mailer)
+    }
+
+    fn request(email: &str) -> EmailVerificationResendRequest {
+        EmailVerificationResendRequest {
+            email: email.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn resends_a_verification_link() {
+        let (service, mailer) = create_service(false).await;
+
+        let response = service.resend(request("test@example.com")).await;
+
+        let sent = mailer.sent();
+        assert_eq!(response.unwrap().message, EMAIL_VERIFICATION_RESEND_MESSAGE);
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].body.contains("http://localhost/verify?token="));
+    }
+
+    #[tokio::test]
+    async fn throttles_repeated_resends() {
+        let (service, mailer) = create_service(false).await;
+
+        let _ = service.resend(request("test@example.com")).await;
+        let response = service.resend(request("test@example.com")).await;
+
+        assert_eq!(response.unwrap().message, EMAIL_VERIFICATION_RESEND_MESSAGE);
+        assert_eq!(mailer.sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_send_to_verified_or_unknown_users() {
+        let (service, mailer) = create_service(true).await;
+
+        let verified = service.resend(request("test@example.com")).await;
+        let unknown = service.resend(request("unknown@example.com")).await;
+
+        assert!(verified.is_ok());
+        assert!(unknown.is_ok());
+        assert!(mailer.sent().is_empty());
+    }
+}
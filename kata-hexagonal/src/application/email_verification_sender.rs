@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    entities::{
+        email_verification_token::{EmailVerificationToken, EMAIL_VERIFICATION_TOKEN_TIME_TO_LIVE},
+        user::User,
+    },
+    repositories::{
+        email_verification_token_repository::EmailVerificationTokenRepository,
+        repository_error::RepositoryError,
+    },
+    value_objects::id::Id,
+};
+
+use super::ports::mailer::{EmailMessage, Mailer};
+
+/// Minimum number of seconds between two verification emails to the same user.
+pub const EMAIL_VERIFICATION_RESEND_INTERVAL: u64 = 60;
+
+/// Issues verification tokens and emails them. Shared by registration and
+/// the resend use case.
+pub struct EmailVerificationSender {
+    token_repository: Arc<dyn EmailVerificationTokenRepository>,
+    mailer: Arc<dyn Mailer>,
+    verify_url: String,
+}
+
+impl EmailVerificationSender {
+    pub fn new(
+        token_repository: Arc<dyn EmailVerificationTokenRepository>,
+        mailer: Arc<dyn Mailer>,
+        verify_url: String,
+    ) -> Self {
+        EmailVerificationSender {
+            token_repository,
+            mailer,
+            verify_url,
+        }
+    }
+
+    /// Stores a new token for the user and emails the link. A delivery
+    /// failure is logged rather than returned: the user can ask for a resend.
+    pub async fn send(&self, user: &User) -> Result<(), RepositoryError> {
+        let user_id =
+            Id::from(user.id()).map_err(|e| RepositoryError::CorruptData(e.to_string()))?;
+        let (token, plaintext) = EmailVerificationToken::issue(
+            user_id,
+            EMAIL_VERIFICATION_TOKEN_TIME_TO_LIVE,
+            time::now(),
+        );
+        self.token_repository.save(token).await?;
+
+        let message = EmailMessage {
+            to: user.email(),
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Follow this link to verify your email address:\n\n{}?token={}\n\nThe link expires in 24 hours.",
+                self.verify_url, plaintext
+            ),
+        };
+        if let Err(error) = self.mailer.send(message).await {
+            log::error!("could not send verification email: {}", error);
+        }
+
+        Ok(())
+    }
+
+    /// Returns true when a verification email was sent to the user too recently.
+    pub async fn is_throttled(&self, user_id: &Id, now: u64) -> Result<bool, RepositoryError> {
+        let latest = self.token_repository.find_latest_for_user(user_id).await?;
+
+        Ok(latest.is_some_and(|t| now < t.issued_at() + EMAIL_VERIFICATION_RESEND_INTERVAL))
+    }
+}
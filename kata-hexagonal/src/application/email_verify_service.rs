@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    entities::email_verification_token::EmailVerificationToken,
+    repositories::{
+        email_verification_token_repository::EmailVerificationTokenRepository,
+        user_repository::UserRepository,
+    },
+};
+
+use super::{
+    application_error::ApplicationError,
+    dtos::{EmailVerifyRequest, MessageResponse},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid or expired verification token")]
+pub struct InvalidVerificationTokenError {}
+
+pub struct EmailVerifyService {
+    user_repository: Arc<dyn UserRepository>,
+    token_repository: Arc<dyn EmailVerificationTokenRepository>,
+}
+
+impl EmailVerifyService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        token_repository: Arc<dyn EmailVerificationTokenRepository>,
+    ) -> Self {
+        EmailVerifyService {
+            user_repository,
+            token_repository,
+        }
+    }
+
+    pub async fn verify(
+        &self,
+        request: EmailVerifyRequest,
+    ) -> Result<MessageResponse, ApplicationError> {
+        let token = self
+            .token_repository
+            .find_by_hash(&EmailVerificationToken::hash(&request.token))
+            .await?
+            .ok_or(InvalidVerificationTokenError {})?;
+
+        if token.is_used()
+            || token.is_expired(time::now())
+            || !self.token_repository.mark_as_used(&token).await?
+        {
+            return Err(InvalidVerificationTokenError {}.into());
+        }
+
+        let mut user = self
+            .user_repository
+            .find_by_id(token.user_id().clone())
+            .await?
+            .ok_or(InvalidVerificationTokenError {})?;
+
+        user.verify_email();
+        self.user_repository.save(user).await?;
+        self.token_repository
+            .invalidate_for_user(token.user_id())
+            .await?;
+
+        Ok(MessageResponse::new("Email address verified"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, dtos::EmailVerifyRequest,
+            email_verify_service::EmailVerifyService,
+        },
+        domain::{
+            common::time,
+            entities::{email_verification_token::EmailVerificationToken, user::User},
+            repositories::{
+                email_verification_token_repository::EmailVerificationTokenRepository,
+                user_repository::UserRepository,
+            },
+            value_objects::id::Id,
+        },
+        infrastructure::{
+            in_memory_email_verification_token_repository::InMemoryEmailVerificationTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+        test_support::create_stored_user,
+    };
+
+    #[tokio::test]
+    async fn verifies_the_email_of_the_token_owner() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryEmailVerificationTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = EmailVerifyService::new(users.clone(), tokens.clone());
+        let token = issue_token(&tokens, &user, 60).await;
+
+        let response = service.verify(EmailVerifyRequest { token }).await;
+
+        assert!(response.is_ok());
+        assert!(is_verified(&users, &user).await);
+    }
+
+    #[tokio::test]
+    async fn a_token_can_only_be_used_once() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryEmailVerificationTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = EmailVerifyService::new(users, tokens.clone());
+        let token = issue_token(&tokens, &user, 60).await;
+
+        let _ = service
+            .verify(EmailVerifyRequest {
+                token: token.clone(),
+            })
+            .await;
+        let response = service.verify(EmailVerifyRequest { token }).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidVerificationToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_expired_token() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryEmailVerificationTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = EmailVerifyService::new(users.clone(), tokens.clone());
+        let token = issue_token(&tokens, &user, 0).await;
+
+        let response = service.verify(EmailVerifyRequest { token }).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidVerificationToken(_)
+        ));
+        assert!(!is_verified(&users, &user).await);
+    }
+
+    async fn issue_token(
+        tokens: &InMemoryEmailVerificationTokenRepository,
+        user: &User,
+        time_to_live: u64,
+    ) -> String {
+        let (token, plaintext) =
+            EmailVerificationToken::issue(Id::from(user.id()).unwrap(), time_to_live, time::now());
+        tokens.save(token).await.unwrap();
+        plaintext
+    }
+
+    async fn is_verified(users: &InMemoryUserRepository, user: &User) -> bool {
+        users
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .unwrap()
+            .is_email_verified()
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use crate::domain::value_objects::lockout_policy::LockoutPolicy;
+
+/// Failing emails tracked before the least recently failing one is
+/// forgotten, and as many lockouts before the least recently locked one is.
+const MAX_TRACKED_EMAILS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Attempts {
+    failed: u32,
+    locked_until: Option<u64>,
+}
+
+/// Attempts by email, evicting the least recently inserted in amortized
+/// constant time. Inserting an email again queues it again; its earlier
+/// place is left behind and skipped on eviction.
+#[derive(Debug)]
+struct LruAttempts {
+    entries: HashMap<String, (Attempts, u64)>,
+    order: VecDeque<(String, u64)>,
+    next_stamp: u64,
+    capacity: usize,
+}
+
+impl LruAttempts {
+    fn new(capacity: usize) -> Self {
+        LruAttempts {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            next_stamp: 0,
+            capacity,
+        }
+    }
+
+    fn get(&self, email: &str) -> Option<&Attempts> {
+        self.entries.get(email).map(|(attempts, _)| attempts)
+    }
+
+    fn remove(&mut self, email: &str) -> Option<Attempts> {
+        self.entries.remove(email).map(|(attempts, _)| attempts)
+    }
+
+    fn insert(&mut self, email: &str, attempts: Attempts) {
+        if !self.entries.contains_key(email) && self.entries.len() >= self.capacity {
+            self.evict_least_recent();
+        }
+
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        self.order.push_back((email.to_string(), stamp));
+        self.entries.insert(email.to_string(), (attempts, stamp));
+
+        // Drop the places left behind once they outnumber the live ones.
+        if self.order.len() > 2 * self.capacity.max(1) {
+            let entries = &self.entries;
+            self.order
+                .retain(|(email, stamp)| entries.get(email).is_some_and(|(_, s)| s == stamp));
+        }
+    }
+
+    fn evict_least_recent(&mut self) {
+        while let Some((email, stamp)) = self.order.pop_front() {
+            if self.entries.get(&email).is_some_and(|(_, s)| *s == stamp) {
+                self.entries.remove(&email);
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Tracked {
+    failing: LruAttempts,
+    /// Kept apart so a flood of single failures cannot evict a lockout.
+    locked: LruAttempts,
+}
+
+/// Failed logins on emails without an account, locked out under the same
+/// [`LockoutPolicy`] as real accounts so a lockout does not tell them apart.
+/// Process-local, like the rate limiter.
+#[derive(Debug)]
+pub struct LoginAttempts {
+    tracked: Mutex<Tracked>,
+}
+
+impl LoginAttempts {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_TRACKED_EMAILS)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        LoginAttempts {
+            tracked: Mutex::new(Tracked {
+                failing: LruAttempts::new(capacity),
+                locked: LruAttempts::new(capacity),
+            }),
+        }
+    }
+
+    pub fn is_locked(&self, email: &str, now: u64) -> bool {
+        self.lock()
+            .locked
+            .get(email)
+            .and_then(|attempts| attempts.locked_until)
+            .is_some_and(|until| now < until)
+    }
+
+    pub fn register_failure(&self, email: &str, policy: &LockoutPolicy, now: u64) {
+        let mut tracked = self.lock();
+
+        let previous = match tracked.locked.remove(email) {
+            Some(attempts) => Some(attempts),
+            None => tracked.failing.remove(email),
+        };
+        let failed = previous
+            .map_or(0, |attempts| attempts.failed)
+            .saturating_add(1);
+
+        match policy.lockout_duration(failed) {
+            Some(duration) => tracked.locked.insert(
+                email,
+                Attempts {
+                    failed,
+                    locked_until: Some(now + duration),
+                },
+            ),
+            None => tracked.failing.insert(
+                email,
+                Attempts {
+                    failed,
+                    locked_until: None,
+                },
+            ),
+        }
+    }
+
+    // A poisoned lock only means another request panicked mid-update; the
+    // counters are still usable.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Tracked> {
+        match self.tracked.lock() {
+            Ok(lock) => lock,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl Default for LoginAttempts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::value_objects::lockout_policy::LockoutPolicy;
+
+    use super::LoginAttempts;
+
+    #[test]
+    fn locks_an_email_like_an_account() {
+        let attempts = LoginAttempts::new();
+        let policy = LockoutPolicy::default();
+
+        for _ in 0..policy.max_attempts - 1 {
+            attempts.register_failure("test@example.com", &policy, 100);
+        }
+        assert!(!attempts.is_locked("test@example.com", 100));
+
+        attempts.register_failure("test@example.com", &policy, 100);
+        assert!(attempts.is_locked("test@example.com", 159));
+        assert!(!attempts.is_locked("test@example.com", 160));
+        assert!(!attempts.is_locked("other@example.com", 100));
+    }
+
+    #[test]
+    fn forgets_the_oldest_email_when_full() {
+        let attempts = LoginAttempts::with_capacity(2);
+        let policy = LockoutPolicy {
+            max_attempts: 1,
+            ..LockoutPolicy::default()
+        };
+
+        attempts.register_failure("a@example.com", &policy, 100);
+        attempts.register_failure("b@example.com", &policy, 101);
+        attempts.register_failure("c@example.com", &policy, 102);
+
+        assert!(!attempts.is_locked("a@example.com", 102));
+        assert!(attempts.is_locked("b@example.com", 102));
+        assert!(attempts.is_locked("c@example.com", 102));
+    }
+
+    #[test]
+    fn keeps_a_lockout_when_flooded_with_other_emails() {
+        let attempts = LoginAttempts::with_capacity(100);
+        let policy = LockoutPolicy::default();
+
+        for _ in 0..policy.max_attempts {
+            attempts.register_failure("victim@example.com", &policy, 100);
+        }
+        for i in 0..10_000 {
+            attempts.register_failure(&format!("flood-{}@example.com", i), &policy, 101);
+        }
+
+        assert!(attempts.is_locked("victim@example.com", 101));
+        let tracked = attempts.lock();
+        assert_eq!(tracked.failing.entries.len(), 100);
+        assert!(tracked.failing.order.len() <= 200);
+    }
+
+    #[test]
+    fn keeps_counting_an_email_failing_repeatedly() {
+        let attempts = LoginAttempts::with_capacity(2);
+        let policy = LockoutPolicy::default();
+
+        for _ in 0..policy.max_attempts - 1 {
+            attempts.register_failure("test@example.com", &policy, 100);
+            attempts.register_failure("other@example.com", &policy, 100);
+        }
+        attempts.register_failure("test@example.com", &policy, 100);
+
+        assert!(attempts.is_locked("test@example.com", 100));
+        assert!(attempts.lock().failing.order.len() <= 4);
+    }
+}
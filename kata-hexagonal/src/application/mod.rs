@@ -1,3 +1,28 @@
+pub mod account_delete_service;
+pub mod account_purge_service;
+pub mod account_restore_service;
+pub mod application_error;
+pub mod audit_log_query_service;
+pub mod authorizer;
+pub mod blocking;
 pub mod dtos;
+pub mod email_verification_resend_service;
+pub mod email_verification_sender;
+pub mod email_verify_service;
+pub mod login_attempts;
+pub mod outbox_dispatcher;
+pub mod password_force_reset_service;
+pub mod password_forgot_service;
+pub mod password_reset_sender;
+pub mod password_reset_service;
+pub mod ports;
+pub mod token_refresh_service;
+pub mod two_factor_confirm_service;
+pub mod two_factor_enroll_service;
+pub mod user_change_password_service;
+pub mod user_delete_service;
+pub mod user_find_service;
+pub mod user_list_service;
 pub mod user_login_service;
 pub mod user_register_service;
+pub mod user_role_assign_service;
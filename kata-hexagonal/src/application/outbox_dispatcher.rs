@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use crate::domain::{common::time, repositories::outbox_repository::OutboxRepository};
+
+use super::{application_error::ApplicationError, ports::event_publisher::EventPublisher};
+
+const BATCH_SIZE: usize = 100;
+
+/// Deliveries tried before an entry is given up on and left in the outbox,
+/// so that one event that never goes through cannot hold back the rest.
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+
+/// How long dispatched entries are kept before they are pruned.
+pub const DISPATCHED_RETENTION: u64 = 7 * 24 * 60 * 60;
+
+/// Delivers the outbox to a publisher, at least once: an entry is marked as
+/// dispatched only after it was published, so a crash in between delivers it
+/// again on the next run.
+pub struct OutboxDispatcher {
+    outbox: Arc<dyn OutboxRepository>,
+    publisher: Arc<dyn EventPublisher>,
+}
+
+impl OutboxDispatcher {
+    pub fn new(outbox: Arc<dyn OutboxRepository>, publisher: Arc<dyn EventPublisher>) -> Self {
+        OutboxDispatcher { outbox, publisher }
+    }
+
+    /// Publishes pending entries in order, returning how many were delivered.
+    /// Stops at the first failure so that later events never overtake it,
+    /// until that entry runs out of attempts.
+    pub async fn dispatch_pending(&self) -> Result<usize, ApplicationError> {
+        let mut delivered = 0;
+
+        loop {
+            let entries = self
+                .outbox
+                .find_pending(BATCH_SIZE, MAX_DELIVERY_ATTEMPTS)
+                .await?;
+            let batch_size = entries.len();
+
+            for entry in entries {
+                if let Err(error) = self.publisher.publish(vec![entry.event]).await {
+                    log::warn!(
+                        "could not deliver outbox entry {} (attempt {}): {}",
+                        entry.id,
+                        entry.attempts + 1,
+                        error
+                    );
+                    self.outbox
+                        .record_failure(entry.id, error.to_string())
+                        .await?;
+                    if entry.attempts + 1 >= MAX_DELIVERY_ATTEMPTS {
+                        log::error!("gave up delivering outbox entry {}", entry.id);
+                    }
+                    return Ok(delivered);
+                }
+                self.outbox.mark_dispatched(entry.id, time::now()).await?;
+                delivered += 1;
+            }
+
+            if batch_size < BATCH_SIZE {
+                return Ok(delivered);
+            }
+        }
+    }
+
+    /// Deletes entries dispatched more than `DISPATCHED_RETENTION` ago,
+    /// returning how many.
+    pub async fn prune_dispatched(&self, now: u64) -> Result<u64, ApplicationError> {
+        let cutoff = now.saturating_sub(DISPATCHED_RETENTION);
+        Ok(self.outbox.prune_dispatched_before(cutoff).await?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use async_trait::async_trait;
+
+    use crate::{
+        application::{
+            outbox_dispatcher::{OutboxDispatcher, DISPATCHED_RETENTION, MAX_DELIVERY_ATTEMPTS},
+            ports::event_publisher::{EventPublisher, PublishError},
+        },
+        domain::{
+            entities::user::User,
+            events::user_event::UserEvent,
+            repositories::{outbox_repository::OutboxRepository, user_repository::UserRepository},
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{in_memory_event_bus::InMemoryEventBus, sqlite_user_repository::Sqlite},
+    };
+
+    /// Fails until it is told to recover.
+    struct FlakyPublisher {
+        available: AtomicBool,
+        bus: InMemoryEventBus,
+    }
+
+    #[async_trait]
+    impl EventPublisher for FlakyPublisher {
+        async fn publish(&self, events: Vec<UserEvent>) -> Result<(), PublishError> {
+            if !self.available.load(Ordering::SeqCst) {
+                return Err(PublishError("down".to_string()));
+            }
+            self.bus.publish(events).await
+        }
+    }
+
+    /// Fails every event of one user, as for an address the mailer rejects.
+    struct PoisonedPublisher {
+        poisoned_user_id: String,
+        bus: InMemoryEventBus,
+    }
+
+    #[async_trait]
+    impl EventPublisher for PoisonedPublisher {
+        async fn publish(&self, events: Vec<UserEvent>) -> Result<(), PublishError> {
+            if events.iter().any(|e| e.user_id() == self.poisoned_user_id) {
+                return Err(PublishError("rejected".to_string()));
+            }
+            self.bus.publish(events).await
+        }
+    }
+
+    async fn registered_user(repo: &Sqlite, email: &str) -> User {
+        let mut user = User::register(
+            Id::generate_unique_identifier(),
+            Email::new(email.to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.record_login(100);
+        let _ = repo.save(user.clone()).await;
+        user
+    }
+
+    #[tokio::test]
+    async fn delivers_saved_events_once_in_order() {
+        let repo = Arc::new(Sqlite::new(":memory:").await.unwrap());
+        let user = registered_user(&repo, "test@example.com").await;
+        let bus = Arc::new(InMemoryEventBus::new());
+        let dispatcher = OutboxDispatcher::new(repo.clone(), bus.clone());
+
+        assert_eq!(dispatcher.dispatch_pending().await, Ok(2));
+        assert_eq!(dispatcher.dispatch_pending().await, Ok(0));
+
+        assert_eq!(bus.published(), user.events());
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await,
+            Ok(vec![])
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_events_pending_until_they_are_delivered() {
+        let repo = Arc::new(Sqlite::new(":memory:").await.unwrap());
+        let user = registered_user(&repo, "test@example.com").await;
+        let publisher = Arc::new(FlakyPublisher {
+            available: AtomicBool::new(false),
+            bus: InMemoryEventBus::new(),
+        });
+        let dispatcher = OutboxDispatcher::new(repo.clone(), publisher.clone());
+
+        assert_eq!(dispatcher.dispatch_pending().await, Ok(0));
+        let pending = repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].attempts, 1);
+
+        publisher.available.store(true, Ordering::SeqCst);
+        assert_eq!(dispatcher.dispatch_pending().await, Ok(2));
+        assert_eq!(publisher.bus.published(), user.events());
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_an_entry_that_keeps_failing() {
+        let repo = Arc::new(Sqlite::new(":memory:").await.unwrap());
+        let poisoned = User::register(
+            Id::generate_unique_identifier(),
+            Email::new("poisoned@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        let _ = repo.save(poisoned.clone()).await;
+        let user = registered_user(&repo, "test@example.com").await;
+        let publisher = Arc::new(PoisonedPublisher {
+            poisoned_user_id: poisoned.id(),
+            bus: InMemoryEventBus::new(),
+        });
+        let dispatcher = OutboxDispatcher::new(repo.clone(), publisher.clone());
+
+        for _ in 0..MAX_DELIVERY_ATTEMPTS {
+            assert_eq!(dispatcher.dispatch_pending().await, Ok(0));
+        }
+        assert_eq!(dispatcher.dispatch_pending().await, Ok(2));
+
+        assert_eq!(publisher.bus.published(), user.events());
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await,
+            Ok(vec![])
+        );
+    }
+
+    #[tokio::test]
+    async fn prunes_entries_dispatched_longer_ago_than_the_retention() {
+        let repo = Arc::new(Sqlite::new(":memory:").await.unwrap());
+        let _ = registered_user(&repo, "test@example.com").await;
+        let dispatcher = OutboxDispatcher::new(repo.clone(), Arc::new(InMemoryEventBus::new()));
+        let pending = repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await.unwrap();
+        let _ = repo.mark_dispatched(pending[0].id, 100).await;
+        let _ = repo.mark_dispatched(pending[1].id, 200).await;
+
+        let pruned = dispatcher
+            .prune_dispatched(DISPATCHED_RETENTION + 150)
+            .await;
+
+        assert_eq!(pruned, Ok(1));
+    }
+}
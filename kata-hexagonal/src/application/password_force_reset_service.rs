@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    repositories::{
+        refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+    },
+    value_objects::{id::Id, password::Password},
+};
+
+use super::{
+    application_error::ApplicationError,
+    dtos::{MessageResponse, PasswordForceResetRequest},
+    ports::event_publisher::EventPublisher,
+    user_find_service::UserNotFoundError,
+};
+
+/// Administrative reset: the current password stops working straight away
+/// and every session ends, and the user is sent a link to choose a new one.
+pub struct PasswordForceResetService {
+    user_repository: Arc<dyn UserRepository>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    event_publisher: Arc<dyn EventPublisher>,
+}
+
+impl PasswordForceResetService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        event_publisher: Arc<dyn EventPublisher>,
+    ) -> Self {
+        PasswordForceResetService {
+            user_repository,
+            refresh_token_repository,
+            event_publisher,
+        }
+    }
+
+    pub async fn force_reset(
+        &self,
+        request: PasswordForceResetRequest,
+    ) -> Result<MessageResponse, ApplicationError> {
+        let user_id = Id::from(request.id)?;
+        let mut user = self
+            .user_repository
+            .find_by_id(user_id.clone())
+            .await?
+            .ok_or(UserNotFoundError {})?;
+
+        let previous_hash = user.password();
+        user.reset_password(Password::unusable());
+        user.request_password_reset(time::now());
+        if !self
+            .user_repository
+            .replace_password(user.clone(), previous_hash)
+            .await?
+        {
+            return Err(UserNotFoundError {}.into());
+        }
+        // Whoever the account was taken from must not keep a session either.
+        self.refresh_token_repository
+            .revoke_all_for_user(&user_id)
+            .await?;
+        if let Err(error) = self.event_publisher.publish(user.pull_events()).await {
+            log::error!("could not publish user events: {}", error);
+        }
+
+        Ok(MessageResponse::new(
+            "Password revoked, a reset link has been sent",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, dtos::PasswordForceResetRequest,
+            password_force_reset_service::PasswordForceResetService,
+            password_reset_sender::PasswordResetSender,
+        },
+        domain::{
+            common::time,
+            entities::{refresh_token::RefreshToken, user::User},
+            repositories::{
+                refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+            },
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::{
+            in_memory_event_bus::InMemoryEventBus, in_memory_mailer::InMemoryMailer,
+            in_memory_password_reset_token_repository::InMemoryPasswordResetTokenRepository,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+        test_support::{create_stored_user, StaleUserRepository},
+    };
+
+    fn create_service(
+        users: Arc<dyn UserRepository>,
+        mailer: Arc<InMemoryMailer>,
+    ) -> PasswordForceResetService {
+        create_service_with_sessions(
+            users,
+            mailer,
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+        )
+    }
+
+    fn create_service_with_sessions(
+        users: Arc<dyn UserRepository>,
+        mailer: Arc<InMemoryMailer>,
+        sessions: Arc<InMemoryRefreshTokenRepository>,
+    ) -> PasswordForceResetService {
+        let events = Arc::new(InMemoryEventBus::new());
+        events.forward_to(Arc::new(PasswordResetSender::new(
+            Arc::new(InMemoryPasswordResetTokenRepository::new()),
+            mailer,
+            "http://localhost/password/reset".to_string(),
+        )));
+        PasswordForceResetService::new(users, sessions, events)
+    }
+
+    #[tokio::test]
+    async fn revokes_the_password_and_emails_a_reset_link() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let mailer = Arc::new(InMemoryMailer::new());
+        let user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        let _ = users.save(user.clone()).await;
+
+        let response = create_service(users.clone(), mailer.clone())
+            .force_reset(PasswordForceResetRequest { id: user.id() })
+            .await;
+
+        let stored = users
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.is_ok());
+        assert!(!stored.is_matching_password("TestPass123_"));
+        assert_eq!(mailer.sent().len(), 1);
+        assert_eq!(mailer.sent()[0].to, "test@example.com");
+    }
+
+    #[tokio::test]
+    async fn signs_out_every_session() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let sessions = Arc::new(InMemoryRefreshTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let (session, session_plaintext) =
+            RefreshToken::issue(Id::from(user.id()).unwrap(), 60, time::now());
+        let _ = sessions.save(session).await;
+        let service =
+            create_service_with_sessions(users, Arc::new(InMemoryMailer::new()), sessions.clone());
+
+        let response = service
+            .force_reset(PasswordForceResetRequest { id: user.id() })
+            .await;
+
+        let session = sessions
+            .find_by_hash(&RefreshToken::hash(&session_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.is_ok());
+        assert!(session.is_revoked());
+    }
+
+    #[tokio::test]
+    async fn keeps_a_role_change_made_during_the_reset() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let user = create_stored_user(&users).await;
+        let user_id = Id::from(user.id()).unwrap();
+        let stale = Arc::new(StaleUserRepository {
+            users: users.clone(),
+            stale: user.clone(),
+        });
+        users
+            .assign_role(user_id.clone(), Role::Admin)
+            .await
+            .unwrap();
+
+        let response = create_service(stale, Arc::new(InMemoryMailer::new()))
+            .force_reset(PasswordForceResetRequest { id: user.id() })
+            .await;
+
+        let stored = users.find_by_id(user_id).await.unwrap().unwrap();
+        assert!(response.is_ok());
+        assert_ne!(stored.password(), user.password());
+        assert_eq!(stored.role(), Role::Admin);
+    }
+
+    #[tokio::test]
+    async fn fails_when_user_does_not_exist() {
+        let mailer = Arc::new(InMemoryMailer::new());
+
+        let response = create_service(Arc::new(InMemoryUserRepository::new()), mailer.clone())
+            .force_reset(PasswordForceResetRequest {
+                id: Id::generate_unique_identifier().to_string(),
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::UserNotFound(_)
+        ));
+        assert!(mailer.sent().is_empty());
+    }
+}
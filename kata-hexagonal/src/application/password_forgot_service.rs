@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time, repositories::user_repository::UserRepository, value_objects::email::Email,
+};
+
+use super::{
+    application_error::ApplicationError,
+    dtos::{MessageResponse, PasswordForgotRequest},
+    ports::event_publisher::EventPublisher,
+};
+
+pub const PASSWORD_FORGOT_MESSAGE: &str =
+    "If the email is registered, a password reset link has been sent";
+
+pub struct PasswordForgotService {
+    user_repository: Arc<dyn UserRepository>,
+    event_publisher: Arc<dyn EventPublisher>,
+}
+
+impl PasswordForgotService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        event_publisher: Arc<dyn EventPublisher>,
+    ) -> Self {
+        PasswordForgotService {
+            user_repository,
+            event_publisher,
+        }
+    }
+
+    /// Queues a reset link when the address is registered; the link is
+    /// issued and emailed once the event is delivered, see
+    /// `PasswordResetSender`. Failing to queue it is only logged, so the
+    /// response is identical either way and callers cannot probe for accounts.
+    pub async fn forgot(
+        &self,
+        request: PasswordForgotRequest,
+    ) -> Result<MessageResponse, ApplicationError> {
+        let email = Email::new(request.email)?;
+
+        if let Some(mut user) = self.user_repository.find_by_email(email).await? {
+            user.request_password_reset(time::now());
+            if let Err(error) = self.user_repository.save(user.clone()).await {
+                log::error!("could not queue password reset email: {}", error);
+            } else if let Err(error) = self.event_publisher.publish(user.pull_events()).await {
+                log::error!("could not publish user events: {}", error);
+            }
+        }
+
+        Ok(MessageResponse::new(PASSWORD_FORGOT_MESSAGE))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+
+    use crate::{
+        application::{
+            dtos::PasswordForgotRequest,
+            password_forgot_service::{PasswordForgotService, PASSWORD_FORGOT_MESSAGE},
+            password_reset_sender::PasswordResetSender,
+            ports::event_publisher::{EventPublisher, PublishError},
+        },
+        domain::{
+            entities::user::User,
+            events::user_event::UserEvent,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            in_memory_event_bus::InMemoryEventBus, in_memory_mailer::InMemoryMailer,
+            in_memory_password_reset_token_repository::InMemoryPasswordResetTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    struct FailingEventPublisher {}
+
+    #[async_trait]
+    impl EventPublisher for FailingEventPublisher {
+        async fn publish(&self, _events: Vec<UserEvent>) -> Result<(), PublishError> {
+            Err(PublishError("mail queue is down".to_string()))
+        }
+    }
+
+    async fn create_service() -> (PasswordForgotService, Arc<InMemoryMailer>) {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let mailer = Arc::new(InMemoryMailer::new());
+        let events = Arc::new(InMemoryEventBus::new());
+        events.forward_to(Arc::new(PasswordResetSender::new(
+            Arc::new(InMemoryPasswordResetTokenRepository::new()),
+            mailer.clone(),
+            "http://localhost/password/reset".to_string(),
+        )));
+        let _ = users
+            .save(User::new(
+                Id::generate_unique_identifier(),
+                Email::new("test@example.com".to_string()).unwrap(),
+                Password::new("TestPass123_".to_string()).unwrap(),
+            ))
+            .await;
+
+        let service = PasswordForgotService::new(users, events);
+        (service, mailer)
+    }
+
+    #[tokio::test]
+    async fn emails_a_reset_link_to_registered_users() {
+        let (service, mailer) = create_service().await;
+
+        let response = service
+            .forgot(PasswordForgotRequest {
+                email: "test@example.com".to_string(),
+            })
+            .await;
+
+        let sent = mailer.sent();
+        assert_eq!(response.unwrap().message, PASSWORD_FORGOT_MESSAGE);
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "test@example.com");
+        assert!(sent[0]
+            .body
+            .contains("http://localhost/password/reset?token="));
+    }
+
+    #[tokio::test]
+    async fn responds_the_same_for_unknown_emails_without_sending() {
+        let (service, mailer) = create_service().await;
+
+        let response = service
+            .forgot(PasswordForgotRequest {
+                email: "unknown@example.com".to_string(),
+            })
+            .await;
+
+        assert_eq!(response.unwrap().message, PASSWORD_FORGOT_MESSAGE);
+        assert!(mailer.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn responds_the_same_when_the_link_cannot_be_queued() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let _ = users
+            .save(User::new(
+                Id::generate_unique_identifier(),
+                Email::new("test@example.com".to_string()).unwrap(),
+                Password::new("TestPass123_".to_string()).unwrap(),
+            ))
+            .await;
+        let service = PasswordForgotService::new(users, Arc::new(FailingEventPublisher {}));
+
+        let response = service
+            .forgot(PasswordForgotRequest {
+                email: "test@example.com".to_string(),
+            })
+            .await;
+
+        assert_eq!(response.unwrap().message, PASSWORD_FORGOT_MESSAGE);
+    }
+}
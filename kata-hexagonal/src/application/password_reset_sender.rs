@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    common::time,
+    entities::password_reset_token::{PasswordResetToken, PASSWORD_RESET_TOKEN_TIME_TO_LIVE},
+    events::user_event::UserEvent,
+    repositories::{
+        password_reset_token_repository::PasswordResetTokenRepository,
+        repository_error::RepositoryError,
+    },
+    value_objects::id::Id,
+};
+
+use super::ports::{
+    event_publisher::{EventPublisher, PublishError},
+    mailer::{EmailMessage, Mailer},
+};
+
+/// Emails a reset link for every `PasswordResetRequested` it is published,
+/// so the request is answered without waiting for the mail.
+pub struct PasswordResetSender {
+    reset_token_repository: Arc<dyn PasswordResetTokenRepository>,
+    mailer: Arc<dyn Mailer>,
+    reset_url: String,
+}
+
+impl PasswordResetSender {
+    pub fn new(
+        reset_token_repository: Arc<dyn PasswordResetTokenRepository>,
+        mailer: Arc<dyn Mailer>,
+        reset_url: String,
+    ) -> Self {
+        PasswordResetSender {
+            reset_token_repository,
+            mailer,
+            reset_url,
+        }
+    }
+
+    /// Stores a new token for the user and emails the link. A delivery
+    /// failure is logged rather than returned: the user can ask again.
+    pub async fn send(&self, user_id: &str, email: &str) -> Result<(), RepositoryError> {
+        let user_id = Id::from(user_id.to_string())
+            .map_err(|e| RepositoryError::CorruptData(e.to_string()))?;
+        let (token, plaintext) =
+            PasswordResetToken::issue(user_id, PASSWORD_RESET_TOKEN_TIME_TO_LIVE, time::now());
+        self.reset_token_repository.save(token).await?;
+
+        let message = EmailMessage {
+            to: email.to_string(),
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Follow this link to choose a new password:\n\n{}?token={}\n\nThe link expires in one hour. If you did not ask for it, ignore this email.",
+                self.reset_url, plaintext
+            ),
+        };
+        if let Err(error) = self.mailer.send(message).await {
+            log::error!("could not send password reset email: {}", error);
+        }
+
+        Ok(())
+    }
+}
+
+/// Failing to store the token fails the publish, so an outbox delivers the
+/// request again.
+#[async_trait]
+impl EventPublisher for PasswordResetSender {
+    async fn publish(&self, events: Vec<UserEvent>) -> Result<(), PublishError> {
+        for event in events {
+            if let UserEvent::PasswordResetRequested { user_id, email, .. } = event {
+                self.send(&user_id, &email)
+                    .await
+                    .map_err(|e| PublishError(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            password_reset_sender::PasswordResetSender, ports::event_publisher::EventPublisher,
+        },
+        domain::{
+            entities::password_reset_token::PasswordResetToken, events::user_event::UserEvent,
+            repositories::password_reset_token_repository::PasswordResetTokenRepository,
+            value_objects::id::Id,
+        },
+        infrastructure::{
+            in_memory_mailer::InMemoryMailer,
+            in_memory_password_reset_token_repository::InMemoryPasswordResetTokenRepository,
+        },
+    };
+
+    #[tokio::test]
+    async fn emails_a_link_with_a_stored_token_for_reset_requests_only() {
+        let tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let mailer = Arc::new(InMemoryMailer::new());
+        let sender = PasswordResetSender::new(
+            tokens.clone(),
+            mailer.clone(),
+            "http://localhost/password/reset".to_string(),
+        );
+        let user_id = Id::generate_unique_identifier().to_string();
+
+        let published = sender
+            .publish(vec![
+                UserEvent::UserLoggedIn {
+                    user_id: user_id.clone(),
+                    occurred_at: 1,
+                },
+                UserEvent::PasswordResetRequested {
+                    user_id,
+                    email: "test@example.com".to_string(),
+                    occurred_at: 1,
+                },
+            ])
+            .await;
+
+        let sent = mailer.sent();
+        assert_eq!(published, Ok(()));
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "test@example.com");
+        let plaintext = sent[0].body.split("?token=").nth(1).unwrap();
+        let plaintext = plaintext.split_whitespace().next().unwrap();
+        assert!(tokens
+            .find_by_hash(&PasswordResetToken::hash(plaintext))
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    entities::password_reset_token::PasswordResetToken,
+    repositories::{
+        password_reset_token_repository::PasswordResetTokenRepository,
+        refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+    },
+    value_objects::password::Password,
+};
+
+use super::{
+    application_error::ApplicationError,
+    blocking::run_blocking,
+    dtos::{MessageResponse, PasswordResetRequest},
+    ports::{
+        audit_log::{AuditEntry, AuditEventType, AuditLog},
+        event_publisher::EventPublisher,
+    },
+};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid or expired password reset token")]
+pub struct InvalidResetTokenError {}
+
+pub struct PasswordResetService {
+    user_repository: Arc<dyn UserRepository>,
+    reset_token_repository: Arc<dyn PasswordResetTokenRepository>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    event_publisher: Arc<dyn EventPublisher>,
+    audit_log: Arc<dyn AuditLog>,
+}
+
+impl PasswordResetService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        reset_token_repository: Arc<dyn PasswordResetTokenRepository>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        audit_log: Arc<dyn AuditLog>,
+    ) -> Self {
+        PasswordResetService {
+            user_repository,
+            reset_token_repository,
+            refresh_token_repository,
+            event_publisher,
+            audit_log,
+        }
+    }
+
+    pub async fn reset(
+        &self,
+        request: PasswordResetRequest,
+    ) -> Result<MessageResponse, ApplicationError> {
+        // Validate before consuming the token so a weak password does not burn it.
+        let new_password = request.new_password;
+        let password = run_blocking(move || Password::new(new_password)).await?;
+
+        let token = self
+            .reset_token_repository
+            .find_by_hash(&PasswordResetToken::hash(&request.token))
+            .await?
+            .ok_or(InvalidResetTokenError {})?;
+
+        if token.is_used()
+            || token.is_expired(time::now())
+            || !self.reset_token_repository.mark_as_used(&token).await?
+        {
+            return Err(InvalidResetTokenError {}.into());
+        }
+
+        let mut user = self
+            .user_repository
+            .find_by_id(token.user_id().clone())
+            .await?
+            .ok_or(InvalidResetTokenError {})?;
+
+        let previous_hash = user.password();
+        user.reset_password(password);
+        if !self
+            .user_repository
+            .replace_password(user.clone(), previous_hash)
+            .await?
+        {
+            return Err(InvalidResetTokenError {}.into());
+        }
+        // Whoever knew the old password may still hold a session.
+        self.refresh_token_repository
+            .revoke_all_for_user(token.user_id())
+            .await?;
+        if let Err(error) = self.event_publisher.publish(user.pull_events()).await {
+            log::error!("could not publish user events: {}", error);
+        }
+        let entry = AuditEntry::new(
+            AuditEventType::PasswordChanged,
+            user.email(),
+            request.client_ip,
+            time::now(),
+        )
+        .for_user(user.id());
+        if let Err(error) = self.audit_log.record(entry).await {
+            log::error!("could not write audit entry: {}", error);
+        }
+        self.reset_token_repository
+            .invalidate_for_user(token.user_id())
+            .await?;
+
+        Ok(MessageResponse::new("Password has been reset"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, dtos::PasswordResetRequest,
+            password_reset_service::PasswordResetService,
+        },
+        domain::{
+            common::time,
+            entities::{
+                password_reset_token::PasswordResetToken, refresh_token::RefreshToken, user::User,
+            },
+            repositories::{
+                password_reset_token_repository::PasswordResetTokenRepository,
+                refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+            },
+            value_objects::{email::Email, id::Id, lockout_policy::LockoutPolicy, role::Role},
+        },
+        infrastructure::{
+            in_memory_audit_log::InMemoryAuditLog, in_memory_event_bus::InMemoryEventBus,
+            in_memory_password_reset_token_repository::InMemoryPasswordResetTokenRepository,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+        test_support::{create_stored_user, StaleUserRepository},
+    };
+
+    #[tokio::test]
+    async fn resets_the_password_and_invalidates_other_tokens() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = create_service(users.clone(), tokens.clone());
+        let token = issue_token(&tokens, &user, 60).await;
+        let another_token = issue_token(&tokens, &user, 60).await;
+
+        let response = service.reset(create_request(&token, "NewPass123_")).await;
+        let other = service
+            .reset(create_request(&another_token, "OtherPass123_"))
+            .await;
+
+        let stored = users
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.is_ok());
+        assert!(stored.is_matching_password("NewPass123_"));
+        assert!(matches!(
+            other.unwrap_err(),
+            ApplicationError::InvalidResetToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn signs_out_every_session_and_lifts_the_lockout() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let sessions = Arc::new(InMemoryRefreshTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let user_id = Id::from(user.id()).unwrap();
+        let service = PasswordResetService::new(
+            users.clone(),
+            tokens.clone(),
+            sessions.clone(),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let (session, session_plaintext) = RefreshToken::issue(user_id.clone(), 60, time::now());
+        let _ = sessions.save(session).await;
+        let lock_at_once = LockoutPolicy {
+            max_attempts: 1,
+            ..LockoutPolicy::default()
+        };
+        let _ = users
+            .register_failed_login(user_id.clone(), lock_at_once, time::now())
+            .await;
+        let token = issue_token(&tokens, &user, 60).await;
+
+        let response = service.reset(create_request(&token, "NewPass123_")).await;
+
+        let session = sessions
+            .find_by_hash(&RefreshToken::hash(&session_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        let stored = users.find_by_id(user_id).await.unwrap().unwrap();
+        assert!(response.is_ok());
+        assert!(session.is_revoked());
+        assert_eq!(stored.failed_login_attempts(), 0);
+        assert!(!stored.is_locked(time::now()));
+    }
+
+    #[tokio::test]
+    async fn keeps_a_role_change_made_during_the_reset() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let user_id = Id::from(user.id()).unwrap();
+        let service = create_stale_service(&users, &user, tokens.clone());
+        users
+            .assign_role(user_id.clone(), Role::Admin)
+            .await
+            .unwrap();
+        let token = issue_token(&tokens, &user, 60).await;
+
+        let response = service.reset(create_request(&token, "NewPass123_")).await;
+
+        let stored = users.find_by_id(user_id).await.unwrap().unwrap();
+        assert!(response.is_ok());
+        assert!(stored.is_matching_password("NewPass123_"));
+        assert_eq!(stored.role(), Role::Admin);
+    }
+
+    #[tokio::test]
+    async fn does_not_bring_back_an_account_deleted_during_the_reset() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = create_stale_service(&users, &user, tokens.clone());
+        let mut deleted = user.clone();
+        deleted.delete(time::now());
+        users.save(deleted).await.unwrap();
+        let token = issue_token(&tokens, &user, 60).await;
+
+        let response = service.reset(create_request(&token, "NewPass123_")).await;
+
+        let email = Email::new(user.email()).unwrap();
+        let stored = users.find_deleted_by_email(email).await.unwrap().unwrap();
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidResetToken(_)
+        ));
+        assert!(stored.is_deleted());
+        assert_eq!(stored.password(), user.password());
+    }
+
+    #[tokio::test]
+    async fn a_token_can_only_be_used_once() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = create_service(users, tokens.clone());
+        let token = issue_token(&tokens, &user, 60).await;
+
+        let _ = service.reset(create_request(&token, "NewPass123_")).await;
+        let response = service.reset(create_request(&token, "OtherPass123_")).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidResetToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_expired_token() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = create_service(users, tokens.clone());
+        let token = issue_token(&tokens, &user, 0).await;
+
+        let response = service.reset(create_request(&token, "NewPass123_")).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidResetToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_weak_password_does_not_consume_the_token() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = create_service(users, tokens.clone());
+        let token = issue_token(&tokens, &user, 60).await;
+
+        let weak = service.reset(create_request(&token, "weak")).await;
+        let response = service.reset(create_request(&token, "NewPass123_")).await;
+
+        assert!(matches!(
+            weak.unwrap_err(),
+            ApplicationError::InvalidPassword(_)
+        ));
+        assert!(response.is_ok());
+    }
+
+    fn create_service(
+        users: Arc<InMemoryUserRepository>,
+        tokens: Arc<InMemoryPasswordResetTokenRepository>,
+    ) -> PasswordResetService {
+        PasswordResetService::new(
+            users,
+            tokens,
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+    }
+
+    /// Loads `user` as stored now, however `users` changes afterwards.
+    fn create_stale_service(
+        users: &Arc<InMemoryUserRepository>,
+        user: &User,
+        tokens: Arc<InMemoryPasswordResetTokenRepository>,
+    ) -> PasswordResetService {
+        PasswordResetService::new(
+            Arc::new(StaleUserRepository {
+                users: users.clone(),
+                stale: user.clone(),
+            }),
+            tokens,
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+    }
+
+    async fn issue_token(
+        tokens: &InMemoryPasswordResetTokenRepository,
+        user: &User,
+        time_to_live: u64,
+    ) -> String {
+        let (token, plaintext) =
+            PasswordResetToken::issue(Id::from(user.id()).unwrap(), time_to_live, time::now());
+        tokens.save(token).await.unwrap();
+        plaintext
+    }
+
+    fn create_request(token: &str, new_password: &str) -> PasswordResetRequest {
+        PasswordResetRequest {
+            token: token.to_string(),
+            new_password: new_password.to_string(),
+            client_ip: None,
+        }
+    }
+}
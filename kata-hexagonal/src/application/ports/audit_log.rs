@@ -0,0 +1,111 @@
+use std::{fmt, str::FromStr};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Could not access the audit log: {0}")]
+pub struct AuditLogError(pub String);
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unknown audit event type {0:?}")]
+pub struct UnknownAuditEventTypeError(pub String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventType {
+    Registered,
+    LoginSucceeded,
+    LoginFailed,
+    PasswordChanged,
+}
+
+impl AuditEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventType::Registered => "registered",
+            AuditEventType::LoginSucceeded => "login_succeeded",
+            AuditEventType::LoginFailed => "login_failed",
+            AuditEventType::PasswordChanged => "password_changed",
+        }
+    }
+}
+
+impl FromStr for AuditEventType {
+    type Err = UnknownAuditEventTypeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "registered" => Ok(AuditEventType::Registered),
+            "login_succeeded" => Ok(AuditEventType::LoginSucceeded),
+            "login_failed" => Ok(AuditEventType::LoginFailed),
+            "password_changed" => Ok(AuditEventType::PasswordChanged),
+            other => Err(UnknownAuditEventTypeError(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for AuditEventType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Who did what, from where and when. `user_id` is missing when the email
+/// did not belong to any account, e.g. a login attempt on an unknown email.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub event_type: AuditEventType,
+    pub user_id: Option<String>,
+    pub email: String,
+    pub ip: Option<String>,
+    pub occurred_at: u64,
+}
+
+impl AuditEntry {
+    pub fn new(event_type: AuditEventType, email: String, ip: Option<String>, now: u64) -> Self {
+        AuditEntry {
+            event_type,
+            user_id: None,
+            email,
+            ip,
+            occurred_at: now,
+        }
+    }
+
+    pub fn for_user(mut self, user_id: String) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn matches(&self, query: &AuditQuery) -> bool {
+        query
+            .user_id
+            .as_ref()
+            .is_none_or(|user_id| self.user_id.as_ref() == Some(user_id))
+            && query
+                .event_type
+                .is_none_or(|event_type| self.event_type == event_type)
+            && query.from.is_none_or(|from| self.occurred_at >= from)
+            && query.until.is_none_or(|until| self.occurred_at < until)
+    }
+}
+
+/// Filters for reading the audit log. `from` is inclusive and `until`
+/// exclusive, both in seconds since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditQuery {
+    pub user_id: Option<String>,
+    pub event_type: Option<AuditEventType>,
+    pub from: Option<u64>,
+    pub until: Option<u64>,
+    pub limit: usize,
+}
+
+/// Append-only record of authentication activity, kept for compliance.
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn record(&self, entry: AuditEntry) -> Result<(), AuditLogError>;
+    /// Matching entries, newest first.
+    async fn query(&self, query: AuditQuery) -> Result<Vec<AuditEntry>, AuditLogError>;
+}
@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use crate::domain::events::user_event::UserEvent;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Could not publish events: {0}")]
+pub struct PublishError(pub String);
+
+/// Called by the use cases once the user that recorded `events` is saved.
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, events: Vec<UserEvent>) -> Result<(), PublishError>;
+}
@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Could not send email: {0}")]
+pub struct MailerError(pub String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: EmailMessage) -> Result<(), MailerError>;
+}
@@ -0,0 +1,4 @@
+pub mod audit_log;
+pub mod event_publisher;
+pub mod mailer;
+pub mod token_issuer;
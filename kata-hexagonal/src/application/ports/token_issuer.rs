@@ -0,0 +1,22 @@
+use crate::domain::{entities::user::User, value_objects::id::Id};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum TokenError {
+    #[error("Token has expired")]
+    Expired,
+    #[error("Invalid token")]
+    Invalid,
+    #[error("Could not issue token: {0}")]
+    Issuing(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires_in: u64,
+}
+
+pub trait TokenIssuer: Send + Sync {
+    fn issue(&self, user: &User) -> Result<AccessToken, TokenError>;
+    fn verify(&self, token: &str) -> Result<Id, TokenError>;
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    entities::refresh_token::{RefreshToken, REFRESH_TOKEN_TIME_TO_LIVE},
+    repositories::{
+        refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+    },
+};
+
+use super::{
+    application_error::ApplicationError,
+    dtos::{TokenRefreshRequest, TokenRefreshResponse},
+    ports::token_issuer::TokenIssuer,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid refresh token")]
+pub struct InvalidRefreshTokenError {}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Refresh token reuse detected, all sessions have been revoked")]
+pub struct RefreshTokenReuseError {}
+
+pub struct TokenRefreshService {
+    user_repository: Arc<dyn UserRepository>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    token_issuer: Arc<dyn TokenIssuer>,
+}
+
+impl TokenRefreshService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        token_issuer: Arc<dyn TokenIssuer>,
+    ) -> Self {
+        TokenRefreshService {
+            user_repository,
+            refresh_token_repository,
+            token_issuer,
+        }
+    }
+
+    pub async fn refresh(
+        &self,
+        request: TokenRefreshRequest,
+    ) -> Result<TokenRefreshResponse, ApplicationError> {
+        let now = time::now();
+        let token = self
+            .refresh_token_repository
+            .find_by_hash(&RefreshToken::hash(&request.refresh_token))
+            .await?
+            .ok_or(InvalidRefreshTokenError {})?;
+
+        if token.is_revoked() || token.is_expired(now) {
+            return Err(InvalidRefreshTokenError {}.into());
+        }
+
+        if !self.refresh_token_repository.mark_as_used(&token).await? {
+            self.refresh_token_repository
+                .revoke_family(token.family_id())
+                .await?;
+            log::warn!(
+                "refresh token reuse detected for user {}, revoked family {}",
+                token.user_id(),
+                token.family_id()
+            );
+            return Err(RefreshTokenReuseError {}.into());
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(token.user_id().clone())
+            .await?
+            .ok_or(InvalidRefreshTokenError {})?;
+
+        let access_token = self.token_issuer.issue(&user)?;
+        let (next_token, next_plaintext) = token.rotate(REFRESH_TOKEN_TIME_TO_LIVE, now);
+        self.refresh_token_repository.save(next_token).await?;
+
+        Ok(TokenRefreshResponse::new(access_token, next_plaintext))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, dtos::TokenRefreshRequest,
+            token_refresh_service::TokenRefreshService,
+        },
+        domain::{
+            common::time,
+            entities::{refresh_token::RefreshToken, user::User},
+            repositories::refresh_token_repository::RefreshTokenRepository,
+            value_objects::id::Id,
+        },
+        infrastructure::{
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository, jwt_token_issuer::JwtTokenIssuer,
+        },
+        test_support::create_stored_user,
+    };
+
+    #[tokio::test]
+    async fn rotates_the_refresh_token() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryRefreshTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = create_service(users, tokens.clone());
+        let refresh_token = issue_token(&tokens, &user, 60).await;
+
+        let response = service.refresh(request(&refresh_token)).await.unwrap();
+
+        assert_ne!(response.refresh_token, refresh_token);
+        assert_eq!(response.token_type, "Bearer");
+        assert!(service
+            .refresh(request(&response.refresh_token))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unknown_token() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        create_stored_user(&users).await;
+        let service = create_service(users, Arc::new(InMemoryRefreshTokenRepository::new()));
+
+        let response = service.refresh(request("unknown")).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidRefreshToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_expired_token() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryRefreshTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = create_service(users, tokens.clone());
+        let refresh_token = issue_token(&tokens, &user, 0).await;
+
+        let response = service.refresh(request(&refresh_token)).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidRefreshToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn reuse_of_a_rotated_token_revokes_the_whole_family() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryRefreshTokenRepository::new());
+        let user = create_stored_user(&users).await;
+        let service = create_service(users, tokens.clone());
+        let refresh_token = issue_token(&tokens, &user, 60).await;
+        let rotated = service
+            .refresh(request(&refresh_token))
+            .await
+            .unwrap()
+            .refresh_token;
+
+        let reuse = service.refresh(request(&refresh_token)).await;
+        let after_reuse = service.refresh(request(&rotated)).await;
+
+        assert!(matches!(
+            reuse.unwrap_err(),
+            ApplicationError::RefreshTokenReuse(_)
+        ));
+        assert!(matches!(
+            after_reuse.unwrap_err(),
+            ApplicationError::InvalidRefreshToken(_)
+        ));
+    }
+
+    fn create_service(
+        users: Arc<InMemoryUserRepository>,
+        tokens: Arc<InMemoryRefreshTokenRepository>,
+    ) -> TokenRefreshService {
+        let issuer = Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)));
+        TokenRefreshService::new(users, tokens, issuer)
+    }
+
+    async fn issue_token(
+        tokens: &InMemoryRefreshTokenRepository,
+        user: &User,
+        time_to_live: u64,
+    ) -> String {
+        let (token, plaintext) =
+            RefreshToken::issue(Id::from(user.id()).unwrap(), time_to_live, time::now());
+        tokens.save(token).await.unwrap();
+        plaintext
+    }
+
+    fn request(refresh_token: &str) -> TokenRefreshRequest {
+        TokenRefreshRequest {
+            refresh_token: refresh_token.to_string(),
+        }
+    }
+}
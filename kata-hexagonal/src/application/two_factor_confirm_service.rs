@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time, repositories::two_factor_repository::TwoFactorRepository, value_objects::id::Id,
+};
+
+use super::{
+    application_error::ApplicationError,
+    dtos::{TwoFactorConfirmRequest, TwoFactorConfirmResponse},
+    two_factor_enroll_service::TwoFactorAlreadyEnabledError,
+    user_login_service::InvalidTwoFactorCodeError,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Two-factor authentication has not been enrolled")]
+pub struct TwoFactorNotEnrolledError {}
+
+pub struct TwoFactorConfirmService {
+    two_factor_repository: Arc<dyn TwoFactorRepository>,
+}
+
+impl TwoFactorConfirmService {
+    pub fn new(two_factor_repository: Arc<dyn TwoFactorRepository>) -> Self {
+        TwoFactorConfirmService {
+            two_factor_repository,
+        }
+    }
+
+    /// Enables two-factor authentication once the user proves their
+    /// authenticator produces valid codes, and hands out the recovery codes.
+    pub async fn confirm(
+        &self,
+        request: TwoFactorConfirmRequest,
+    ) -> Result<TwoFactorConfirmResponse, ApplicationError> {
+        let mut two_factor = self
+            .two_factor_repository
+            .find_by_user_id(&Id::from(request.user_id)?)
+            .await?
+            .ok_or(TwoFactorNotEnrolledError {})?;
+        if two_factor.is_confirmed() {
+            return Err(TwoFactorAlreadyEnabledError {}.into());
+        }
+
+        let recovery_codes = two_factor
+            .confirm(&request.code, time::now())
+            .ok_or(InvalidTwoFactorCodeError {})?;
+        self.two_factor_repository.save(two_factor).await?;
+
+        Ok(TwoFactorConfirmResponse { recovery_codes })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, dtos::TwoFactorConfirmRequest,
+            two_factor_confirm_service::TwoFactorConfirmService,
+        },
+        domain::{
+            common::{time, totp},
+            entities::two_factor::{TwoFactor, RECOVERY_CODE_COUNT},
+            repositories::two_factor_repository::TwoFactorRepository,
+            value_objects::id::Id,
+        },
+        infrastructure::in_memory_two_factor_repository::InMemoryTwoFactorRepository,
+    };
+
+    #[tokio::test]
+    async fn enables_two_factor_with_a_valid_code() {
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let service = TwoFactorConfirmService::new(two_factors.clone());
+        let user_id = Id::generate_unique_identifier();
+        let two_factor = TwoFactor::enroll(user_id.clone());
+        let code = current_code(&two_factor);
+        let _ = two_factors.save(two_factor).await;
+
+        let response = service
+            .confirm(TwoFactorConfirmRequest {
+                user_id: user_id.to_string(),
+                code,
+            })
+            .await
+            .unwrap();
+
+        let stored = two_factors
+            .find_by_user_id(&user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.is_confirmed());
+        assert_eq!(response.recovery_codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(stored.recovery_code_hashes().len(), RECOVERY_CODE_COUNT);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_wrong_code() {
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let service = TwoFactorConfirmService::new(two_factors.clone());
+        let user_id = Id::generate_unique_identifier();
+        let two_factor = TwoFactor::enroll(user_id.clone());
+        let wrong = format!(
+            "{:06}",
+            (totp::parse_code(&current_code(&two_factor)).unwrap() + 1) % 1_000_000
+        );
+        let _ = two_factors.save(two_factor).await;
+
+        let response = service
+            .confirm(TwoFactorConfirmRequest {
+                user_id: user_id.to_string(),
+                code: wrong,
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidTwoFactorCode(_)
+        ));
+        let stored = two_factors
+            .find_by_user_id(&user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!stored.is_confirmed());
+    }
+
+    #[tokio::test]
+    async fn requires_an_enrollment() {
+        let service = TwoFactorConfirmService::new(Arc::new(InMemoryTwoFactorRepository::new()));
+
+        let response = service
+            .confirm(TwoFactorConfirmRequest {
+                user_id: Id::generate_unique_identifier().to_string(),
+                code: "123456".to_string(),
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::TwoFactorNotEnrolled(_)
+        ));
+    }
+
+    fn current_code(two_factor: &TwoFactor) -> String {
+        format!(
+            "{:06}",
+            totp::code(two_factor.secret(), totp::step(time::now()))
+        )
+    }
+}
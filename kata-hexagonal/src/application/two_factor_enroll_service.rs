@@ -0,0 +1,262 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::{time, totp},
+    entities::two_factor::TwoFactor,
+    repositories::{two_factor_repository::TwoFactorRepository, user_repository::UserRepository},
+    value_objects::{id::Id, lockout_policy::LockoutPolicy},
+};
+
+use super::{
+    application_error::ApplicationError,
+    blocking::run_blocking,
+    dtos::{TwoFactorEnrollRequest, TwoFactorEnrollResponse},
+    user_change_password_service::InvalidCurrentPasswordError,
+    user_find_service::UserNotFoundError,
+    user_login_service::AccountLockedError,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Two-factor authentication is already enabled")]
+pub struct TwoFactorAlreadyEnabledError {}
+
+pub struct TwoFactorEnrollService {
+    user_repository: Arc<dyn UserRepository>,
+    two_factor_repository: Arc<dyn TwoFactorRepository>,
+    issuer: String,
+    lockout_policy: LockoutPolicy,
+}
+
+impl TwoFactorEnrollService {
+    /// `issuer` is the name authenticator apps list the account under.
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        two_factor_repository: Arc<dyn TwoFactorRepository>,
+        issuer: String,
+    ) -> Self {
+        TwoFactorEnrollService {
+            user_repository,
+            two_factor_repository,
+            issuer,
+            lockout_policy: LockoutPolicy::default(),
+        }
+    }
+
+    pub fn with_lockout_policy(mut self, lockout_policy: LockoutPolicy) -> Self {
+        self.lockout_policy = lockout_policy;
+        self
+    }
+
+    /// Generates a secret for the user's authenticator once the current
+    /// password checks out, so a stolen access token cannot plant one. Logins
+    /// are not affected until it is confirmed; enrolling again replaces an
+    /// unconfirmed secret.
+    pub async fn enroll(
+        &self,
+        request: TwoFactorEnrollRequest,
+    ) -> Result<TwoFactorEnrollResponse, ApplicationError> {
+        let user_id = Id::from(request.user_id)?;
+        let user = self
+            .user_repository
+            .find_by_id(user_id.clone())
+            .await?
+            .ok_or(UserNotFoundError {})?;
+
+        let now = time::now();
+        if user.is_locked(now) {
+            return Err(AccountLockedError {}.into());
+        }
+
+        let password = request.password;
+        let (user, matching) = run_blocking(move || {
+            let matching = user.is_matching_password(&password);
+            (user, matching)
+        })
+        .await;
+        if !matching {
+            self.user_repository
+                .register_failed_login(user_id, self.lockout_policy, now)
+                .await?;
+            return Err(InvalidCurrentPasswordError {}.into());
+        }
+
+        let existing = self.two_factor_repository.find_by_user_id(&user_id).await?;
+        if existing.is_some_and(|two_factor| two_factor.is_confirmed()) {
+            return Err(TwoFactorAlreadyEnabledError {}.into());
+        }
+
+        let two_factor = TwoFactor::enroll(user_id);
+        let response = TwoFactorEnrollResponse {
+            secret: totp::encode_secret(two_factor.secret()),
+            otpauth_uri: two_factor.provisioning_uri(&self.issuer, &user.email()),
+        };
+        self.two_factor_repository.save(two_factor).await?;
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{error::Error, sync::Arc};
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, dtos::TwoFactorEnrollRequest,
+            two_factor_enroll_service::TwoFactorEnrollService,
+        },
+        domain::{
+            common::totp,
+            entities::{two_factor::TwoFactor, user::User},
+            repositories::{
+                two_factor_repository::TwoFactorRepository, user_repository::UserRepository,
+            },
+            value_objects::{
+                email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password,
+            },
+        },
+        infrastructure::{
+            in_memory_two_factor_repository::InMemoryTwoFactorRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    #[tokio::test]
+    async fn stores_a_secret_and_returns_its_provisioning_uri() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let service =
+            TwoFactorEnrollService::new(users.clone(), two_factors.clone(), "Kata".to_string());
+        let user = create_user().unwrap();
+        let _ = users.save(user.clone()).await;
+
+        let response = service
+            .enroll(create_request(&user, "TestPass123_"))
+            .await
+            .unwrap();
+
+        let stored = two_factors
+            .find_by_user_id(&Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!stored.is_confirmed());
+        assert_eq!(
+            totp::decode_secret(&response.secret).unwrap(),
+            stored.secret()
+        );
+        assert!(response
+            .otpauth_uri
+            .starts_with("otpauth://totp/Kata:test%40example.com?secret="));
+    }
+
+    #[tokio::test]
+    async fn requires_the_current_password() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let service =
+            TwoFactorEnrollService::new(users.clone(), two_factors.clone(), "Kata".to_string());
+        let user = create_user().unwrap();
+        let _ = users.save(user.clone()).await;
+
+        let response = service.enroll(create_request(&user, "WrongPass123_")).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCurrentPassword(_)
+        ));
+        assert_eq!(
+            two_factors
+                .find_by_user_id(&Id::from(user.id()).unwrap())
+                .await,
+            Ok(None)
+        );
+    }
+
+    #[tokio::test]
+    async fn locks_the_account_after_too_many_wrong_passwords() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let service = TwoFactorEnrollService::new(
+            users.clone(),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            "Kata".to_string(),
+        )
+        .with_lockout_policy(LockoutPolicy {
+            max_attempts: 2,
+            ..LockoutPolicy::default()
+        });
+        let user = create_user().unwrap();
+        let _ = users.save(user.clone()).await;
+
+        for _ in 0..2 {
+            let _ = service.enroll(create_request(&user, "WrongPass123_")).await;
+        }
+        let response = service.enroll(create_request(&user, "TestPass123_")).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::AccountLocked(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn does_not_replace_a_confirmed_secret() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let service =
+            TwoFactorEnrollService::new(users.clone(), two_factors.clone(), "Kata".to_string());
+        let user = create_user().unwrap();
+        let _ = users.save(user.clone()).await;
+        let confirmed = TwoFactor::new(
+            Id::from(user.id()).unwrap(),
+            totp::generate_secret(),
+            true,
+            None,
+            Vec::new(),
+        );
+        let _ = two_factors.save(confirmed).await;
+
+        let response = service.enroll(create_request(&user, "TestPass123_")).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::TwoFactorAlreadyEnabled(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_users() {
+        let service = TwoFactorEnrollService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            "Kata".to_string(),
+        );
+
+        let response = service
+            .enroll(TwoFactorEnrollRequest {
+                user_id: Id::generate_unique_identifier().to_string(),
+                password: "TestPass123_".to_string(),
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::UserNotFound(_)
+        ));
+    }
+
+    fn create_request(user: &User, password: &str) -> TwoFactorEnrollRequest {
+        TwoFactorEnrollRequest {
+            user_id: user.id(),
+            password: password.to_string(),
+        }
+    }
+
+    fn create_user() -> Result<User, Box<dyn Error>> {
+        let id = Id::generate_unique_identifier();
+        let email = Email::new("test@example.com".to_string())?;
+        let password = Password::new("TestPass123_".to_string())?;
+
+        Ok(User::new(id, email, password))
+    }
+}
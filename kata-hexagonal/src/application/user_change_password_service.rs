@@ -0,0 +1,397 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    repositories::{
+        refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+    },
+    value_objects::{id::Id, lockout_policy::LockoutPolicy},
+};
+
+use super::{
+    application_error::ApplicationError,
+    blocking::run_blocking,
+    dtos::{UserChangePasswordRequest, UserChangePasswordResponse},
+    ports::{
+        audit_log::{AuditEntry, AuditEventType, AuditLog},
+        event_publisher::EventPublisher,
+    },
+    user_find_service::UserNotFoundError,
+    user_login_service::AccountLockedError,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Current password is incorrect")]
+pub struct InvalidCurrentPasswordError {}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Not allowed to change another user's password")]
+pub struct ForbiddenPasswordChangeError {}
+
+pub struct UserChangePasswordService {
+    user_repository: Arc<dyn UserRepository>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    event_publisher: Arc<dyn EventPublisher>,
+    audit_log: Arc<dyn AuditLog>,
+    lockout_policy: LockoutPolicy,
+}
+
+impl UserChangePasswordService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        audit_log: Arc<dyn AuditLog>,
+    ) -> Self {
+        UserChangePasswordService {
+            user_repository,
+            refresh_token_repository,
+            event_publisher,
+            audit_log,
+            lockout_policy: LockoutPolicy::default(),
+        }
+    }
+
+    pub fn with_lockout_policy(mut self, lockout_policy: LockoutPolicy) -> Self {
+        self.lockout_policy = lockout_policy;
+        self
+    }
+
+    pub async fn change_password(
+        &self,
+        request: UserChangePasswordRequest,
+    ) -> Result<UserChangePasswordResponse, ApplicationError> {
+        if request.requester_id != request.user_id {
+            return Err(ForbiddenPasswordChangeError {}.into());
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(Id::from(request.user_id)?)
+            .await?
+            .ok_or(UserNotFoundError {})?;
+
+        // A stolen access token must not turn into unlimited password guesses.
+        let now = time::now();
+        if user.is_locked(now) {
+            return Err(AccountLockedError {}.into());
+        }
+
+        let current_password = request.current_password;
+        let (mut user, matching) = run_blocking(move || {
+            let matching = user.is_matching_password(&current_password);
+            (user, matching)
+        })
+        .await;
+        if !matching {
+            self.user_repository
+                .register_failed_login(Id::from(user.id())?, self.lockout_policy, now)
+                .await?;
+            return Err(InvalidCurrentPasswordError {}.into());
+        }
+
+        let previous_hash = user.password();
+        let new_password = request.new_password;
+        let (mut user, changed) = run_blocking(move || {
+            let changed = user.change_password(new_password);
+            (user, changed)
+        })
+        .await;
+        changed?;
+
+        let dto = user.to_dto();
+        // Only the password is written, so a role change or deletion made
+        // while hashing is kept; a password changed meanwhile wins.
+        if !self
+            .user_repository
+            .replace_password(user.clone(), previous_hash)
+            .await?
+        {
+            return Err(InvalidCurrentPasswordError {}.into());
+        }
+        // Sessions opened elsewhere with the old password end here too.
+        self.refresh_token_repository
+            .revoke_all_for_user(&Id::from(user.id())?)
+            .await?;
+        if let Err(error) = self.event_publisher.publish(user.pull_events()).await {
+            log::error!("could not publish user events: {}", error);
+        }
+        let entry = AuditEntry::new(
+            AuditEventType::PasswordChanged,
+            user.email(),
+            request.client_ip,
+            now,
+        )
+        .for_user(user.id());
+        if let Err(error) = self.audit_log.record(entry).await {
+            log::error!("could not write audit entry: {}", error);
+        }
+
+        Ok(dto.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, dtos::UserChangePasswordRequest,
+            user_change_password_service::UserChangePasswordService,
+        },
+        domain::{
+            common::time,
+            entities::{refresh_token::RefreshToken, user::User},
+            repositories::{
+                refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+            },
+            value_objects::{
+                email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password, role::Role,
+            },
+        },
+        infrastructure::{
+            in_memory_audit_log::InMemoryAuditLog, in_memory_event_bus::InMemoryEventBus,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+        test_support::StaleUserRepository,
+    };
+
+    async fn create_service() -> (UserChangePasswordService, Arc<InMemoryUserRepository>, User) {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        let _ = repo.save(user.clone()).await;
+
+        (
+            UserChangePasswordService::new(
+                repo.clone(),
+                Arc::new(InMemoryRefreshTokenRepository::new()),
+                Arc::new(InMemoryEventBus::new()),
+                Arc::new(InMemoryAuditLog::new()),
+            ),
+            repo,
+            user,
+        )
+    }
+
+    /// Loads `user` as stored now, however `repo` changes afterwards.
+    fn create_stale_service(
+        repo: &Arc<InMemoryUserRepository>,
+        user: &User,
+    ) -> UserChangePasswordService {
+        UserChangePasswordService::new(
+            Arc::new(StaleUserRepository {
+                users: repo.clone(),
+                stale: user.clone(),
+            }),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+    }
+
+    fn create_request(user: &User, current: &str, new: &str) -> UserChangePasswordRequest {
+        UserChangePasswordRequest {
+            requester_id: user.id(),
+            user_id: user.id(),
+            current_password: current.to_string(),
+            new_password: new.to_string(),
+            client_ip: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn changes_the_password() {
+        let (service, repo, user) = create_service().await;
+
+        let response = service
+            .change_password(create_request(&user, "TestPass123_", "AnotherPass123_"))
+            .await;
+
+        let stored = repo
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.is_ok());
+        assert!(stored.is_matching_password("AnotherPass123_"));
+    }
+
+    #[tokio::test]
+    async fn publishes_password_changed() {
+        let (_, repo, user) = create_service().await;
+        let events = Arc::new(InMemoryEventBus::new());
+        let service = UserChangePasswordService::new(
+            repo,
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            events.clone(),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+
+        let _ = service
+            .change_password(create_request(&user, "TestPass123_", "AnotherPass123_"))
+            .await;
+
+        let published = events.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].name(), "PasswordChanged");
+    }
+
+    #[tokio::test]
+    async fn rejects_a_wrong_current_password() {
+        let (service, _, user) = create_service().await;
+
+        let response = service
+            .change_password(create_request(&user, "WrongPass123_", "AnotherPass123_"))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCurrentPassword(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn locks_the_account_after_too_many_wrong_current_passwords() {
+        let (_, repo, user) = create_service().await;
+        let service = UserChangePasswordService::new(
+            repo,
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+        .with_lockout_policy(LockoutPolicy {
+            max_attempts: 2,
+            ..LockoutPolicy::default()
+        });
+
+        for _ in 0..2 {
+            let _ = service
+                .change_password(create_request(&user, "WrongPass123_", "AnotherPass123_"))
+                .await;
+        }
+        let response = service
+            .change_password(create_request(&user, "TestPass123_", "AnotherPass123_"))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::AccountLocked(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn signs_out_every_session() {
+        let (_, repo, user) = create_service().await;
+        let sessions = Arc::new(InMemoryRefreshTokenRepository::new());
+        let service = UserChangePasswordService::new(
+            repo,
+            sessions.clone(),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let (session, session_plaintext) =
+            RefreshToken::issue(Id::from(user.id()).unwrap(), 60, time::now());
+        let _ = sessions.save(session).await;
+
+        let response = service
+            .change_password(create_request(&user, "TestPass123_", "AnotherPass123_"))
+            .await;
+
+        let session = sessions
+            .find_by_hash(&RefreshToken::hash(&session_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.is_ok());
+        assert!(session.is_revoked());
+    }
+
+    #[tokio::test]
+    async fn keeps_a_role_change_made_while_hashing() {
+        let (_, repo, user) = create_service().await;
+        let user_id = Id::from(user.id()).unwrap();
+        let service = create_stale_service(&repo, &user);
+        repo.assign_role(user_id.clone(), Role::Admin)
+            .await
+            .unwrap();
+
+        let response = service
+            .change_password(create_request(&user, "TestPass123_", "AnotherPass123_"))
+            .await;
+
+        let stored = repo.find_by_id(user_id).await.unwrap().unwrap();
+        assert!(response.is_ok());
+        assert!(stored.is_matching_password("AnotherPass123_"));
+        assert_eq!(stored.role(), Role::Admin);
+    }
+
+    #[tokio::test]
+    async fn keeps_a_deletion_made_while_hashing() {
+        let (_, repo, user) = create_service().await;
+        let service = create_stale_service(&repo, &user);
+        let mut deleted = user.clone();
+        deleted.delete(time::now());
+        repo.save(deleted).await.unwrap();
+
+        let response = service
+            .change_password(create_request(&user, "TestPass123_", "AnotherPass123_"))
+            .await;
+
+        let email = Email::new(user.email()).unwrap();
+        let stored = repo.find_deleted_by_email(email).await.unwrap().unwrap();
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCurrentPassword(_)
+        ));
+        assert!(stored.is_deleted());
+        assert!(stored.is_matching_password("TestPass123_"));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_weak_new_password() {
+        let (service, _, user) = create_service().await;
+
+        let response = service
+            .change_password(create_request(&user, "TestPass123_", "weak"))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidPassword(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_the_same_password() {
+        let (service, _, user) = create_service().await;
+
+        let response = service
+            .change_password(create_request(&user, "TestPass123_", "TestPass123_"))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::EqualPassword(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_changing_another_users_password() {
+        let (service, _, user) = create_service().await;
+        let mut request = create_request(&user, "TestPass123_", "AnotherPass123_");
+        request.requester_id = Id::generate_unique_identifier().to_string();
+
+        let response = service.change_password(request).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::ForbiddenPasswordChange(_)
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    repositories::user_repository::UserRepository,
+    value_objects::{id::Id, role::Permission},
+};
+
+use super::{
+    application_error::ApplicationError,
+    authorizer::Authorizer,
+    dtos::{MessageResponse, UserDeleteRequest},
+    ports::event_publisher::EventPublisher,
+    user_find_service::UserNotFoundError,
+};
+
+/// Permanently deletes any user, unlike account deletion which users do on
+/// themselves.
+pub struct UserDeleteService {
+    user_repository: Arc<dyn UserRepository>,
+    authorizer: Authorizer,
+    event_publisher: Arc<dyn EventPublisher>,
+}
+
+impl UserDeleteService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        event_publisher: Arc<dyn EventPublisher>,
+    ) -> Self {
+        UserDeleteService {
+            authorizer: Authorizer::new(user_repository.clone()),
+            user_repository,
+            event_publisher,
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        request: UserDeleteRequest,
+    ) -> Result<MessageResponse, ApplicationError> {
+        self.authorizer
+            .authorize(&request.requester, Permission::DeleteUsers)
+            .await?;
+
+        let mut user = self
+            .user_repository
+            .find_by_id(Id::from(request.id)?)
+            .await?
+            .ok_or(UserNotFoundError {})?;
+
+        user.delete(time::now());
+        self.user_repository.remove(user.clone()).await?;
+        if let Err(error) = self.event_publisher.publish(user.pull_events()).await {
+            log::error!("could not publish user events: {}", error);
+        }
+
+        Ok(MessageResponse::new("User deleted"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, authorizer::Requester, dtos::UserDeleteRequest,
+            user_delete_service::UserDeleteService,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    #[tokio::test]
+    async fn deletes_an_existing_user() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = create_user();
+        let _ = repo.save(user.clone()).await;
+
+        let response = UserDeleteService::new(repo.clone(), Arc::new(InMemoryEventBus::new()))
+            .delete(UserDeleteRequest {
+                requester: Requester::System,
+                id: user.id(),
+            })
+            .await;
+
+        assert!(response.is_ok());
+        assert!(repo
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn fails_when_user_does_not_exist() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+
+        let response = UserDeleteService::new(repo, Arc::new(InMemoryEventBus::new()))
+            .delete(UserDeleteRequest {
+                requester: Requester::System,
+                id: Id::generate_unique_identifier().to_string(),
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::UserNotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn forbids_users_without_the_permission() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = create_user();
+        let _ = repo.save(user.clone()).await;
+
+        let response = UserDeleteService::new(repo, Arc::new(InMemoryEventBus::new()))
+            .delete(UserDeleteRequest {
+                requester: Requester::User(user.id()),
+                id: user.id(),
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::Forbidden(_)
+        ));
+    }
+
+    fn create_user() -> User {
+        User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        )
+    }
+}
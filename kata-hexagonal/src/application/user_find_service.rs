@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    repositories::user_repository::UserRepository,
+    value_objects::{email::Email, id::Id, role::Permission},
+};
+
+use super::{
+    application_error::ApplicationError,
+    authorizer::{Authorizer, Requester},
+    dtos::{UserFindByEmailRequest, UserFindRequest, UserFindResponse},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("User not found")]
+pub struct UserNotFoundError {}
+
+pub struct UserFindService {
+    user_repository: Arc<dyn UserRepository>,
+    authorizer: Authorizer,
+}
+
+impl UserFindService {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        UserFindService {
+            authorizer: Authorizer::new(user_repository.clone()),
+            user_repository,
+        }
+    }
+
+    /// Users may look themselves up; anyone else needs to be allowed to list
+    /// users. Checked before the lookup, so it does not reveal which ids exist.
+    pub async fn find_by_id(
+        &self,
+        request: UserFindRequest,
+    ) -> Result<UserFindResponse, ApplicationError> {
+        if request.requester != Requester::User(request.id.clone()) {
+            self.authorizer
+                .authorize(&request.requester, Permission::ListUsers)
+                .await?;
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(Id::from(request.id)?)
+            .await?
+            .ok_or(UserNotFoundError {})?;
+
+        Ok(user.to_dto().into())
+    }
+
+    pub async fn find_by_email(
+        &self,
+        request: UserFindByEmailRequest,
+    ) -> Result<UserFindResponse, ApplicationError> {
+        let user = self
+            .user_repository
+            .find_by_email(Email::new(request.email)?)
+            .await?
+            .ok_or(UserNotFoundError {})?;
+
+        Ok(user.to_dto().into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            authorizer::{ForbiddenError, Requester},
+            dtos::{UserFindByEmailRequest, UserFindRequest},
+            user_find_service::UserFindService,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{
+                email::Email,
+                id::Id,
+                password::Password,
+                role::{Permission, Role},
+            },
+        },
+        infrastructure::in_memory_user_repository::InMemoryUserRepository,
+    };
+
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn finds_an_existing_user() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let find_service = UserFindService::new(repo.clone());
+        let user = create_user();
+        let _ = repo.save(user.clone()).await;
+
+        let response = find_service
+            .find_by_id(UserFindRequest {
+                requester: Requester::User(user.id()),
+                id: user.id(),
+            })
+            .await;
+
+        assert!(response.is_ok_and(|r| r.id == user.id() && r.email == "test@example.com"));
+    }
+
+    #[tokio::test]
+    async fn forbids_finding_another_user() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let find_service = UserFindService::new(repo.clone());
+        let user = create_user();
+        let other = create_user_by_email("other@example.com");
+        let _ = repo.save(user.clone()).await;
+        let _ = repo.save(other.clone()).await;
+
+        let response = find_service
+            .find_by_id(UserFindRequest {
+                requester: Requester::User(other.id()),
+                id: user.id(),
+            })
+            .await;
+
+        assert_eq!(
+            response.unwrap_err(),
+            ApplicationError::Forbidden(ForbiddenError(Permission::ListUsers))
+        );
+    }
+
+    #[tokio::test]
+    async fn lets_admins_find_any_user() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let find_service = UserFindService::new(repo.clone());
+        let user = create_user();
+        let mut admin = create_user_by_email("admin@example.com");
+        admin.assign_role(Role::Admin);
+        let _ = repo.save(user.clone()).await;
+        let _ = repo.save(admin.clone()).await;
+
+        let response = find_service
+            .find_by_id(UserFindRequest {
+                requester: Requester::User(admin.id()),
+                id: user.id(),
+            })
+            .await;
+
+        assert!(response.is_ok_and(|r| r.id == user.id()));
+    }
+
+    #[tokio::test]
+    async fn fails_when_user_does_not_exist() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let find_service = UserFindService::new(repo.clone());
+
+        let response = find_service
+            .find_by_id(UserFindRequest {
+                requester: Requester::System,
+                id: Id::generate_unique_identifier().to_string(),
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::UserNotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn finds_an_existing_user_by_email() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let find_service = UserFindService::new(repo.clone());
+        let user = create_user();
+        let _ = repo.save(user.clone()).await;
+
+        let response = find_service
+            .find_by_email(UserFindByEmailRequest {
+                email: "test@example.com".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.id, user.id());
+        assert!(!response.email_verified);
+    }
+
+    fn create_user() -> User {
+        create_user_by_email("test@example.com")
+    }
+
+    fn create_user_by_email(email: &str) -> User {
+        let id = Id::generate_unique_identifier();
+        let email = Email::new(email.to_string()).unwrap();
+        let password = Password::new("TestPass123_".to_string()).unwrap();
+
+        User::new(id, email, password)
+    }
+}
@@ -0,0 +1,280 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    common::time,
+    repositories::{
+        user_query::{SortDirection, UserCursor, UserQuery, UserSort, UserStatus, MAX_PAGE_SIZE},
+        user_repository::UserRepository,
+    },
+    value_objects::role::Permission,
+};
+
+use super::{
+    application_error::ApplicationError,
+    authorizer::Authorizer,
+    dtos::{UserListResponse, UserPageResponse, UserSearchRequest},
+};
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid user query: {0}")]
+pub struct InvalidUserQueryError(pub String);
+
+pub struct UserListService {
+    user_repository: Arc<dyn UserRepository>,
+    authorizer: Authorizer,
+}
+
+impl UserListService {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        UserListService {
+            authorizer: Authorizer::new(user_repository.clone()),
+            user_repository,
+        }
+    }
+
+    pub async fn list(&self) -> Result<UserListResponse, ApplicationError> {
+        let users = self.user_repository.find_all().await?;
+
+        Ok(UserListResponse {
+            users: users.iter().map(|user| user.to_dto().into()).collect(),
+        })
+    }
+
+    pub async fn search(
+        &self,
+        request: UserSearchRequest,
+    ) -> Result<UserPageResponse, ApplicationError> {
+        self.authorizer
+            .authorize(&request.requester, Permission::ListUsers)
+            .await?;
+
+        let page = self.user_repository.find_page(to_query(request)?).await?;
+
+        Ok(UserPageResponse {
+            users: page.users.iter().map(|user| user.to_dto().into()).collect(),
+            next_cursor: page.next.as_ref().map(encode_cursor),
+        })
+    }
+}
+
+fn to_query(request: UserSearchRequest) -> Result<UserQuery, InvalidUserQueryError> {
+    let invalid = |message: &str| InvalidUserQueryError(message.to_string());
+
+    let status = match request.status.as_deref() {
+        None => None,
+        Some("verified") => Some(UserStatus::Verified),
+        Some("unverified") => Some(UserStatus::Unverified),
+        Some("locked") => Some(UserStatus::Locked),
+        Some(_) => return Err(invalid("status must be verified, unverified or locked")),
+    };
+    let sort = match request.sort.as_deref() {
+        None | Some("created_at") => UserSort::CreatedAt,
+        Some("email") => UserSort::Email,
+        Some(_) => return Err(invalid("sort must be created_at or email")),
+    };
+    let direction = match request.order.as_deref() {
+        None | Some("asc") => SortDirection::Ascending,
+        Some("desc") => SortDirection::Descending,
+        Some(_) => return Err(invalid("order must be asc or desc")),
+    };
+    let limit = request.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(InvalidUserQueryError(format!(
+            "limit must be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let after = match request.cursor.as_deref() {
+        None => None,
+        Some(cursor) => Some(decode_cursor(cursor).ok_or_else(|| invalid("malformed cursor"))?),
+    };
+
+    Ok(UserQuery {
+        email_contains: request.email_contains.filter(|part| !part.is_empty()),
+        email_domain: request.email_domain.filter(|domain| !domain.is_empty()),
+        status,
+        sort,
+        direction,
+        after,
+        limit,
+        now: time::now(),
+    })
+}
+
+/// Cursors are opaque to clients: the sort keys of the last user, hex encoded
+/// so they can go in a query string as is.
+fn encode_cursor(cursor: &UserCursor) -> String {
+    format!("{}:{}:{}", cursor.created_at, cursor.id, cursor.email)
+        .bytes()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn decode_cursor(cursor: &str) -> Option<UserCursor> {
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(cursor.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let decoded = String::from_utf8(bytes).ok()?;
+
+    let mut parts = decoded.splitn(3, ':');
+    Some(UserCursor {
+        created_at: parts.next()?.parse().ok()?,
+        id: parts.next()?.to_string(),
+        email: parts.next()?.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            authorizer::Requester,
+            dtos::UserSearchRequest,
+            user_list_service::{decode_cursor, encode_cursor, UserListService},
+        },
+        domain::{
+            entities::user::User,
+            repositories::{user_query::UserCursor, user_repository::UserRepository},
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::in_memory_user_repository::InMemoryUserRepository,
+    };
+
+    #[tokio::test]
+    async fn lists_every_user() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let _ = repo.save(create_user("first@example.com")).await;
+        let _ = repo.save(create_user("second@example.com")).await;
+
+        let response = UserListService::new(repo).list().await.unwrap();
+
+        let mut emails: Vec<String> = response.users.into_iter().map(|u| u.email).collect();
+        emails.sort();
+        assert_eq!(emails, vec!["first@example.com", "second@example.com"]);
+    }
+
+    #[tokio::test]
+    async fn lists_nothing_when_there_are_no_users() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+
+        let response = UserListService::new(repo).list().await.unwrap();
+
+        assert!(response.users.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pages_through_users_for_an_administrator() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let admin = create_admin(&repo).await;
+        let _ = repo.save(create_user("first@example.com")).await;
+        let _ = repo.save(create_user("second@example.com")).await;
+        let service = UserListService::new(repo);
+        let request = UserSearchRequest {
+            sort: Some("email".to_string()),
+            limit: Some(2),
+            ..UserSearchRequest::new(Requester::User(admin.id()))
+        };
+
+        let first = service.search(request.clone()).await.unwrap();
+        let second = service
+            .search(UserSearchRequest {
+                cursor: first.next_cursor.clone(),
+                ..request
+            })
+            .await
+            .unwrap();
+
+        let emails: Vec<String> = first
+            .users
+            .into_iter()
+            .chain(second.users)
+            .map(|u| u.email)
+            .collect();
+        assert_eq!(
+            emails,
+            vec![
+                "admin@example.com",
+                "first@example.com",
+                "second@example.com"
+            ]
+        );
+        assert_eq!(second.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn refuses_users_who_are_not_administrators() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = create_user("user@example.com");
+        let _ = repo.save(user.clone()).await;
+        let service = UserListService::new(repo);
+
+        let response = service
+            .search(UserSearchRequest::new(Requester::User(user.id())))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::Forbidden(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_parameters() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let admin = create_admin(&repo).await;
+        let service = UserListService::new(repo);
+        let request = UserSearchRequest::new(Requester::User(admin.id()));
+
+        for invalid in [
+            UserSearchRequest {
+                limit: Some(0),
+                ..request.clone()
+            },
+            UserSearchRequest {
+                sort: Some("password".to_string()),
+                ..request.clone()
+            },
+            UserSearchRequest {
+                cursor: Some("not a cursor".to_string()),
+                ..request.clone()
+            },
+        ] {
+            assert!(matches!(
+                service.search(invalid).await.unwrap_err(),
+                ApplicationError::InvalidUserQuery(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn round_trips_cursors() {
+        let cursor = UserCursor {
+            created_at: 42,
+            email: "a:b@example.com".to_string(),
+            id: Id::generate_unique_identifier().to_string(),
+        };
+
+        assert_eq!(decode_cursor(&encode_cursor(&cursor)), Some(cursor));
+    }
+
+    async fn create_admin(repo: &InMemoryUserRepository) -> User {
+        let mut admin = create_user("admin@example.com");
+        admin.assign_role(Role::Admin);
+        let _ = repo.save(admin.clone()).await;
+        admin
+    }
+
+    fn create_user(email: &str) -> User {
+        User::new(
+            Id::generate_unique_identifier(),
+            Email::new(email.to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        )
+    }
+}
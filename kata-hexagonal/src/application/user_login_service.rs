@@ -1,73 +1,1186 @@
-use std::{error::Error, sync::Arc};
+use std::sync::{Arc, OnceLock};
 
 use crate::domain::{
-    repositories::user_repository::UserRepository,
-    value_objects::{email::Email, password::Password},
+    common::{hash, time},
+    entities::{
+        login_challenge::{LoginChallenge, LOGIN_CHALLENGE_TIME_TO_LIVE},
+        refresh_token::{RefreshToken, REFRESH_TOKEN_TIME_TO_LIVE},
+        two_factor::TwoFactor,
+        user::User,
+    },
+    repositories::{
+        login_challenge_repository::LoginChallengeRepository,
+        refresh_token_repository::RefreshTokenRepository,
+        two_factor_repository::TwoFactorRepository, user_repository::UserRepository,
+    },
+    value_objects::{email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password},
 };
 
-use super::dtos::{UserLoginRequest, UserLoginResponse};
+use super::{
+    application_error::ApplicationError,
+    blocking::run_blocking,
+    dtos::{
+        LoginChallengeResponse, TwoFactorLoginRequest, UserLoginOutcome, UserLoginRequest,
+        UserLoginResponse,
+    },
+    login_attempts::LoginAttempts,
+    ports::{
+        audit_log::{AuditEntry, AuditEventType, AuditLog},
+        event_publisher::EventPublisher,
+        token_issuer::TokenIssuer,
+    },
+};
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 #[error("Invalid email or password")]
 pub struct InvalidCredentialsError {}
 
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Email address has not been verified")]
+pub struct UnverifiedEmailError {}
+
+/// Deliberately silent about which account is locked and for how long. Emails
+/// without an account are locked out the same way, so it cannot be used to
+/// tell registered emails apart.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Too many failed login attempts, try again later")]
+pub struct AccountLockedError {}
+
+/// The challenge is unknown, expired, used or out of attempts; the login has
+/// to start over with the password.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid or expired login challenge")]
+pub struct InvalidLoginChallengeError {}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid two-factor authentication code")]
+pub struct InvalidTwoFactorCodeError {}
+
 pub struct UserLoginService {
     user_repository: Arc<dyn UserRepository>,
+    token_issuer: Arc<dyn TokenIssuer>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    two_factor_repository: Arc<dyn TwoFactorRepository>,
+    login_challenge_repository: Arc<dyn LoginChallengeRepository>,
+    event_publisher: Arc<dyn EventPublisher>,
+    audit_log: Arc<dyn AuditLog>,
+    require_verified_email: bool,
+    lockout_policy: LockoutPolicy,
+    unknown_email_attempts: LoginAttempts,
 }
 
 impl UserLoginService {
-    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
-        UserLoginService { user_repository }
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        token_issuer: Arc<dyn TokenIssuer>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        two_factor_repository: Arc<dyn TwoFactorRepository>,
+        login_challenge_repository: Arc<dyn LoginChallengeRepository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        audit_log: Arc<dyn AuditLog>,
+    ) -> Self {
+        UserLoginService {
+            user_repository,
+            token_issuer,
+            refresh_token_repository,
+            two_factor_repository,
+            login_challenge_repository,
+            event_publisher,
+            audit_log,
+            require_verified_email: false,
+            lockout_policy: LockoutPolicy::default(),
+            unknown_email_attempts: LoginAttempts::new(),
+        }
+    }
+
+    /// Rejects logins of users whose email address is not verified yet.
+    pub fn require_verified_email(mut self, required: bool) -> Self {
+        self.require_verified_email = required;
+        self
     }
 
+    pub fn with_lockout_policy(mut self, lockout_policy: LockoutPolicy) -> Self {
+        self.lockout_policy = lockout_policy;
+        self
+    }
+
+    /// Checks the password. Users with two-factor authentication get a
+    /// challenge to complete with [`Self::login_second_factor`] instead of
+    /// tokens.
     pub async fn login(
         &self,
         request: UserLoginRequest,
-    ) -> Result<UserLoginResponse, Box<dyn Error>> {
-        let password = Password::new(request.password)?;
-        let optional_user = self
+    ) -> Result<UserLoginOutcome, ApplicationError> {
+        let email = Email::new(request.email.clone())?;
+        let optional_user = self.user_repository.find_by_email(email.clone()).await?;
+
+        let now = time::now();
+        let Some(user) = optional_user else {
+            let email = email.to_string();
+            let locked = self.unknown_email_attempts.is_locked(&email, now);
+            if !locked {
+                // Spend the same hashing time as for a real account.
+                let password = request.password.clone();
+                run_blocking(move || dummy_password().verify(&password)).await;
+                self.unknown_email_attempts
+                    .register_failure(&email, &self.lockout_policy, now);
+            }
+            let entry = AuditEntry::new(
+                AuditEventType::LoginFailed,
+                request.email,
+                request.client_ip,
+                now,
+            );
+            self.record(entry).await;
+            return Err(if locked {
+                AccountLockedError {}.into()
+            } else {
+                InvalidCredentialsError {}.into()
+            });
+        };
+
+        if user.is_locked(now) {
+            self.audit_user(AuditEventType::LoginFailed, &user, &request.client_ip)
+                .await;
+            return Err(AccountLockedError {}.into());
+        }
+
+        let password = request.password.clone();
+        let (mut user, matching) = run_blocking(move || {
+            let matching = user.is_matching_password(&password);
+            (user, matching)
+        })
+        .await;
+        if !matching {
+            self.user_repository
+                .register_failed_login(Id::from(user.id())?, self.lockout_policy, now)
+                .await?;
+            self.audit_user(AuditEventType::LoginFailed, &user, &request.client_ip)
+                .await;
+            return Err(InvalidCredentialsError {}.into());
+        }
+
+        let password = request.password.clone();
+        let previous_hash = user.password();
+        let (mut user, hash_upgraded) = run_blocking(move || {
+            let hash_upgraded = user.upgrade_password_hash(&password);
+            (user, hash_upgraded)
+        })
+        .await;
+        if hash_upgraded {
+            let id = Id::from(user.id())?;
+            if let Err(error) = self
+                .user_repository
+                .upgrade_password_hash(id, previous_hash, user.password())
+                .await
+            {
+                log::warn!("could not upgrade password hash after login: {}", error);
+            }
+        }
+        let verified = !self.require_verified_email || user.is_email_verified();
+        if !verified {
+            if user.failed_login_attempts() > 0 || user.locked_until().is_some() {
+                self.register_successful_login(&mut user).await;
+            }
+            self.audit_user(AuditEventType::LoginFailed, &user, &request.client_ip)
+                .await;
+            return Err(UnverifiedEmailError {}.into());
+        }
+
+        if self.confirmed_two_factor(&user).await?.is_some() {
+            let (challenge, challenge_token) =
+                LoginChallenge::issue(Id::from(user.id())?, LOGIN_CHALLENGE_TIME_TO_LIVE, now);
+            self.login_challenge_repository.save(challenge).await?;
+            return Ok(UserLoginOutcome::SecondFactorRequired(
+                LoginChallengeResponse::new(challenge_token, LOGIN_CHALLENGE_TIME_TO_LIVE),
+            ));
+        }
+
+        let response = self.complete_login(user, now, &request.client_ip).await?;
+        Ok(UserLoginOutcome::Authenticated(response))
+    }
+
+    /// Completes a login challenged for a second factor, with either a TOTP
+    /// code or a recovery code. Each is only accepted once.
+    pub async fn login_second_factor(
+        &self,
+        request: TwoFactorLoginRequest,
+    ) -> Result<UserLoginResponse, ApplicationError> {
+        let now = time::now();
+        let Some(challenge) = self
+            .login_challenge_repository
+            .find_by_hash(&LoginChallenge::hash(&request.challenge_token))
+            .await?
+        else {
+            return Err(InvalidLoginChallengeError {}.into());
+        };
+        // The attempt is used up before the code is checked, so parallel
+        // requests cannot get more guesses than the challenge allows.
+        if challenge.is_expired(now)
+            || !self
+                .login_challenge_repository
+                .claim_attempt(challenge.id())
+                .await?
+        {
+            self.login_challenge_repository
+                .remove(challenge.id())
+                .await?;
+            return Err(InvalidLoginChallengeError {}.into());
+        }
+
+        let user = self
             .user_repository
-            .find_by_email(Email::new(request.email.clone())?)
-            .await
-            .map_err(|_| InvalidCredentialsError {})?;
+            .find_by_id(challenge.user_id().clone())
+            .await?;
+        let two_factor = match &user {
+            Some(user) => self.confirmed_two_factor(user).await?,
+            None => None,
+        };
+        let (Some(user), Some(two_factor)) = (user, two_factor) else {
+            // Deleted or disabled since the password was checked.
+            self.login_challenge_repository
+                .remove(challenge.id())
+                .await?;
+            return Err(InvalidLoginChallengeError {}.into());
+        };
+
+        if user.is_locked(now) {
+            self.audit_user(AuditEventType::LoginFailed, &user, &request.client_ip)
+                .await;
+            return Err(AccountLockedError {}.into());
+        }
+
+        // Wrong codes count towards the lockout like wrong passwords, so new
+        // challenges do not bring new guesses.
+        if !self
+            .accept_second_factor(&two_factor, &request.code, now)
+            .await?
+        {
+            self.user_repository
+                .register_failed_login(Id::from(user.id())?, self.lockout_policy, now)
+                .await?;
+            self.audit_user(AuditEventType::LoginFailed, &user, &request.client_ip)
+                .await;
+            return Err(InvalidTwoFactorCodeError {}.into());
+        }
+
+        // Removing is what redeems the challenge, so a concurrent request
+        // with another valid code cannot log in a second time.
+        if !self
+            .login_challenge_repository
+            .remove(challenge.id())
+            .await?
+        {
+            return Err(InvalidLoginChallengeError {}.into());
+        }
+
+        self.complete_login(user, now, &request.client_ip).await
+    }
+
+    async fn confirmed_two_factor(
+        &self,
+        user: &User,
+    ) -> Result<Option<TwoFactor>, ApplicationError> {
+        let two_factor = self
+            .two_factor_repository
+            .find_by_user_id(&Id::from(user.id())?)
+            .await?;
+        Ok(two_factor.filter(TwoFactor::is_confirmed))
+    }
 
-        if let Some(user) = optional_user {
-            if user.is_matching_password(&password) {
-                return Ok(user.to_dto().into());
+    /// The claim and the consumption are atomic in the repository, which is
+    /// what prevents replaying a code across concurrent requests.
+    async fn accept_second_factor(
+        &self,
+        two_factor: &TwoFactor,
+        code: &str,
+        now: u64,
+    ) -> Result<bool, ApplicationError> {
+        if let Some(step) = two_factor.matching_step(code, now) {
+            return Ok(self
+                .two_factor_repository
+                .claim_step(two_factor.user_id(), step)
+                .await?);
+        }
+
+        Ok(self
+            .two_factor_repository
+            .consume_recovery_code(two_factor.user_id(), &TwoFactor::hash(code))
+            .await?)
+    }
+
+    async fn complete_login(
+        &self,
+        mut user: User,
+        now: u64,
+        client_ip: &Option<String>,
+    ) -> Result<UserLoginResponse, ApplicationError> {
+        user.record_login(now);
+        if !self.register_successful_login(&mut user).await {
+            // Deleted, or the password changed, since it was checked.
+            self.audit_user(AuditEventType::LoginFailed, &user, client_ip)
+                .await;
+            return Err(InvalidCredentialsError {}.into());
+        }
+
+        let token = self.token_issuer.issue(&user)?;
+        let (refresh_token, refresh_plaintext) = RefreshToken::issue(
+            Id::from(user.id())?,
+            REFRESH_TOKEN_TIME_TO_LIVE,
+            time::now(),
+        );
+        self.refresh_token_repository.save(refresh_token).await?;
+        self.audit_user(AuditEventType::LoginSucceeded, &user, client_ip)
+            .await;
+
+        Ok(UserLoginResponse::new(
+            user.to_dto(),
+            token,
+            refresh_plaintext,
+        ))
+    }
+
+    /// Returns false when the user changed under the login. The login goes
+    /// ahead even if the bookkeeping cannot be stored.
+    async fn register_successful_login(&self, user: &mut User) -> bool {
+        match self
+            .user_repository
+            .register_successful_login(user.clone())
+            .await
+        {
+            Ok(false) => return false,
+            Ok(true) => {
+                if let Err(error) = self.event_publisher.publish(user.pull_events()).await {
+                    log::error!("could not publish user events: {}", error);
+                }
             }
+            Err(error) => log::warn!("could not update user after login: {}", error),
         }
+        true
+    }
+
+    async fn audit_user(
+        &self,
+        event_type: AuditEventType,
+        user: &User,
+        client_ip: &Option<String>,
+    ) {
+        let entry = AuditEntry::new(event_type, user.email(), client_ip.clone(), time::now())
+            .for_user(user.id());
+        self.record(entry).await;
+    }
 
-        Err(Box::new(InvalidCredentialsError {}))
+    /// Failing to audit is logged rather than failing the login.
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(error) = self.audit_log.record(entry).await {
+            log::error!("could not write audit entry: {}", error);
+        }
     }
 }
 
+/// A password to verify against when there is no account, so that failing
+/// on an unknown email takes as long as failing on a wrong password.
+pub(crate) fn dummy_password() -> &'static Password {
+    static DUMMY_PASSWORD: OnceLock<Password> = OnceLock::new();
+    DUMMY_PASSWORD.get_or_init(|| Password::from_hash(hash::hash("DummyPass123_")))
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
-        application::{dtos::UserLoginRequest, user_login_service::UserLoginService},
+        application::{
+            application_error::ApplicationError,
+            dtos::{TwoFactorLoginRequest, UserLoginOutcome, UserLoginRequest},
+            ports::{
+                audit_log::{AuditEventType, AuditLog, AuditQuery},
+                token_issuer::TokenIssuer,
+            },
+            user_login_service::UserLoginService,
+        },
         domain::{
-            entities::user::User,
-            repositories::user_repository::UserRepository,
-            value_objects::{email::Email, id::Id, password::Password},
+            common::{time, totp},
+            entities::{
+                login_challenge::{LoginChallenge, MAX_LOGIN_CHALLENGE_ATTEMPTS},
+                two_factor::TwoFactor,
+                user::User,
+            },
+            repositories::{
+                login_challenge_repository::LoginChallengeRepository,
+                repository_error::RepositoryError,
+                two_factor_repository::TwoFactorRepository,
+                user_query::{UserPage, UserQuery},
+                user_repository::UserRepository,
+            },
+            value_objects::{
+                email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password, role::Role,
+            },
         },
-        infrastructure::in_memory_user_repository::InMemoryUserRepository,
+        infrastructure::{
+            in_memory_audit_log::InMemoryAuditLog, in_memory_event_bus::InMemoryEventBus,
+            in_memory_login_challenge_repository::InMemoryLoginChallengeRepository,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_two_factor_repository::InMemoryTwoFactorRepository,
+            in_memory_user_repository::InMemoryUserRepository, jwt_token_issuer::JwtTokenIssuer,
+        },
+        test_support::FailingUserRepository,
     };
 
-    use std::{error::Error, sync::Arc};
+    use async_trait::async_trait;
+    use std::{
+        error::Error,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
 
     #[tokio::test]
     async fn register_with_valid_credentials() {
         let login_request = create_login_request();
 
         let repo = Arc::new(InMemoryUserRepository::new());
-        let login_service = UserLoginService::new(repo.clone());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
 
         let user = create_user().unwrap();
-        repo.save(user).await;
+        let _ = repo.save(user).await;
 
         let response = login_service.login(login_request).await;
 
-        assert!(response.is_ok_and(|r| r.email == "test@example.com".to_string()));
+        assert!(response.is_ok_and(|outcome| matches!(
+            outcome,
+            UserLoginOutcome::Authenticated(login) if login.email == "test@example.com"
+        )));
+    }
+
+    #[tokio::test]
+    async fn issues_an_access_token_for_the_user() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let token_issuer = token_issuer();
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer.clone(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+
+        let UserLoginOutcome::Authenticated(response) =
+            login_service.login(create_login_request()).await.unwrap()
+        else {
+            panic!("expected tokens");
+        };
+
+        assert_eq!(response.token_type, "Bearer");
+        assert_eq!(response.expires_in, 60);
+        assert_eq!(
+            token_issuer.verify(&response.access_token),
+            Ok(Id::from(user.id()).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_password() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+
+        let _ = repo.save(create_user().unwrap()).await;
+
+        let response = login_service
+            .login(UserLoginRequest {
+                email: "test@example.com".to_string(),
+                password: "WrongPass123_".to_string(),
+                client_ip: None,
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCredentials(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn propagates_repository_failures() {
+        let login_service = UserLoginService::new(
+            Arc::new(FailingUserRepository {}),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+
+        let response = login_service.login(create_login_request()).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::Repository(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn upgrades_legacy_password_hash_on_login() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let email = Email::new("test@example.com".to_string()).unwrap();
+
+        let legacy_user = User::new(
+            Id::generate_unique_identifier(),
+            email.clone(),
+            Password::from_hash(
+                "4d886cf89225b666e92b7f5db6ad6c033624c2c55e0313d727bbde29a2d4d809".to_string(),
+            ),
+        );
+        let _ = repo.save(legacy_user).await;
+
+        let response = login_service.login(create_login_request()).await;
+        let stored = repo.find_by_email(email).await.unwrap().unwrap();
+
+        assert!(response.is_ok());
+        assert!(stored.password().starts_with("$argon2id$"));
+        assert!(stored.is_matching_password("TestPass123_"));
+    }
+
+    #[tokio::test]
+    async fn rejects_unverified_users_when_verification_is_required() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+        .require_verified_email(true);
+        let mut user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+
+        let unverified = login_service.login(create_login_request()).await;
+        user.verify_email();
+        let _ = repo.save(user).await;
+        let verified = login_service.login(create_login_request()).await;
+
+        assert!(matches!(
+            unverified.unwrap_err(),
+            ApplicationError::UnverifiedEmail(_)
+        ));
+        assert!(verified.is_ok());
+    }
+
+    #[tokio::test]
+    async fn publishes_user_logged_in_only_for_completed_logins() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let events = Arc::new(InMemoryEventBus::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            events.clone(),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+        .require_verified_email(true);
+        let mut user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+
+        let _ = login_service.login(create_login_request()).await;
+        assert!(events.published().is_empty());
+
+        user.verify_email();
+        let _ = repo.save(user.clone()).await;
+        let _ = login_service.login(create_login_request()).await;
+
+        let published = events.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].name(), "UserLoggedIn");
+        assert_eq!(published[0].user_id(), user.id());
+    }
+
+    #[tokio::test]
+    async fn audits_failed_and_successful_logins() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let audit_log = Arc::new(InMemoryAuditLog::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            audit_log.clone(),
+        );
+        let user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+        let attempt = |email: &str, password: &str| UserLoginRequest {
+            email: email.to_string(),
+            password: password.to_string(),
+            client_ip: Some("203.0.113.7".to_string()),
+        };
+
+        let _ = login_service
+            .login(attempt("nobody@example.com", "TestPass123_"))
+            .await;
+        let _ = login_service
+            .login(attempt("test@example.com", "WrongPass123_"))
+            .await;
+        let _ = login_service
+            .login(attempt("test@example.com", "TestPass123_"))
+            .await;
+
+        let mut entries = audit_log
+            .query(AuditQuery {
+                user_id: None,
+                event_type: None,
+                from: None,
+                until: None,
+                limit: 10,
+            })
+            .await
+            .unwrap();
+        entries.reverse();
+        let recorded: Vec<(AuditEventType, Option<String>)> = entries
+            .iter()
+            .map(|entry| (entry.event_type, entry.user_id.clone()))
+            .collect();
+        assert_eq!(
+            recorded,
+            vec![
+                (AuditEventType::LoginFailed, None),
+                (AuditEventType::LoginFailed, Some(user.id())),
+                (AuditEventType::LoginSucceeded, Some(user.id())),
+            ]
+        );
+        assert!(entries
+            .iter()
+            .all(|entry| entry.ip.as_deref() == Some("203.0.113.7")));
+    }
+
+    #[tokio::test]
+    async fn checks_the_password_before_the_verification_state() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+        .require_verified_email(true);
+        let _ = repo.save(create_user().unwrap()).await;
+
+        let response = login_service
+            .login(UserLoginRequest {
+                email: "test@example.com".to_string(),
+                password: "WrongPass123_".to_string(),
+                client_ip: None,
+            })
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCredentials(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn locks_the_account_after_too_many_failed_attempts() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let policy = LockoutPolicy {
+            max_attempts: 3,
+            ..LockoutPolicy::default()
+        };
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+        .with_lockout_policy(policy);
+        let _ = repo.save(create_user().unwrap()).await;
+        let wrong_password = UserLoginRequest {
+            email: "test@example.com".to_string(),
+            password: "WrongPass123_".to_string(),
+            client_ip: None,
+        };
+
+        for _ in 0..policy.max_attempts {
+            let response = login_service.login(wrong_password.clone()).await;
+            assert!(matches!(
+                response.unwrap_err(),
+                ApplicationError::InvalidCredentials(_)
+            ));
+        }
+        let response = login_service.login(create_login_request()).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::AccountLocked(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unlocks_the_account_after_the_cooldown() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let expired_lock = create_user().unwrap().with_login_attempts(5, Some(1));
+        let _ = repo.save(expired_lock).await;
+
+        let response = login_service.login(create_login_request()).await;
+        let stored = repo
+            .find_by_email(Email::new("test@example.com".to_string()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(response.is_ok());
+        assert_eq!(stored.failed_login_attempts(), 0);
+        assert_eq!(stored.locked_until(), None);
+    }
+
+    #[tokio::test]
+    async fn locks_unknown_emails_like_registered_ones() {
+        let login_service = UserLoginService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+
+        for _ in 0..LockoutPolicy::default().max_attempts {
+            let response = login_service.login(create_login_request()).await;
+            assert!(matches!(
+                response.unwrap_err(),
+                ApplicationError::InvalidCredentials(_)
+            ));
+        }
+        let response = login_service.login(create_login_request()).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::AccountLocked(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn asks_for_a_second_factor_when_two_factor_is_enabled() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let challenges = Arc::new(InMemoryLoginChallengeRepository::new());
+        let events = Arc::new(InMemoryEventBus::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            two_factors.clone(),
+            challenges.clone(),
+            events.clone(),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+        enable_two_factor(&two_factors, &user).await;
+
+        let outcome = login_service.login(create_login_request()).await.unwrap();
+
+        let token = challenge_token(outcome);
+        assert!(challenges
+            .find_by_hash(&LoginChallenge::hash(&token))
+            .await
+            .unwrap()
+            .is_some());
+        assert!(events.published().is_empty());
+    }
+
+    #[tokio::test]
+    async fn completes_the_login_with_a_totp_code_only_once() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            two_factors.clone(),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+        let (secret, _) = enable_two_factor(&two_factors, &user).await;
+        let code = code_at(&secret, time::now());
+
+        let first = challenge_token(login_service.login(create_login_request()).await.unwrap());
+        let response = login_service
+            .login_second_factor(second_factor(&first, &code))
+            .await
+            .unwrap();
+        let reused_challenge = login_service
+            .login_second_factor(second_factor(&first, &code))
+            .await;
+        let second = challenge_token(login_service.login(create_login_request()).await.unwrap());
+        let replayed_code = login_service
+            .login_second_factor(second_factor(&second, &code))
+            .await;
+
+        assert_eq!(response.email, "test@example.com");
+        assert!(matches!(
+            reused_challenge.unwrap_err(),
+            ApplicationError::InvalidLoginChallenge(_)
+        ));
+        assert!(matches!(
+            replayed_code.unwrap_err(),
+            ApplicationError::InvalidTwoFactorCode(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn accepts_each_recovery_code_once() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            two_factors.clone(),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+        let (_, recovery_codes) = enable_two_factor(&two_factors, &user).await;
+
+        let first = challenge_token(login_service.login(create_login_request()).await.unwrap());
+        let accepted = login_service
+            .login_second_factor(second_factor(&first, &recovery_codes[0]))
+            .await;
+        let second = challenge_token(login_service.login(create_login_request()).await.unwrap());
+        let reused = login_service
+            .login_second_factor(second_factor(&second, &recovery_codes[0]))
+            .await;
+
+        assert!(accepted.is_ok());
+        assert!(matches!(
+            reused.unwrap_err(),
+            ApplicationError::InvalidTwoFactorCode(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn drops_the_challenge_after_too_many_wrong_codes() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            two_factors.clone(),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+        let (secret, _) = enable_two_factor(&two_factors, &user).await;
+        let token = challenge_token(login_service.login(create_login_request()).await.unwrap());
+
+        for _ in 0..MAX_LOGIN_CHALLENGE_ATTEMPTS {
+            let response = login_service
+                .login_second_factor(second_factor(&token, "not-a-code"))
+                .await;
+            assert!(matches!(
+                response.unwrap_err(),
+                ApplicationError::InvalidTwoFactorCode(_)
+            ));
+        }
+        let response = login_service
+            .login_second_factor(second_factor(&token, &code_at(&secret, time::now())))
+            .await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidLoginChallenge(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn locks_the_account_after_too_many_wrong_codes_across_challenges() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let policy = LockoutPolicy {
+            max_attempts: 3,
+            ..LockoutPolicy::default()
+        };
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            two_factors.clone(),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+        .with_lockout_policy(policy);
+        let user = create_user().unwrap();
+        let _ = repo.save(user.clone()).await;
+        enable_two_factor(&two_factors, &user).await;
+
+        for _ in 0..policy.max_attempts {
+            let token = challenge_token(login_service.login(create_login_request()).await.unwrap());
+            let response = login_service
+                .login_second_factor(second_factor(&token, "not-a-code"))
+                .await;
+            assert!(matches!(
+                response.unwrap_err(),
+                ApplicationError::InvalidTwoFactorCode(_)
+            ));
+        }
+        let response = login_service.login(create_login_request()).await;
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::AccountLocked(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn clears_failed_attempts_only_once_both_factors_succeed() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            two_factors.clone(),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let user = create_user().unwrap().with_login_attempts(2, None);
+        let _ = repo.save(user.clone()).await;
+        let (_, recovery_codes) = enable_two_factor(&two_factors, &user).await;
+        let failed_attempts = || async {
+            repo.find_by_id(Id::from(user.id()).unwrap())
+                .await
+                .unwrap()
+                .unwrap()
+                .failed_login_attempts()
+        };
+
+        let token = challenge_token(login_service.login(create_login_request()).await.unwrap());
+        let after_password = failed_attempts().await;
+        let _ = login_service
+            .login_second_factor(second_factor(&token, &recovery_codes[0]))
+            .await
+            .unwrap();
+
+        assert_eq!(after_password, 2);
+        assert_eq!(failed_attempts().await, 0);
+    }
+
+    #[tokio::test]
+    async fn keeps_a_password_reset_made_during_the_login() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let new_password = Password::new("AnotherPass123_".to_string()).unwrap();
+        let login_service = UserLoginService::new(
+            Arc::new(ResetOnFind {
+                users: users.clone(),
+                password: Mutex::new(Some(new_password.clone())),
+            }),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let user = create_user().unwrap();
+        let _ = users.save(user.clone()).await;
+
+        let response = login_service.login(create_login_request()).await;
+        let stored = users
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(
+            response.unwrap_err(),
+            ApplicationError::InvalidCredentials(_)
+        ));
+        assert_eq!(stored.password(), new_password.to_string());
+    }
+
+    /// Resets the password right after handing out the user, as a reset
+    /// landing while the login is still checking the old password would.
+    struct ResetOnFind {
+        users: Arc<InMemoryUserRepository>,
+        password: Mutex<Option<Password>>,
+    }
+
+    #[async_trait]
+    impl UserRepository for ResetOnFind {
+        async fn save(&self, user: User) -> Result<(), RepositoryError> {
+            self.users.save(user).await
+        }
+
+        async fn find_by_id(&self, id: Id) -> Result<Option<User>, RepositoryError> {
+            self.users.find_by_id(id).await
+        }
+
+        async fn find_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError> {
+            let found = self.users.find_by_email(email).await?;
+            let password = self.password.lock().unwrap().take();
+            if let (Some(user), Some(password)) = (&found, password) {
+                let mut reset = user.clone();
+                reset.reset_password(password);
+                self.users.save(reset).await?;
+            }
+            Ok(found)
+        }
+
+        async fn find_deleted_by_email(
+            &self,
+            email: Email,
+        ) -> Result<Option<User>, RepositoryError> {
+            self.users.find_deleted_by_email(email).await
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+            self.users.find_all().await
+        }
+
+        async fn find_page(&self, query: UserQuery) -> Result<UserPage, RepositoryError> {
+            self.users.find_page(query).await
+        }
+
+        async fn remove(&self, user: User) -> Result<(), RepositoryError> {
+            self.users.remove(user).await
+        }
+
+        async fn register_failed_login(
+            &self,
+            id: Id,
+            policy: LockoutPolicy,
+            now: u64,
+        ) -> Result<(), RepositoryError> {
+            self.users.register_failed_login(id, policy, now).await
+        }
+
+        async fn register_successful_login(&self, user: User) -> Result<bool, RepositoryError> {
+            self.users.register_successful_login(user).await
+        }
+
+        async fn upgrade_password_hash(
+            &self,
+            id: Id,
+            previous_hash: String,
+            upgraded_hash: String,
+        ) -> Result<bool, RepositoryError> {
+            self.users
+                .upgrade_password_hash(id, previous_hash, upgraded_hash)
+                .await
+        }
+
+        async fn replace_password(
+            &self,
+            user: User,
+            previous_hash: String,
+        ) -> Result<bool, RepositoryError> {
+            self.users.replace_password(user, previous_hash).await
+        }
+
+        async fn mark_deleted(&self, user: User) -> Result<bool, RepositoryError> {
+            self.users.mark_deleted(user).await
+        }
+
+        async fn assign_role(&self, id: Id, role: Role) -> Result<bool, RepositoryError> {
+            self.users.assign_role(id, role).await
+        }
+
+        async fn purge_deleted_before(&self, cutoff: u64) -> Result<u64, RepositoryError> {
+            self.users.purge_deleted_before(cutoff).await
+        }
+    }
+
+    /// Confirmed with the previous step's code, so the current one is unused.
+    async fn enable_two_factor(
+        two_factors: &InMemoryTwoFactorRepository,
+        user: &User,
+    ) -> (Vec<u8>, Vec<String>) {
+        let now = time::now();
+        let mut two_factor = TwoFactor::enroll(Id::from(user.id()).unwrap());
+        let secret = two_factor.secret().to_vec();
+        let recovery_codes = two_factor
+            .confirm(&code_at(&secret, now - totp::PERIOD), now)
+            .unwrap();
+        two_factors.save(two_factor).await.unwrap();
+        (secret, recovery_codes)
+    }
+
+    fn code_at(secret: &[u8], now: u64) -> String {
+        format!("{:06}", totp::code(secret, totp::step(now)))
+    }
+
+    fn challenge_token(outcome: UserLoginOutcome) -> String {
+        match outcome {
+            UserLoginOutcome::SecondFactorRequired(challenge) => challenge.challenge_token,
+            UserLoginOutcome::Authenticated(_) => panic!("expected a second factor challenge"),
+        }
+    }
+
+    fn second_factor(challenge_token: &str, code: &str) -> TwoFactorLoginRequest {
+        TwoFactorLoginRequest {
+            challenge_token: challenge_token.to_string(),
+            code: code.to_string(),
+            client_ip: None,
+        }
     }
 
     fn create_user() -> Result<User, Box<dyn Error>> {
@@ -82,6 +1195,11 @@ mod test {
         UserLoginRequest {
             email: "test@example.com".to_string(),
             password: "TestPass123_".to_string(),
+            client_ip: None,
         }
     }
+
+    fn token_issuer() -> Arc<JwtTokenIssuer> {
+        Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)))
+    }
 }
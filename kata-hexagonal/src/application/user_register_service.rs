@@ -1,35 +1,90 @@
-use std::{error::Error, sync::Arc};
+use std::sync::Arc;
 
 use crate::domain::{
+    common::time,
     entities::user::User,
-    repositories::user_repository::UserRepository,
+    repositories::{repository_error::RepositoryError, user_repository::UserRepository},
     value_objects::{email::Email, id::Id, password::Password},
 };
 
-use super::dtos::{UserRegisterRequest, UserRegisterResponse};
+use super::{
+    application_error::ApplicationError,
+    blocking::run_blocking,
+    dtos::{UserRegisterRequest, UserRegisterResponse},
+    email_verification_sender::EmailVerificationSender,
+    ports::{
+        audit_log::{AuditEntry, AuditEventType, AuditLog},
+        event_publisher::EventPublisher,
+    },
+};
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 #[error("User already exists with this email")]
 pub struct ExistingUserError {}
 
+/// The email still belongs to a soft-deleted account, which keeps it until
+/// the account is purged.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("The account with this email is pending deletion, restore it instead")]
+pub struct AccountPendingDeletionError {}
+
 pub struct UserRegisterService {
     user_repository: Arc<dyn UserRepository>,
+    verification_sender: EmailVerificationSender,
+    event_publisher: Arc<dyn EventPublisher>,
+    audit_log: Arc<dyn AuditLog>,
 }
 
 impl UserRegisterService {
-    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
-        UserRegisterService { user_repository }
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        verification_sender: EmailVerificationSender,
+        event_publisher: Arc<dyn EventPublisher>,
+        audit_log: Arc<dyn AuditLog>,
+    ) -> Self {
+        UserRegisterService {
+            user_repository,
+            verification_sender,
+            event_publisher,
+            audit_log,
+        }
     }
 
     pub async fn register(
         &self,
         request: UserRegisterRequest,
-    ) -> Result<UserRegisterResponse, Box<dyn Error>> {
+    ) -> Result<UserRegisterResponse, ApplicationError> {
         self.ensure_user_does_not_exist(&request).await?;
-        let user = self.create_user(request)?;
+        let client_ip = request.client_ip.clone();
+        let mut user = self.create_user(request).await?;
         let dto = user.to_dto();
 
-        self.user_repository.save(user).await?;
+        self.user_repository
+            .save(user.clone())
+            .await
+            .map_err(|error| match error {
+                RepositoryError::ConstraintViolation(_) => ExistingUserError {}.into(),
+                other => ApplicationError::from(other),
+            })?;
+        if let Err(error) = self.event_publisher.publish(user.pull_events()).await {
+            log::error!("could not publish user events: {}", error);
+        }
+        let entry = AuditEntry::new(
+            AuditEventType::Registered,
+            user.email(),
+            client_ip,
+            time::now(),
+        )
+        .for_user(user.id());
+        if let Err(error) = self.audit_log.record(entry).await {
+            log::error!("could not write audit entry: {}", error);
+        }
+
+        // The account exists at this point; a failed send can be retried
+        // through the resend endpoint rather than failing registration.
+        if let Err(error) = self.verification_sender.send(&user).await {
+            log::error!("could not issue verification token: {}", error);
+        }
 
         Ok(dto.into())
     }
@@ -37,44 +92,127 @@ impl UserRegisterService {
     async fn ensure_user_does_not_exist(
         &self,
         request: &UserRegisterRequest,
-    ) -> Result<(), Box<dyn Error>> {
-        let user_found = self
+    ) -> Result<(), ApplicationError> {
+        let email = Email::new(request.email.clone())?;
+        if self
             .user_repository
-            .find_by_email(Email::new(request.email.clone())?)
-            .await;
-
-        if let Ok(Some(_)) = user_found {
-            Err(Box::new(ExistingUserError {}))
-        } else {
-            Ok(())
+            .find_by_email(email.clone())
+            .await?
+            .is_some()
+        {
+            return Err(ExistingUserError {}.into());
+        }
+        if self
+            .user_repository
+            .find_deleted_by_email(email)
+            .await?
+            .is_some()
+        {
+            return Err(AccountPendingDeletionError {}.into());
         }
+        Ok(())
     }
 
-    fn create_user(&self, request: UserRegisterRequest) -> Result<User, Box<dyn Error>> {
+    async fn create_user(&self, request: UserRegisterRequest) -> Result<User, ApplicationError> {
         let id = Id::generate_unique_identifier();
         let email = Email::new(request.email)?;
-        let password = Password::new(request.password)?;
-        Ok(User::new(id, email, password))
+        let password = run_blocking(move || Password::new(request.password)).await?;
+        Ok(User::register(id, email, password))
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
-        domain::{repositories::user_repository::UserRepository, value_objects::email::Email},
-        infrastructure::in_memory_user_repository::InMemoryUserRepository,
+        application::{
+            application_error::ApplicationError,
+            email_verification_sender::EmailVerificationSender,
+            ports::audit_log::{AuditEventType, AuditLog, AuditQuery},
+        },
+        domain::{
+            common::time,
+            repositories::{repository_error::RepositoryError, user_repository::UserRepository},
+            value_objects::email::Email,
+        },
+        infrastructure::{
+            in_memory_audit_log::InMemoryAuditLog,
+            in_memory_email_verification_token_repository::InMemoryEmailVerificationTokenRepository,
+            in_memory_event_bus::InMemoryEventBus, in_memory_mailer::InMemoryMailer,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+        test_support::FailingUserRepository,
     };
 
     use std::sync::Arc;
 
     use super::{UserRegisterRequest, UserRegisterService};
 
+    #[tokio::test]
+    async fn publishes_user_registered_once_saved() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let events = Arc::new(InMemoryEventBus::new());
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(Arc::new(InMemoryMailer::new())),
+            events.clone(),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+
+        let response = register_service
+            .register(create_register_request())
+            .await
+            .unwrap();
+
+        let published = events.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].name(), "UserRegistered");
+        assert_eq!(published[0].user_id(), response.id);
+    }
+
+    #[tokio::test]
+    async fn audits_the_registration() {
+        let audit_log = Arc::new(InMemoryAuditLog::new());
+        let register_service = UserRegisterService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            verification_sender(Arc::new(InMemoryMailer::new())),
+            Arc::new(InMemoryEventBus::new()),
+            audit_log.clone(),
+        );
+
+        let response = register_service
+            .register(UserRegisterRequest {
+                client_ip: Some("203.0.113.7".to_string()),
+                ..create_register_request()
+            })
+            .await
+            .unwrap();
+
+        let entries = audit_log
+            .query(AuditQuery {
+                user_id: Some(response.id.clone()),
+                event_type: Some(AuditEventType::Registered),
+                from: None,
+                until: None,
+                limit: 10,
+            })
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].email, response.email);
+        assert_eq!(entries[0].ip.as_deref(), Some("203.0.113.7"));
+    }
+
     #[tokio::test]
     async fn register_with_valid_credentials() {
         let register_request = create_register_request();
 
         let repo = Arc::new(InMemoryUserRepository::new());
-        let register_service = UserRegisterService::new(repo.clone());
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(Arc::new(InMemoryMailer::new())),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
 
         let _ = register_service.register(register_request).await;
 
@@ -93,18 +231,105 @@ mod test {
         let register_request = create_register_request();
 
         let repo = Arc::new(InMemoryUserRepository::new());
-        let register_service = UserRegisterService::new(repo.clone());
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(Arc::new(InMemoryMailer::new())),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
 
         let _ = register_service.register(register_request.clone()).await;
         let res = register_service.register(register_request.clone()).await;
 
-        assert!(res.is_err());
+        assert!(matches!(
+            res.unwrap_err(),
+            ApplicationError::ExistingUser(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn asks_to_restore_an_account_pending_deletion() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(Arc::new(InMemoryMailer::new())),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let _ = register_service.register(create_register_request()).await;
+        let mut deleted = repo
+            .find_by_email(Email::new("test@example.com".to_string()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        deleted.delete(time::now());
+        let _ = repo.save(deleted).await;
+
+        let res = register_service.register(create_register_request()).await;
+
+        assert!(matches!(
+            res.unwrap_err(),
+            ApplicationError::AccountPendingDeletion(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn propagates_repository_failures_instead_of_registering() {
+        let register_service = UserRegisterService::new(
+            Arc::new(FailingUserRepository {}),
+            verification_sender(Arc::new(InMemoryMailer::new())),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+
+        let res = register_service.register(create_register_request()).await;
+
+        assert_eq!(
+            res.unwrap_err(),
+            ApplicationError::Repository(RepositoryError::Unavailable(
+                "database is down".to_string()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn registers_an_unverified_user_and_emails_a_verification_link() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mailer = Arc::new(InMemoryMailer::new());
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(mailer.clone()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+
+        let _ = register_service.register(create_register_request()).await;
+
+        let user = repo
+            .find_by_email(Email::new("test@example.com".to_string()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        let sent = mailer.sent();
+        assert!(!user.is_email_verified());
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "test@example.com");
+        assert!(sent[0].body.contains("http://localhost/verify?token="));
+    }
+
+    fn verification_sender(mailer: Arc<InMemoryMailer>) -> EmailVerificationSender {
+        EmailVerificationSender::new(
+            Arc::new(InMemoryEmailVerificationTokenRepository::new()),
+            mailer,
+            "http://localhost/verify".to_string(),
+        )
     }
 
     fn create_register_request() -> UserRegisterRequest {
         UserRegisterRequest {
             email: "test@example.com".to_string(),
             password: "TestPass123_".to_string(),
+            client_ip: None,
         }
     }
 }
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    repositories::{repository_error::RepositoryError, user_repository::UserRepository},
+    value_objects::{
+        id::Id,
+        role::{Permission, Role},
+    },
+};
+
+use super::{
+    application_error::ApplicationError,
+    authorizer::Authorizer,
+    dtos::{UserFindResponse, UserRoleAssignRequest},
+    user_find_service::UserNotFoundError,
+};
+
+/// Someone has to be left able to assign roles.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Cannot demote the last admin")]
+pub struct LastAdminError {}
+
+pub struct UserRoleAssignService {
+    user_repository: Arc<dyn UserRepository>,
+    authorizer: Authorizer,
+}
+
+impl UserRoleAssignService {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        UserRoleAssignService {
+            authorizer: Authorizer::new(user_repository.clone()),
+            user_repository,
+        }
+    }
+
+    pub async fn assign(
+        &self,
+        request: UserRoleAssignRequest,
+    ) -> Result<UserFindResponse, ApplicationError> {
+        self.authorizer
+            .authorize(&request.requester, Permission::ManageRoles)
+            .await?;
+
+        let role: Role = request.role.parse()?;
+        let id = Id::from(request.user_id)?;
+        let mut user = self
+            .user_repository
+            .find_by_id(id.clone())
+            .await?
+            .ok_or(UserNotFoundError {})?;
+
+        match self.user_repository.assign_role(id, role).await {
+            Ok(true) => user.assign_role(role),
+            Ok(false) => return Err(LastAdminError {}.into()),
+            Err(RepositoryError::NotFound) => return Err(UserNotFoundError {}.into()),
+            Err(error) => return Err(error.into()),
+        }
+
+        Ok(user.to_dto().into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError, authorizer::Requester,
+            dtos::UserRoleAssignRequest, user_role_assign_service::UserRoleAssignService,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::in_memory_user_repository::InMemoryUserRepository,
+    };
+
+    async fn assign(requester_role: Role, role: &str) -> Result<Role, ApplicationError> {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut requester = create_user("admin@example.com");
+        requester.assign_role(requester_role);
+        let user = create_user("test@example.com");
+        let _ = repo.save(requester.clone()).await;
+        let _ = repo.save(user.clone()).await;
+
+        UserRoleAssignService::new(repo.clone())
+            .assign(UserRoleAssignRequest {
+                requester: Requester::User(requester.id()),
+                user_id: user.id(),
+                role: role.to_string(),
+            })
+            .await?;
+
+        Ok(repo
+            .find_by_id(Id::from(user.id()).unwrap())
+            .await
+            .unwrap()
+            .unwrap()
+            .role())
+    }
+
+    #[tokio::test]
+    async fn lets_an_admin_promote_a_user() {
+        assert_eq!(assign(Role::Admin, "admin").await, Ok(Role::Admin));
+    }
+
+    #[tokio::test]
+    async fn refuses_to_let_the_last_admin_demote_themselves() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut admin = create_user("admin@example.com");
+        admin.assign_role(Role::Admin);
+        let _ = repo.save(admin.clone()).await;
+
+        let result = UserRoleAssignService::new(repo.clone())
+            .assign(UserRoleAssignRequest {
+                requester: Requester::User(admin.id()),
+                user_id: admin.id(),
+                role: "user".to_string(),
+            })
+            .await;
+
+        assert!(matches!(result, Err(ApplicationError::LastAdmin(_))));
+        assert_eq!(
+            repo.find_by_id(Id::from(admin.id()).unwrap())
+                .await
+                .unwrap()
+                .unwrap()
+                .role(),
+            Role::Admin
+        );
+    }
+
+    #[tokio::test]
+    async fn lets_an_admin_demote_another_admin() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut admin = create_user("admin@example.com");
+        admin.assign_role(Role::Admin);
+        let mut other = create_user("other@example.com");
+        other.assign_role(Role::Admin);
+        let _ = repo.save(admin.clone()).await;
+        let _ = repo.save(other.clone()).await;
+
+        let response = UserRoleAssignService::new(repo.clone())
+            .assign(UserRoleAssignRequest {
+                requester: Requester::User(admin.id()),
+                user_id: other.id(),
+                role: "user".to_string(),
+            })
+            .await;
+
+        assert_eq!(response.unwrap().role, "user");
+    }
+
+    #[tokio::test]
+    async fn forbids_regular_users_from_changing_roles() {
+        assert!(matches!(
+            assign(Role::User, "admin").await,
+            Err(ApplicationError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_roles() {
+        assert!(matches!(
+            assign(Role::Admin, "root").await,
+            Err(ApplicationError::InvalidRole(_))
+        ));
+    }
+
+    fn create_user(email: &str) -> User {
+        User::new(
+            Id::generate_unique_identifier(),
+            Email::new(email.to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        )
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    io::{self, IsTerminal},
+    process::ExitCode,
+    sync::Arc,
+};
+
+use clap::Parser;
+use kata_hexagonal::infrastructure::{
+    admin_cli::{Admin, AdminCli, Command, EXIT_UNAVAILABLE, EXIT_USAGE},
+    config::Config,
+    container::Repositories,
+    maildir_mailer::MaildirMailer,
+};
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = AdminCli::parse();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let config = match Config::load(&cli.server_cli()) {
+        Ok(config) => config,
+        Err(error) => return fail(error, EXIT_USAGE),
+    };
+    let repositories = match Repositories::open(&config.database).await {
+        Ok(repositories) => repositories,
+        Err(error) => {
+            return fail(
+                format!("could not open database: {:#}", error),
+                EXIT_UNAVAILABLE,
+            )
+        }
+    };
+    let repositories = match repositories.with_audit_log(&config.audit) {
+        Ok(repositories) => repositories,
+        Err(error) => {
+            return fail(
+                format!("could not open audit log: {}", error),
+                EXIT_UNAVAILABLE,
+            )
+        }
+    };
+    let mailer = match MaildirMailer::new(&config.mail.maildir, &config.mail.from) {
+        Ok(mailer) => mailer,
+        Err(error) => {
+            return fail(
+                format!("could not open maildir: {}", error),
+                EXIT_UNAVAILABLE,
+            )
+        }
+    };
+    let admin = Admin::new(&config, repositories, Arc::new(mailer));
+
+    let stdin = io::stdin();
+    if matches!(cli.command, Command::Create { .. }) && stdin.is_terminal() {
+        eprint!("Password: ");
+    }
+    let output = match admin.run(&cli.command, &mut stdin.lock()).await {
+        Ok(output) => output.render(cli.format),
+        Err(error) => return fail(&error, error.exit_code()),
+    };
+
+    match &cli.command {
+        Command::Export { output: Some(path) } => {
+            if let Err(error) = std::fs::write(path, output + "\n") {
+                return fail(
+                    format!("could not write {}: {}", path.display(), error),
+                    EXIT_UNAVAILABLE,
+                );
+            }
+        }
+        _ => println!("{}", output),
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn fail(error: impl std::fmt::Display, code: u8) -> ExitCode {
+    eprintln!("error: {}", error);
+    ExitCode::from(code)
+}
@@ -1,8 +1,92 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2,
+};
 use sha2::{Digest, Sha256};
 
+/// Hashes `data` with Argon2id and a random salt, returning a PHC string.
 pub fn hash(data: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(data.as_bytes(), &salt)
+        .expect("Argon2 hashing with default params cannot fail")
+        .to_string()
+}
+
+/// Checks `data` against a PHC string or a legacy unsalted SHA-256 hex digest.
+pub fn verify(data: &str, hash: &str) -> bool {
+    if is_legacy(hash) {
+        return constant_time_eq(digest(data).as_bytes(), hash.as_bytes());
+    }
+
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(data.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Returns true when `hash` is not an Argon2id PHC string and should be upgraded.
+pub fn needs_rehash(hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => parsed.algorithm != Algorithm::Argon2id.ident(),
+        Err(_) => true,
+    }
+}
+
+/// Unsalted SHA-256 hex digest. Only suitable for high-entropy secrets such as
+/// generated tokens, never for user-chosen passwords.
+pub fn digest(data: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(data);
     let result = hasher.finalize();
     format!("{:x}", result) // Convert the hash result to a hex string
 }
+
+fn is_legacy(hash: &str) -> bool {
+    hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hash, needs_rehash, verify};
+
+    #[test]
+    fn produces_salted_argon2id_hashes() {
+        let a_hash = hash("SecurePass123_");
+        let another_hash = hash("SecurePass123_");
+
+        assert!(a_hash.starts_with("$argon2id$"));
+        assert_ne!(a_hash, another_hash);
+    }
+
+    #[test]
+    fn verifies_argon2id_hashes() {
+        let hashed = hash("SecurePass123_");
+
+        assert!(verify("SecurePass123_", &hashed));
+        assert!(!verify("SecurePass12_", &hashed));
+    }
+
+    #[test]
+    fn verifies_legacy_sha256_hashes() {
+        let legacy = "5e884898da28047151d0e56f8dc6292773603d0d6aabbdd62a11ef721d1542d8";
+
+        assert!(verify("password", legacy));
+        assert!(!verify("Password", legacy));
+    }
+
+    #[test]
+    fn flags_legacy_hashes_for_rehash() {
+        assert!(needs_rehash(
+            "5e884898da28047151d0e56f8dc6292773603d0d6aabbdd62a11ef721d1542d8"
+        ));
+        assert!(needs_rehash("not a hash"));
+        assert!(!needs_rehash(&hash("SecurePass123_")));
+    }
+}
@@ -1,2 +1,5 @@
 pub mod hash;
+pub mod time;
+pub mod token;
+pub mod totp;
 pub mod uuid;
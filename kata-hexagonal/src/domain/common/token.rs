@@ -0,0 +1,10 @@
+use rand::{distributions::Alphanumeric, Rng};
+
+/// Generates an opaque, URL-safe random token with 256 bits of entropy.
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(43)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,112 @@
+use crate::domain::{
+    common::{hash, token},
+    value_objects::id::Id,
+};
+
+pub const LOGIN_CHALLENGE_TIME_TO_LIVE: u64 = 5 * 60;
+/// Wrong codes allowed before the password has to be entered again.
+pub const MAX_LOGIN_CHALLENGE_ATTEMPTS: u32 = 5;
+
+/// Issued once the password of a user with two-factor authentication is
+/// verified, and exchanged for tokens together with a second factor.
+/// Only its digest is stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginChallenge {
+    id: Id,
+    user_id: Id,
+    token_hash: String,
+    expires_at: u64,
+    attempts: u32,
+}
+
+impl LoginChallenge {
+    pub fn new(id: Id, user_id: Id, token_hash: String, expires_at: u64, attempts: u32) -> Self {
+        LoginChallenge {
+            id,
+            user_id,
+            token_hash,
+            expires_at,
+            attempts,
+        }
+    }
+
+    /// Returns the challenge and its plaintext value, which is never stored.
+    pub fn issue(user_id: Id, time_to_live: u64, now: u64) -> (LoginChallenge, String) {
+        let plaintext = token::generate_token();
+        let challenge = LoginChallenge {
+            id: Id::generate_unique_identifier(),
+            user_id,
+            token_hash: Self::hash(&plaintext),
+            expires_at: now + time_to_live,
+            attempts: 0,
+        };
+
+        (challenge, plaintext)
+    }
+
+    pub fn hash(plaintext: &str) -> String {
+        hash::digest(plaintext)
+    }
+
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    pub fn user_id(&self) -> &Id {
+        &self.user_id
+    }
+
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        self.expires_at
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Uses up one attempt at a code. Returns false when none are left.
+    pub fn claim_attempt(&mut self) -> bool {
+        if self.attempts >= MAX_LOGIN_CHALLENGE_ATTEMPTS {
+            return false;
+        }
+        self.attempts += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::value_objects::id::Id;
+
+    use super::{LoginChallenge, MAX_LOGIN_CHALLENGE_ATTEMPTS};
+
+    #[test]
+    fn stores_only_the_digest_of_the_challenge() {
+        let (challenge, plaintext) = LoginChallenge::issue(Id::generate_unique_identifier(), 60, 0);
+
+        assert_ne!(challenge.token_hash(), plaintext);
+        assert_eq!(challenge.token_hash(), LoginChallenge::hash(&plaintext));
+        assert!(!challenge.is_expired(59));
+        assert!(challenge.is_expired(60));
+    }
+
+    #[test]
+    fn runs_out_of_attempts() {
+        let (mut challenge, _) = LoginChallenge::issue(Id::generate_unique_identifier(), 60, 0);
+
+        for _ in 0..MAX_LOGIN_CHALLENGE_ATTEMPTS {
+            assert!(challenge.claim_attempt());
+        }
+
+        assert!(!challenge.claim_attempt());
+        assert_eq!(challenge.attempts(), MAX_LOGIN_CHALLENGE_ATTEMPTS);
+    }
+}
@@ -1 +1,6 @@
+pub mod email_verification_token;
+pub mod login_challenge;
+pub mod password_reset_token;
+pub mod refresh_token;
+pub mod two_factor;
 pub mod user;
@@ -0,0 +1,99 @@
+use crate::domain::{
+    common::{hash, token},
+    value_objects::id::Id,
+};
+
+pub const PASSWORD_RESET_TOKEN_TIME_TO_LIVE: u64 = 60 * 60;
+
+/// A single-use token emailed to a user who forgot their password.
+/// Only its digest is stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordResetToken {
+    id: Id,
+    user_id: Id,
+    token_hash: String,
+    expires_at: u64,
+    used: bool,
+}
+
+impl PasswordResetToken {
+    pub fn new(id: Id, user_id: Id, token_hash: String, expires_at: u64, used: bool) -> Self {
+        PasswordResetToken {
+            id,
+            user_id,
+            token_hash,
+            expires_at,
+            used,
+        }
+    }
+
+    /// Returns the token and its plaintext value, which is never stored.
+    pub fn issue(user_id: Id, time_to_live: u64, now: u64) -> (PasswordResetToken, String) {
+        let plaintext = token::generate_token();
+        let reset_token = PasswordResetToken {
+            id: Id::generate_unique_identifier(),
+            user_id,
+            token_hash: Self::hash(&plaintext),
+            expires_at: now + time_to_live,
+            used: false,
+        };
+
+        (reset_token, plaintext)
+    }
+
+    pub fn hash(plaintext: &str) -> String {
+        hash::digest(plaintext)
+    }
+
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    pub fn user_id(&self) -> &Id {
+        &self.user_id
+    }
+
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        self.expires_at
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn mark_used(&mut self) {
+        self.used = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::value_objects::id::Id;
+
+    use super::PasswordResetToken;
+
+    #[test]
+    fn stores_only_the_digest_of_the_issued_token() {
+        let (token, plaintext) =
+            PasswordResetToken::issue(Id::generate_unique_identifier(), 60, 100);
+
+        assert_ne!(token.token_hash(), plaintext);
+        assert_eq!(token.token_hash(), PasswordResetToken::hash(&plaintext));
+    }
+
+    #[test]
+    fn expires_after_its_time_to_live() {
+        let (token, _) = PasswordResetToken::issue(Id::generate_unique_identifier(), 60, 100);
+
+        assert!(!token.is_expired(159));
+        assert!(token.is_expired(160));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::domain::{
+    common::{hash, token},
+    value_objects::id::Id,
+};
+
+pub const REFRESH_TOKEN_TIME_TO_LIVE: u64 = 30 * 24 * 60 * 60;
+
+/// A single-use, opaque refresh token. Only its digest is stored; tokens
+/// issued by rotating one another share a family so a reused token can
+/// revoke every descendant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshToken {
+    id: Id,
+    family_id: Id,
+    user_id: Id,
+    token_hash: String,
+    expires_at: u64,
+    used: bool,
+    revoked: bool,
+}
+
+impl RefreshToken {
+    pub fn new(
+        id: Id,
+        family_id: Id,
+        user_id: Id,
+        token_hash: String,
+        expires_at: u64,
+        used: bool,
+        revoked: bool,
+    ) -> Self {
+        RefreshToken {
+            id,
+            family_id,
+            user_id,
+            token_hash,
+            expires_at,
+            used,
+            revoked,
+        }
+    }
+
+    /// Starts a new token family. Returns the token and its plaintext value,
+    /// which is never stored and must be handed to the client.
+    pub fn issue(user_id: Id, time_to_live: u64, now: u64) -> (RefreshToken, String) {
+        Self::issue_in_family(Id::generate_unique_identifier(), user_id, time_to_live, now)
+    }
+
+    /// Issues the successor of this token in the same family.
+    pub fn rotate(&self, time_to_live: u64, now: u64) -> (RefreshToken, String) {
+        Self::issue_in_family(
+            self.family_id.clone(),
+            self.user_id.clone(),
+            time_to_live,
+            now,
+        )
+    }
+
+    fn issue_in_family(
+        family_id: Id,
+        user_id: Id,
+        time_to_live: u64,
+        now: u64,
+    ) -> (RefreshToken, String) {
+        let plaintext = token::generate_token();
+        let refresh_token = RefreshToken {
+            id: Id::generate_unique_identifier(),
+            family_id,
+            user_id,
+            token_hash: Self::hash(&plaintext),
+            expires_at: now + time_to_live,
+            used: false,
+            revoked: false,
+        };
+
+        (refresh_token, plaintext)
+    }
+
+    pub fn hash(plaintext: &str) -> String {
+        hash::digest(plaintext)
+    }
+
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    pub fn family_id(&self) -> &Id {
+        &self.family_id
+    }
+
+    pub fn user_id(&self) -> &Id {
+        &self.user_id
+    }
+
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        self.expires_at
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn mark_used(&mut self) {
+        self.used = true;
+    }
+
+    pub fn revoke(&mut self) {
+        self.revoked = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::value_objects::id::Id;
+
+    use super::RefreshToken;
+
+    #[test]
+    fn stores_only_the_digest_of_the_issued_token() {
+        let (token, plaintext) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 100);
+
+        assert_ne!(token.token_hash(), plaintext);
+        assert_eq!(token.token_hash(), RefreshToken::hash(&plaintext));
+    }
+
+    #[test]
+    fn expires_after_its_time_to_live() {
+        let (token, _) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 100);
+
+        assert!(!token.is_expired(159));
+        assert!(token.is_expired(160));
+    }
+
+    #[test]
+    fn rotation_keeps_family_and_user() {
+        let (token, plaintext) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 100);
+
+        let (rotated, rotated_plaintext) = token.rotate(60, 120);
+
+        assert_eq!(rotated.family_id(), token.family_id());
+        assert_eq!(rotated.user_id(), token.user_id());
+        assert_ne!(rotated.id(), token.id());
+        assert_ne!(rotated_plaintext, plaintext);
+        assert_eq!(rotated.expires_at(), 180);
+    }
+}
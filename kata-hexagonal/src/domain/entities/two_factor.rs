@@ -0,0 +1,205 @@
+use rand::Rng;
+
+use crate::domain::{
+    common::{hash, totp},
+    value_objects::id::Id,
+};
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghijkmnpqrstuvwxyz23456789";
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// A user's TOTP enrollment. It only protects logins once confirmed with a
+/// code, which proves the authenticator holds the secret. Recovery codes are
+/// only stored as digests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwoFactor {
+    user_id: Id,
+    secret: Vec<u8>,
+    confirmed: bool,
+    last_used_step: Option<u64>,
+    recovery_code_hashes: Vec<String>,
+}
+
+impl TwoFactor {
+    pub fn new(
+        user_id: Id,
+        secret: Vec<u8>,
+        confirmed: bool,
+        last_used_step: Option<u64>,
+        recovery_code_hashes: Vec<String>,
+    ) -> Self {
+        TwoFactor {
+            user_id,
+            secret,
+            confirmed,
+            last_used_step,
+            recovery_code_hashes,
+        }
+    }
+
+    /// Starts an enrollment with a fresh secret.
+    pub fn enroll(user_id: Id) -> Self {
+        TwoFactor::new(user_id, totp::generate_secret(), false, None, Vec::new())
+    }
+
+    pub fn user_id(&self) -> &Id {
+        &self.user_id
+    }
+
+    pub fn secret(&self) -> &[u8] {
+        &self.secret
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed
+    }
+
+    pub fn last_used_step(&self) -> Option<u64> {
+        self.last_used_step
+    }
+
+    pub fn recovery_code_hashes(&self) -> &[String] {
+        &self.recovery_code_hashes
+    }
+
+    pub fn provisioning_uri(&self, issuer: &str, account: &str) -> String {
+        totp::provisioning_uri(&self.secret, issuer, account)
+    }
+
+    /// The step of the window around `now` that `code` was generated for,
+    /// unless that step or a later one was already used.
+    pub fn matching_step(&self, code: &str, now: u64) -> Option<u64> {
+        let code = totp::parse_code(code)?;
+        let current = totp::step(now);
+
+        (current.saturating_sub(totp::SKEW)..=current + totp::SKEW)
+            .filter(|step| self.last_used_step.is_none_or(|used| *step > used))
+            .find(|step| totp::code(&self.secret, *step) == code)
+    }
+
+    /// Records `step` as used, so its code cannot be replayed. Returns false
+    /// when it or a later step already was.
+    pub fn claim_step(&mut self, step: u64) -> bool {
+        if self.last_used_step.is_some_and(|used| step <= used) {
+            return false;
+        }
+        self.last_used_step = Some(step);
+        true
+    }
+
+    /// Turns the enrollment on if `code` is valid, returning the plaintext
+    /// recovery codes, which are never available again.
+    pub fn confirm(&mut self, code: &str, now: u64) -> Option<Vec<String>> {
+        let step = self.matching_step(code, now)?;
+        self.claim_step(step);
+        self.confirmed = true;
+
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| generate_recovery_code())
+            .collect();
+        self.recovery_code_hashes = codes.iter().map(|code| Self::hash(code)).collect();
+        Some(codes)
+    }
+
+    /// Removes the recovery code with this digest, returning false when it is
+    /// not one of them.
+    pub fn remove_recovery_code(&mut self, code_hash: &str) -> bool {
+        let before = self.recovery_code_hashes.len();
+        self.recovery_code_hashes
+            .retain(|stored| stored != code_hash);
+        self.recovery_code_hashes.len() < before
+    }
+
+    /// Digest of a recovery code, ignoring case and the separating dash.
+    pub fn hash(recovery_code: &str) -> String {
+        let normalized: String = recovery_code
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        hash::digest(&normalized)
+    }
+}
+
+/// Ten characters without look-alikes, e.g. `k3f9q-x7m2p`.
+fn generate_recovery_code() -> String {
+    let mut rng = rand::thread_rng();
+    let characters: String = (0..RECOVERY_CODE_LENGTH)
+        .map(|_| char::from(RECOVERY_CODE_ALPHABET[rng.gen_range(0..RECOVERY_CODE_ALPHABET.len())]))
+        .collect();
+    format!(
+        "{}-{}",
+        &characters[..RECOVERY_CODE_LENGTH / 2],
+        &characters[RECOVERY_CODE_LENGTH / 2..]
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::{common::totp, value_objects::id::Id};
+
+    use super::{TwoFactor, RECOVERY_CODE_COUNT};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn code_at(two_factor: &TwoFactor, now: u64) -> String {
+        format!("{:06}", totp::code(two_factor.secret(), totp::step(now)))
+    }
+
+    #[test]
+    fn accepts_codes_within_the_skew_window() {
+        let two_factor = TwoFactor::enroll(Id::generate_unique_identifier());
+
+        for drift in [-30, 0, 30] {
+            let code = code_at(&two_factor, NOW.saturating_add_signed(drift));
+            assert!(two_factor.matching_step(&code, NOW).is_some(), "{}", drift);
+        }
+        let stale = code_at(&two_factor, NOW - 90);
+        assert_eq!(two_factor.matching_step(&stale, NOW), None);
+    }
+
+    #[test]
+    fn rejects_a_replayed_code() {
+        let mut two_factor = TwoFactor::enroll(Id::generate_unique_identifier());
+        let code = code_at(&two_factor, NOW);
+
+        let step = two_factor.matching_step(&code, NOW).unwrap();
+        assert!(two_factor.claim_step(step));
+
+        assert!(!two_factor.claim_step(step));
+        assert_eq!(two_factor.matching_step(&code, NOW), None);
+        assert_eq!(two_factor.matching_step(&code, NOW + 10), None);
+    }
+
+    #[test]
+    fn confirms_with_a_valid_code_and_issues_recovery_codes() {
+        let mut two_factor = TwoFactor::enroll(Id::generate_unique_identifier());
+
+        assert_eq!(two_factor.confirm("000000x", NOW), None);
+        assert!(!two_factor.is_confirmed());
+
+        let codes = two_factor.confirm(&code_at(&two_factor, NOW), NOW).unwrap();
+
+        assert!(two_factor.is_confirmed());
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(two_factor.recovery_code_hashes().len(), RECOVERY_CODE_COUNT);
+        assert!(!two_factor.recovery_code_hashes().contains(&codes[0]));
+    }
+
+    #[test]
+    fn recovery_codes_are_single_use() {
+        let mut two_factor = TwoFactor::enroll(Id::generate_unique_identifier());
+        let codes = two_factor.confirm(&code_at(&two_factor, NOW), NOW).unwrap();
+
+        let typed = codes[0].to_uppercase().replace('-', "");
+
+        assert!(two_factor.remove_recovery_code(&TwoFactor::hash(&typed)));
+        assert!(!two_factor.remove_recovery_code(&TwoFactor::hash(&codes[0])));
+        assert_eq!(
+            two_factor.recovery_code_hashes().len(),
+            RECOVERY_CODE_COUNT - 1
+        );
+    }
+}
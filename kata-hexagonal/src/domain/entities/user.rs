@@ -1,34 +1,116 @@
-use crate::domain::value_objects::{
-    email::{self, Email},
-    id::Id,
-    password::Password,
+use crate::domain::{
+    common::time,
+    events::user_event::UserEvent,
+    value_objects::{
+        email::Email,
+        id::Id,
+        lockout_policy::LockoutPolicy,
+        password::{Password, PasswordError},
+        role::{Permission, Role},
+    },
 };
 
+/// How long a deleted account can still be restored before it is purged.
+pub const ACCOUNT_RESTORE_WINDOW: u64 = 30 * 24 * 60 * 60;
+
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 #[error("New password must be different")]
 pub struct EqualPasswordError {}
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ChangePasswordError {
+    #[error(transparent)]
+    EqualPassword(#[from] EqualPasswordError),
+    #[error(transparent)]
+    InvalidPassword(#[from] PasswordError),
+}
+
 #[derive(Debug, Clone)]
 pub struct User {
     id: Id,
     email: Email,
     password: Password,
+    email_verified: bool,
+    failed_login_attempts: u32,
+    locked_until: Option<u64>,
+    deleted_at: Option<u64>,
+    created_at: u64,
+    role: Role,
+    events: Vec<UserEvent>,
 }
 
 pub struct UserDto {
     pub id: String,
     pub email: String,
+    pub email_verified: bool,
+    pub created_at: u64,
+    pub role: String,
 }
 
 impl User {
+    /// Creates a user, timestamped now, whose email address has not been
+    /// verified yet.
     pub fn new(id: Id, email: Email, password: Password) -> Self {
         User {
             id,
             email,
             password,
+            email_verified: false,
+            failed_login_attempts: 0,
+            locked_until: None,
+            deleted_at: None,
+            created_at: time::now(),
+            role: Role::default(),
+            events: Vec::new(),
         }
     }
 
+    /// Creates a user signing up, recording `UserRegistered`.
+    pub fn register(id: Id, email: Email, password: Password) -> Self {
+        let mut user = User::new(id, email, password);
+        user.record(UserEvent::UserRegistered {
+            user_id: user.id(),
+            email: user.email(),
+            occurred_at: user.created_at,
+        });
+        user
+    }
+
+    /// Restores the creation time of a persisted user.
+    pub fn with_created_at(mut self, created_at: u64) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    /// Restores the login attempt state of a persisted user.
+    pub fn with_login_attempts(
+        mut self,
+        failed_login_attempts: u32,
+        locked_until: Option<u64>,
+    ) -> Self {
+        self.failed_login_attempts = failed_login_attempts;
+        self.locked_until = locked_until;
+        self
+    }
+
+    /// Restores the deletion state of a persisted user.
+    pub fn with_deleted_at(mut self, deleted_at: Option<u64>) -> Self {
+        self.deleted_at = deleted_at;
+        self
+    }
+
+    /// Restores the password of a persisted user.
+    pub fn with_password(mut self, password: Password) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// Restores the role of a persisted user.
+    pub fn with_role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+
     pub fn id(&self) -> String {
         self.id.to_string()
     }
@@ -41,25 +123,157 @@ impl User {
         self.password.to_string()
     }
 
-    pub fn change_password(&mut self, new_password: Password) -> Result<(), EqualPasswordError> {
-        self.ensure_is_different_password(&new_password)?;
-        self.password = new_password;
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    pub fn assign_role(&mut self, role: Role) {
+        self.role = role;
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.role.can(permission)
+    }
+
+    pub fn is_email_verified(&self) -> bool {
+        self.email_verified
+    }
+
+    pub fn verify_email(&mut self) {
+        self.email_verified = true;
+    }
+
+    pub fn failed_login_attempts(&self) -> u32 {
+        self.failed_login_attempts
+    }
+
+    pub fn locked_until(&self) -> Option<u64> {
+        self.locked_until
+    }
+
+    pub fn is_locked(&self, now: u64) -> bool {
+        self.locked_until.is_some_and(|until| now < until)
+    }
+
+    pub fn register_failed_login(&mut self, policy: &LockoutPolicy, now: u64) {
+        self.failed_login_attempts = self.failed_login_attempts.saturating_add(1);
+        if let Some(duration) = policy.lockout_duration(self.failed_login_attempts) {
+            self.locked_until = Some(now + duration);
+        }
+    }
+
+    /// Clears the failure count. Returns true when there was anything to clear.
+    pub fn register_successful_login(&mut self) -> bool {
+        let changed = self.failed_login_attempts > 0 || self.locked_until.is_some();
+        self.failed_login_attempts = 0;
+        self.locked_until = None;
+        changed
+    }
+
+    pub fn record_login(&mut self, now: u64) {
+        self.record(UserEvent::UserLoggedIn {
+            user_id: self.id(),
+            occurred_at: now,
+        });
+    }
+
+    pub fn deleted_at(&self) -> Option<u64> {
+        self.deleted_at
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Soft-deletes the account. It stays restorable for `ACCOUNT_RESTORE_WINDOW`.
+    pub fn delete(&mut self, now: u64) {
+        self.deleted_at = Some(now);
+        self.record(UserEvent::UserDeleted {
+            user_id: self.id(),
+            occurred_at: now,
+        });
+    }
+
+    pub fn can_be_restored(&self, now: u64) -> bool {
+        self.deleted_at
+            .is_some_and(|deleted_at| now < deleted_at + ACCOUNT_RESTORE_WINDOW)
+    }
+
+    pub fn restore(&mut self) {
+        self.deleted_at = None;
+    }
+
+    pub fn change_password(&mut self, plaintext: String) -> Result<(), ChangePasswordError> {
+        self.ensure_is_different_password(&plaintext)?;
+        self.password = Password::new(plaintext)?;
+        self.record_password_change();
         Ok(())
     }
 
-    fn ensure_is_different_password(
-        &mut self,
-        new_password: &Password,
-    ) -> Result<(), EqualPasswordError> {
-        if self.is_matching_password(new_password) {
+    /// Replaces the password without checking the previous one, for flows
+    /// where the user proved their identity some other way. Lifts any
+    /// lockout, since the failed attempts were against the old password.
+    pub fn reset_password(&mut self, password: Password) {
+        self.password = password;
+        self.failed_login_attempts = 0;
+        self.locked_until = None;
+        self.record_password_change();
+    }
+
+    /// Records `PasswordResetRequested`, on which a reset link is emailed.
+    pub fn request_password_reset(&mut self, now: u64) {
+        self.record(UserEvent::PasswordResetRequested {
+            user_id: self.id(),
+            email: self.email(),
+            occurred_at: now,
+        });
+    }
+
+    fn record_password_change(&mut self) {
+        self.record(UserEvent::PasswordChanged {
+            user_id: self.id(),
+            occurred_at: time::now(),
+        });
+    }
+
+    fn record(&mut self, event: UserEvent) {
+        self.events.push(event);
+    }
+
+    /// Events recorded since the user was loaded, oldest first.
+    pub fn events(&self) -> &[UserEvent] {
+        &self.events
+    }
+
+    /// Takes the recorded events, leaving none behind.
+    pub fn pull_events(&mut self) -> Vec<UserEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn ensure_is_different_password(&self, plaintext: &str) -> Result<(), EqualPasswordError> {
+        if self.is_matching_password(plaintext) {
             Err(EqualPasswordError {})
         } else {
             Ok(())
         }
     }
 
-    pub fn is_matching_password(&self, password: &Password) -> bool {
-        self.password == *password
+    pub fn is_matching_password(&self, plaintext: &str) -> bool {
+        self.password.verify(plaintext)
+    }
+
+    /// Re-hashes a legacy password with the current algorithm.
+    /// Returns true when the stored hash changed and the user should be saved.
+    pub fn upgrade_password_hash(&mut self, plaintext: &str) -> bool {
+        if !self.password.needs_rehash() || !self.is_matching_password(plaintext) {
+            return false;
+        }
+        self.password = Password::rehash(plaintext);
+        true
     }
 
     pub fn is_matching_id(&self, id: &Id) -> bool {
@@ -74,6 +288,9 @@ impl User {
         UserDto {
             id: self.id.to_string(),
             email: self.email.to_string(),
+            email_verified: self.email_verified,
+            created_at: self.created_at,
+            role: self.role.to_string(),
         }
     }
 }
@@ -88,8 +305,15 @@ impl Eq for User {}
 #[cfg(test)]
 mod test {
     use crate::domain::{
-        entities::user::EqualPasswordError,
-        value_objects::{email::Email, id::Id, password::Password},
+        entities::user::{ChangePasswordError, EqualPasswordError, ACCOUNT_RESTORE_WINDOW},
+        events::user_event::UserEvent,
+        value_objects::{
+            email::Email,
+            id::Id,
+            lockout_policy::LockoutPolicy,
+            password::Password,
+            role::{Permission, Role},
+        },
     };
 
     use super::User;
@@ -98,11 +322,22 @@ mod test {
     fn changes_password_when_different_provided() {
         let mut user = create_user();
 
-        let _ = user.change_password(Password::new("AnotherSafePass123_".to_string()).unwrap());
+        let _ = user.change_password("AnotherSafePass123_".to_string());
+
+        assert!(user.is_matching_password("AnotherSafePass123_"))
+    }
+
+    #[test]
+    fn records_a_password_change() {
+        let mut user = create_user();
+
+        let _ = user.change_password("AnotherSafePass123_".to_string());
 
-        assert!(
-            user.is_matching_password(&Password::new("AnotherSafePass123_".to_string()).unwrap())
-        )
+        assert!(matches!(
+            user.pull_events()[..],
+            [UserEvent::PasswordChanged { .. }]
+        ));
+        assert!(user.events().is_empty());
     }
 
     #[test]
@@ -110,9 +345,137 @@ mod test {
         let mut user = create_user();
 
         assert_eq!(
-            user.change_password(Password::new("SafePass123_".to_string()).unwrap()),
-            Err(EqualPasswordError {})
+            user.change_password("SafePass123_".to_string()),
+            Err(ChangePasswordError::EqualPassword(EqualPasswordError {}))
+        );
+        assert!(user.events().is_empty());
+    }
+
+    #[test]
+    fn does_not_allow_to_change_with_weak_password() {
+        let mut user = create_user();
+
+        assert!(matches!(
+            user.change_password("weak".to_string()),
+            Err(ChangePasswordError::InvalidPassword(_))
+        ));
+        assert!(user.is_matching_password("SafePass123_"));
+    }
+
+    #[test]
+    fn upgrades_legacy_password_hash() {
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::from_hash(
+                "5e884898da28047151d0e56f8dc6292773603d0d6aabbdd62a11ef721d1542d8".to_string(),
+            ),
+        );
+
+        assert!(user.upgrade_password_hash("password"));
+        assert!(user.password().starts_with("$argon2id$"));
+        assert!(user.is_matching_password("password"));
+    }
+
+    #[test]
+    fn does_not_upgrade_current_password_hash() {
+        let mut user = create_user();
+        let hash = user.password();
+
+        assert!(!user.upgrade_password_hash("SafePass123_"));
+        assert_eq!(user.password(), hash);
+    }
+
+    #[test]
+    fn can_be_restored_only_within_the_window() {
+        let mut user = create_user();
+        assert!(!user.can_be_restored(100));
+
+        user.delete(100);
+        assert!(user.is_deleted());
+        assert!(user.can_be_restored(100 + ACCOUNT_RESTORE_WINDOW - 1));
+        assert!(!user.can_be_restored(100 + ACCOUNT_RESTORE_WINDOW));
+
+        user.restore();
+        assert!(!user.is_deleted());
+    }
+
+    #[test]
+    fn records_registration_login_and_deletion() {
+        let mut user = User::register(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("SafePass123_".to_string()).unwrap(),
         );
+        user.record_login(200);
+        user.delete(300);
+
+        let names: Vec<&str> = user.events().iter().map(|e| e.name()).collect();
+        assert_eq!(names, vec!["UserRegistered", "UserLoggedIn", "UserDeleted"]);
+        assert!(user.events().iter().all(|e| e.user_id() == user.id()));
+    }
+
+    #[test]
+    fn gains_permissions_from_its_role() {
+        let mut user = create_user();
+        assert_eq!(user.role(), Role::User);
+        assert!(!user.can(Permission::DeleteUsers));
+
+        user.assign_role(Role::Admin);
+        assert!(user.can(Permission::DeleteUsers));
+    }
+
+    #[test]
+    fn starts_with_an_unverified_email() {
+        let mut user = create_user();
+
+        assert!(!user.is_email_verified());
+        user.verify_email();
+        assert!(user.is_email_verified());
+    }
+
+    #[test]
+    fn locks_after_too_many_failed_logins_until_the_cooldown_ends() {
+        let mut user = create_user();
+        let policy = LockoutPolicy::default();
+
+        for _ in 0..policy.max_attempts - 1 {
+            user.register_failed_login(&policy, 100);
+        }
+        assert!(!user.is_locked(100));
+
+        user.register_failed_login(&policy, 100);
+        assert!(user.is_locked(100));
+        assert!(user.is_locked(159));
+        assert!(!user.is_locked(160));
+    }
+
+    #[test]
+    fn successful_login_resets_failed_attempts() {
+        let mut user = create_user();
+        let policy = LockoutPolicy::default();
+        for _ in 0..policy.max_attempts {
+            user.register_failed_login(&policy, 100);
+        }
+
+        assert!(user.register_successful_login());
+        assert_eq!(user.failed_login_attempts(), 0);
+        assert!(!user.is_locked(100));
+        assert!(!user.register_successful_login());
+    }
+
+    #[test]
+    fn resetting_the_password_lifts_the_lockout() {
+        let mut user = create_user();
+        let policy = LockoutPolicy::default();
+        for _ in 0..policy.max_attempts {
+            user.register_failed_login(&policy, 100);
+        }
+
+        user.reset_password(Password::new("NewPass123_".to_string()).unwrap());
+
+        assert_eq!(user.failed_login_attempts(), 0);
+        assert_eq!(user.locked_until(), None);
     }
 
     fn create_user() -> User {
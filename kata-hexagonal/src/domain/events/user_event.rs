@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+/// Something that happened to a user, recorded by `User` and published by
+/// the use cases once the user is saved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum UserEvent {
+    UserRegistered {
+        user_id: String,
+        email: String,
+        occurred_at: u64,
+    },
+    PasswordChanged {
+        user_id: String,
+        occurred_at: u64,
+    },
+    UserLoggedIn {
+        user_id: String,
+        occurred_at: u64,
+    },
+    UserDeleted {
+        user_id: String,
+        occurred_at: u64,
+    },
+    PasswordResetRequested {
+        user_id: String,
+        email: String,
+        occurred_at: u64,
+    },
+}
+
+impl UserEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserEvent::UserRegistered { .. } => "UserRegistered",
+            UserEvent::PasswordChanged { .. } => "PasswordChanged",
+            UserEvent::UserLoggedIn { .. } => "UserLoggedIn",
+            UserEvent::UserDeleted { .. } => "UserDeleted",
+            UserEvent::PasswordResetRequested { .. } => "PasswordResetRequested",
+        }
+    }
+
+    pub fn user_id(&self) -> &str {
+        match self {
+            UserEvent::UserRegistered { user_id, .. }
+            | UserEvent::PasswordChanged { user_id, .. }
+            | UserEvent::UserLoggedIn { user_id, .. }
+            | UserEvent::UserDeleted { user_id, .. }
+            | UserEvent::PasswordResetRequested { user_id, .. } => user_id,
+        }
+    }
+
+    pub fn occurred_at(&self) -> u64 {
+        match self {
+            UserEvent::UserRegistered { occurred_at, .. }
+            | UserEvent::PasswordChanged { occurred_at, .. }
+            | UserEvent::UserLoggedIn { occurred_at, .. }
+            | UserEvent::UserDeleted { occurred_at, .. }
+            | UserEvent::PasswordResetRequested { occurred_at, .. } => *occurred_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::UserEvent;
+
+    #[test]
+    fn round_trips_through_json_tagged_with_its_name() {
+        let event = UserEvent::UserRegistered {
+            user_id: "id".to_string(),
+            email: "test@example.com".to_string(),
+            occurred_at: 42,
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(json["type"], event.name());
+        assert_eq!(serde_json::from_value::<UserEvent>(json).unwrap(), event);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod common;
 pub mod entities;
+pub mod events;
 pub mod repositories;
 pub mod value_objects;
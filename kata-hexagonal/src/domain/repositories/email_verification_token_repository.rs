@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+use crate::domain::entities::email_verification_token::EmailVerificationToken;
+use crate::domain::repositories::repository_error::RepositoryError;
+use crate::domain::value_objects::id::Id;
+
+#[async_trait]
+pub trait EmailVerificationTokenRepository: Send + Sync {
+    async fn save(&self, token: EmailVerificationToken) -> Result<(), RepositoryError>;
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<EmailVerificationToken>, RepositoryError>;
+    /// Returns the most recently issued token of the user, used or not.
+    async fn find_latest_for_user(
+        &self,
+        user_id: &Id,
+    ) -> Result<Option<EmailVerificationToken>, RepositoryError>;
+    /// Atomically flags the token as used. Returns false when it already was.
+    async fn mark_as_used(&self, token: &EmailVerificationToken) -> Result<bool, RepositoryError>;
+    /// Marks every outstanding token of the user as used.
+    async fn invalidate_for_user(&self, user_id: &Id) -> Result<(), RepositoryError>;
+}
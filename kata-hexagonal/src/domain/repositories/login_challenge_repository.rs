@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+
+use crate::domain::{
+    entities::login_challenge::LoginChallenge, repositories::repository_error::RepositoryError,
+    value_objects::id::Id,
+};
+
+#[async_trait]
+pub trait LoginChallengeRepository: Send + Sync {
+    /// Stores a new challenge. Attempts are only counted by `claim_attempt`,
+    /// so a removed challenge is never written back.
+    async fn save(&self, challenge: LoginChallenge) -> Result<(), RepositoryError>;
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<LoginChallenge>, RepositoryError>;
+    /// Atomically uses up one attempt at a code, before the code is checked,
+    /// so parallel requests cannot guess more than `MAX_LOGIN_CHALLENGE_ATTEMPTS`
+    /// times. Returns false when none are left or the challenge is gone.
+    async fn claim_attempt(&self, id: &Id) -> Result<bool, RepositoryError>;
+    /// Atomically deletes the challenge. Returns false when it already was,
+    /// so a challenge completes at most once.
+    async fn remove(&self, id: &Id) -> Result<bool, RepositoryError>;
+}
@@ -1 +1,9 @@
+pub mod email_verification_token_repository;
+pub mod login_challenge_repository;
+pub mod outbox_repository;
+pub mod password_reset_token_repository;
+pub mod refresh_token_repository;
+pub mod repository_error;
+pub mod two_factor_repository;
+pub mod user_query;
 pub mod user_repository;
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+
+use crate::domain::{
+    events::user_event::UserEvent, repositories::repository_error::RepositoryError,
+};
+
+/// An event stored alongside the change that recorded it, waiting to be
+/// delivered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxEntry {
+    pub id: u64,
+    pub event: UserEvent,
+    pub attempts: u32,
+}
+
+/// Storage that writes recorded events in the same transaction as the user,
+/// so an event is never lost nor published for a change that was not saved.
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// Undelivered entries that failed fewer than `max_attempts` times,
+    /// oldest first. An entry whose payload cannot be read is parked as
+    /// failed `max_attempts` times instead, since retrying cannot fix it.
+    async fn find_pending(
+        &self,
+        limit: usize,
+        max_attempts: u32,
+    ) -> Result<Vec<OutboxEntry>, RepositoryError>;
+    async fn mark_dispatched(&self, id: u64, now: u64) -> Result<(), RepositoryError>;
+    async fn record_failure(&self, id: u64, error: String) -> Result<(), RepositoryError>;
+    /// Deletes entries dispatched before `cutoff`, returning how many.
+    async fn prune_dispatched_before(&self, cutoff: u64) -> Result<u64, RepositoryError>;
+}
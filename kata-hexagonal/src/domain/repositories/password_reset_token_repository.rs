@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::domain::entities::password_reset_token::PasswordResetToken;
+use crate::domain::repositories::repository_error::RepositoryError;
+use crate::domain::value_objects::id::Id;
+
+#[async_trait]
+pub trait PasswordResetTokenRepository: Send + Sync {
+    async fn save(&self, token: PasswordResetToken) -> Result<(), RepositoryError>;
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PasswordResetToken>, RepositoryError>;
+    /// Atomically flags the token as used. Returns false when it already was.
+    async fn mark_as_used(&self, token: &PasswordResetToken) -> Result<bool, RepositoryError>;
+    /// Marks every outstanding token of the user as used.
+    async fn invalidate_for_user(&self, user_id: &Id) -> Result<(), RepositoryError>;
+}
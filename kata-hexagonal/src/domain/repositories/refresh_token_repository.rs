@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::domain::entities::refresh_token::RefreshToken;
+use crate::domain::repositories::repository_error::RepositoryError;
+use crate::domain::value_objects::id::Id;
+
+#[async_trait]
+pub trait RefreshTokenRepository: Send + Sync {
+    async fn save(&self, token: RefreshToken) -> Result<(), RepositoryError>;
+    async fn find_by_hash(&self, token_hash: &str)
+        -> Result<Option<RefreshToken>, RepositoryError>;
+    /// Atomically flags the token as used. Returns false when it already was,
+    /// which means the token is being replayed.
+    async fn mark_as_used(&self, token: &RefreshToken) -> Result<bool, RepositoryError>;
+    async fn revoke_family(&self, family_id: &Id) -> Result<(), RepositoryError>;
+    /// Revokes every token issued to the user, signing out all their sessions.
+    async fn revoke_all_for_user(&self, user_id: &Id) -> Result<(), RepositoryError>;
+}
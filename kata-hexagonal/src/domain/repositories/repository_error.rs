@@ -0,0 +1,11 @@
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryError {
+    #[error("Repository unavailable: {0}")]
+    Unavailable(String),
+    #[error("Constraint violated: {0}")]
+    ConstraintViolation(String),
+    #[error("Corrupt data: {0}")]
+    CorruptData(String),
+    #[error("Entity not found")]
+    NotFound,
+}
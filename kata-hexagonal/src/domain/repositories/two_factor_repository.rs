@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use crate::domain::{
+    entities::two_factor::TwoFactor, repositories::repository_error::RepositoryError,
+    value_objects::id::Id,
+};
+
+#[async_trait]
+pub trait TwoFactorRepository: Send + Sync {
+    /// Inserts or replaces the enrollment of the user, recovery codes included.
+    async fn save(&self, two_factor: TwoFactor) -> Result<(), RepositoryError>;
+    async fn find_by_user_id(&self, user_id: &Id) -> Result<Option<TwoFactor>, RepositoryError>;
+    /// Atomically records `step` as used. Returns false when it or a later
+    /// step already was, i.e. the code is being replayed.
+    async fn claim_step(&self, user_id: &Id, step: u64) -> Result<bool, RepositoryError>;
+    /// Atomically removes a recovery code. Returns false when it was not there.
+    async fn consume_recovery_code(
+        &self,
+        user_id: &Id,
+        code_hash: &str,
+    ) -> Result<bool, RepositoryError>;
+}
@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+
+use crate::domain::entities::user::User;
+
+pub const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UserSort {
+    #[default]
+    CreatedAt,
+    Email,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserStatus {
+    Verified,
+    Unverified,
+    /// Locked out after too many failed logins at the query's `now`.
+    Locked,
+}
+
+/// Position right after the last user of a page. Holds every sort key so the
+/// next page can be found without reading the previous one again; the id
+/// breaks ties between equal keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserCursor {
+    pub created_at: u64,
+    pub email: String,
+    pub id: String,
+}
+
+impl UserCursor {
+    pub fn after(user: &User) -> Self {
+        UserCursor {
+            created_at: user.created_at(),
+            email: user.email(),
+            id: user.id(),
+        }
+    }
+}
+
+/// A page of non-deleted users. Filters on the email are case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserQuery {
+    pub email_contains: Option<String>,
+    /// Matches the part after the `@`, e.g. `example.com`.
+    pub email_domain: Option<String>,
+    pub status: Option<UserStatus>,
+    pub sort: UserSort,
+    pub direction: SortDirection,
+    pub after: Option<UserCursor>,
+    pub limit: usize,
+    pub now: u64,
+}
+
+impl Default for UserQuery {
+    fn default() -> Self {
+        UserQuery {
+            email_contains: None,
+            email_domain: None,
+            status: None,
+            sort: UserSort::default(),
+            direction: SortDirection::default(),
+            after: None,
+            limit: 20,
+            now: 0,
+        }
+    }
+}
+
+impl UserQuery {
+    /// Whether `user` passes the filters, ignoring the cursor.
+    pub fn matches(&self, user: &User) -> bool {
+        let email = user.email().to_lowercase();
+        let domain = email.rsplit_once('@').map(|(_, domain)| domain);
+
+        !user.is_deleted()
+            && self
+                .email_contains
+                .as_ref()
+                .is_none_or(|part| email.contains(&part.to_lowercase()))
+            && self
+                .email_domain
+                .as_ref()
+                .is_none_or(|wanted| domain == Some(wanted.to_lowercase().as_str()))
+            && self.status.is_none_or(|status| match status {
+                UserStatus::Verified => user.is_email_verified(),
+                UserStatus::Unverified => !user.is_email_verified(),
+                UserStatus::Locked => user.is_locked(self.now),
+            })
+    }
+
+    /// Orders two positions the way the query sorts users.
+    pub fn order(&self, a: &UserCursor, b: &UserCursor) -> Ordering {
+        let ordering = match self.sort {
+            UserSort::CreatedAt => a.created_at.cmp(&b.created_at),
+            UserSort::Email => a.email.cmp(&b.email),
+        }
+        .then_with(|| a.id.cmp(&b.id));
+
+        match self.direction {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserPage {
+    pub users: Vec<User>,
+    /// Set when more users match after this page.
+    pub next: Option<UserCursor>,
+}
+
+impl UserPage {
+    /// Builds a page from up to `limit + 1` sorted users, the extra one only
+    /// telling whether there is a next page.
+    pub fn from_overfetched(mut users: Vec<User>, limit: usize) -> Self {
+        let next = if users.len() > limit {
+            users.truncate(limit);
+            users.last().map(UserCursor::after)
+        } else {
+            None
+        };
+        UserPage { users, next }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+
+    use crate::domain::{
+        entities::user::User,
+        value_objects::{email::Email, id::Id, password::Password},
+    };
+
+    use super::{SortDirection, UserCursor, UserPage, UserQuery, UserSort, UserStatus};
+
+    fn create_user(email: &str) -> User {
+        User::new(
+            Id::generate_unique_identifier(),
+            Email::new(email.to_string()).unwrap(),
+            Password::from_hash("hash".to_string()),
+        )
+    }
+
+    #[test]
+    fn filters_by_email_part_domain_and_status() {
+        let mut user = create_user("Jane.Doe@Example.com");
+        user.verify_email();
+        let query = |query: UserQuery| query.matches(&user);
+
+        assert!(query(UserQuery {
+            email_contains: Some("doe@".to_string()),
+            email_domain: Some("example.com".to_string()),
+            status: Some(UserStatus::Verified),
+            ..UserQuery::default()
+        }));
+        assert!(!query(UserQuery {
+            email_domain: Some("ample.com".to_string()),
+            ..UserQuery::default()
+        }));
+        assert!(!query(UserQuery {
+            status: Some(UserStatus::Locked),
+            ..UserQuery::default()
+        }));
+    }
+
+    #[test]
+    fn breaks_ties_on_the_id_in_the_sort_direction() {
+        let first = UserCursor {
+            created_at: 1,
+            email: "b@example.com".to_string(),
+            id: "1".to_string(),
+        };
+        let second = UserCursor {
+            created_at: 1,
+            email: "a@example.com".to_string(),
+            id: "2".to_string(),
+        };
+        let query = UserQuery::default();
+
+        assert_eq!(query.order(&first, &second), Ordering::Less);
+        assert_eq!(
+            UserQuery {
+                sort: UserSort::Email,
+                ..query.clone()
+            }
+            .order(&first, &second),
+            Ordering::Greater
+        );
+        assert_eq!(
+            UserQuery {
+                direction: SortDirection::Descending,
+                ..query
+            }
+            .order(&first, &second),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn points_the_next_cursor_at_the_last_user_when_more_remain() {
+        let users = vec![
+            create_user("a@example.com"),
+            create_user("b@example.com"),
+            create_user("c@example.com"),
+        ];
+
+        let page = UserPage::from_overfetched(users.clone(), 2);
+        assert_eq!(page.users.len(), 2);
+        assert_eq!(page.next, Some(UserCursor::after(&users[1])));
+
+        let last = UserPage::from_overfetched(users, 3);
+        assert_eq!(last.next, None);
+    }
+}
@@ -1,12 +1,65 @@
 use async_trait::async_trait;
 
 use crate::domain::entities::user::User;
+use crate::domain::repositories::repository_error::RepositoryError;
+use crate::domain::repositories::user_query::{UserPage, UserQuery};
 use crate::domain::value_objects::email::Email;
+use crate::domain::value_objects::id::Id;
+use crate::domain::value_objects::lockout_policy::LockoutPolicy;
+use crate::domain::value_objects::role::Role;
 
+/// The finders skip soft-deleted users, except `find_deleted_by_email`.
 #[async_trait]
-pub trait UserRepository {
-    async fn save(&self, user: User) -> Result<(), String>;
-    async fn find_by_email(&self, email: Email) -> Result<Option<User>, String>;
-    async fn find_all(&self) -> Result<Vec<User>, String>;
-    async fn remove(&self, user: User) -> Result<(), String>;
+pub trait UserRepository: Send + Sync {
+    async fn save(&self, user: User) -> Result<(), RepositoryError>;
+    async fn find_by_id(&self, id: Id) -> Result<Option<User>, RepositoryError>;
+    async fn find_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError>;
+    async fn find_deleted_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError>;
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError>;
+    async fn find_page(&self, query: UserQuery) -> Result<UserPage, RepositoryError>;
+    async fn remove(&self, user: User) -> Result<(), RepositoryError>;
+    /// Counts a failed login in place, without a read-modify-write by the
+    /// caller, and locks the user once the new count calls for it under
+    /// `policy`. Does nothing for unknown or deleted users.
+    async fn register_failed_login(
+        &self,
+        id: Id,
+        policy: LockoutPolicy,
+        now: u64,
+    ) -> Result<(), RepositoryError>;
+    /// Clears the failed login count in place and stores the user's pending
+    /// events, such as `UserLoggedIn`, in the same step. Only applies while
+    /// the user is not deleted and still has the password hash of `user`, so
+    /// a user loaded before the password check cannot undo a concurrent
+    /// reset or deletion. Returns whether it applied.
+    async fn register_successful_login(&self, user: User) -> Result<bool, RepositoryError>;
+    /// Swaps in a rehashed password only while the stored hash is still
+    /// `previous_hash`. Returns whether it did.
+    async fn upgrade_password_hash(
+        &self,
+        id: Id,
+        previous_hash: String,
+        upgraded_hash: String,
+    ) -> Result<bool, RepositoryError>;
+    /// Stores the password hash of `user` and its pending events in place,
+    /// lifting any lockout since the failed attempts were against the
+    /// previous password. Only applies while the user is not deleted and the
+    /// stored hash is still `previous_hash`, and writes nothing else back, so
+    /// a concurrent role change or deletion is kept. Returns whether it
+    /// applied.
+    async fn replace_password(
+        &self,
+        user: User,
+        previous_hash: String,
+    ) -> Result<bool, RepositoryError>;
+    /// Stores the deletion time of `user` and its pending events in place,
+    /// writing nothing else back. Only applies while the user is not deleted
+    /// yet. Returns whether it applied.
+    async fn mark_deleted(&self, user: User) -> Result<bool, RepositoryError>;
+    /// Gives the user `role` in place. Refuses, returning false, to demote
+    /// the last admin that is not deleted, so someone is always left to
+    /// manage roles. Fails with `NotFound` for unknown or deleted users.
+    async fn assign_role(&self, id: Id, role: Role) -> Result<bool, RepositoryError>;
+    /// Permanently removes users soft-deleted before `cutoff`, returning how many.
+    async fn purge_deleted_before(&self, cutoff: u64) -> Result<u64, RepositoryError>;
 }
@@ -0,0 +1,67 @@
+/// How many consecutive failed logins an account tolerates before it is
+/// temporarily locked. Each further failure doubles the lockout, up to a cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    pub max_attempts: u32,
+    pub lockout_seconds: u64,
+    pub max_lockout_seconds: u64,
+}
+
+impl LockoutPolicy {
+    /// Returns how long to lock the account after `failed_attempts`
+    /// consecutive failures, or `None` when it should stay unlocked.
+    pub fn lockout_duration(&self, failed_attempts: u32) -> Option<u64> {
+        if self.max_attempts == 0 || failed_attempts < self.max_attempts {
+            return None;
+        }
+
+        let doublings = (failed_attempts - self.max_attempts).min(32);
+        Some(
+            self.lockout_seconds
+                .saturating_mul(1 << doublings)
+                .min(self.max_lockout_seconds),
+        )
+    }
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        LockoutPolicy {
+            max_attempts: 5,
+            lockout_seconds: 60,
+            max_lockout_seconds: 60 * 60,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LockoutPolicy;
+
+    #[test]
+    fn does_not_lock_below_the_threshold() {
+        let policy = LockoutPolicy::default();
+
+        assert_eq!(policy.lockout_duration(4), None);
+        assert_eq!(policy.lockout_duration(5), Some(60));
+    }
+
+    #[test]
+    fn doubles_the_lockout_up_to_the_cap() {
+        let policy = LockoutPolicy::default();
+
+        assert_eq!(policy.lockout_duration(6), Some(120));
+        assert_eq!(policy.lockout_duration(7), Some(240));
+        assert_eq!(policy.lockout_duration(100), Some(3600));
+    }
+
+    #[test]
+    fn never_locks_when_disabled() {
+        let policy = LockoutPolicy {
+            max_attempts: 0,
+            ..LockoutPolicy::default()
+        };
+
+        assert_eq!(policy.lockout_duration(1000), None);
+    }
+}
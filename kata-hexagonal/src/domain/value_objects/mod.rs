@@ -1,3 +1,5 @@
 pub mod email;
 pub mod id;
+pub mod lockout_policy;
 pub mod password;
+pub mod role;
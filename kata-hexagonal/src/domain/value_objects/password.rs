@@ -24,6 +24,25 @@ pub enum PasswordErrorType {
     MustContainUnderscore,
 }
 
+impl PasswordError {
+    pub fn errors(&self) -> &[PasswordErrorType] {
+        &self.errors
+    }
+}
+
+impl PasswordErrorType {
+    /// Stable identifier of the unmet rule, for clients to match on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PasswordErrorType::TooShort => "too_short",
+            PasswordErrorType::MustContainNumber => "must_contain_number",
+            PasswordErrorType::MustContainLowercase => "must_contain_lowercase",
+            PasswordErrorType::MustContainUppercase => "must_contain_uppercase",
+            PasswordErrorType::MustContainUnderscore => "must_contain_underscore",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Password(String);
 
@@ -37,6 +56,24 @@ impl Password {
         Self(hash)
     }
 
+    /// A password no plaintext matches, for accounts that must go through a
+    /// reset before they can be used again.
+    pub fn unusable() -> Self {
+        Self("!".to_string())
+    }
+
+    pub fn verify(&self, plaintext: &str) -> bool {
+        hash::verify(plaintext, &self.0)
+    }
+
+    pub fn needs_rehash(&self) -> bool {
+        hash::needs_rehash(&self.0)
+    }
+
+    pub(crate) fn rehash(plaintext: &str) -> Self {
+        Self(Self::hash_plaintext(plaintext))
+    }
+
     fn hash_plaintext(plaintext: &str) -> String {
         hash::hash(plaintext)
     }
@@ -102,13 +139,20 @@ impl fmt::Display for Password {
 #[cfg(test)]
 mod test {
     use crate::domain::value_objects::password::{Password, PasswordError, PasswordErrorType};
-    use regex::Regex;
 
     #[test]
     fn creates_correct_password() {
         assert!(Password::new(String::from("SecurePass123_")).is_ok());
     }
 
+    #[test]
+    fn an_unusable_password_matches_nothing() {
+        let password = Password::unusable();
+
+        assert!(!password.verify("!"));
+        assert!(!password.verify(""));
+    }
+
     #[test]
     fn fails_creating_with_short_password() {
         assert_eq!(
@@ -179,26 +223,42 @@ mod test {
         let password = Password::new(String::from("SecurePass123_")).unwrap();
         let hashed_value = password.to_string();
 
-        let regex = Regex::new(r"[a-f-F0-9]{64}").unwrap();
-
         assert_ne!(hashed_value, String::from("SecurePass123_"));
-        assert_eq!(hashed_value.len(), 64);
-        assert!(regex.is_match(&hashed_value));
+        assert!(hashed_value.starts_with("$argon2id$"));
     }
 
     #[test]
-    fn matches_for_two_same_passwords() {
-        let a_password = Password::new("SecurePass123_".to_string());
-        let another_password = Password::new("SecurePass123_".to_string());
+    fn salts_each_hash() {
+        let a_password = Password::new("SecurePass123_".to_string()).unwrap();
+        let another_password = Password::new("SecurePass123_".to_string()).unwrap();
 
-        assert_eq!(a_password, another_password);
+        assert_ne!(a_password, another_password);
     }
 
     #[test]
-    fn does_not_match_for_two_different_passwords() {
-        let a_password = Password::new("SecurePass123_".to_string());
-        let another_password = Password::new("SecurePass12_".to_string());
+    fn verifies_the_same_plaintext() {
+        let password = Password::new("SecurePass123_".to_string()).unwrap();
 
-        assert_ne!(a_password, another_password);
+        assert!(password.verify("SecurePass123_"));
+    }
+
+    #[test]
+    fn does_not_verify_a_different_plaintext() {
+        let password = Password::new("SecurePass123_".to_string()).unwrap();
+
+        assert!(!password.verify("SecurePass12_"));
+    }
+
+    #[test]
+    fn verifies_legacy_sha256_hashes_and_flags_them_for_rehash() {
+        let password = Password::from_hash(
+            "5e884898da28047151d0e56f8dc6292773603d0d6aabbdd62a11ef721d1542d8".to_string(),
+        );
+
+        assert!(password.verify("password"));
+        assert!(password.needs_rehash());
+        assert!(!Password::new("SecurePass123_".to_string())
+            .unwrap()
+            .needs_rehash());
     }
 }
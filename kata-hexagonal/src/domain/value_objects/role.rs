@@ -0,0 +1,99 @@
+use std::{fmt, str::FromStr};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unknown role {0:?}, expected user or admin")]
+pub struct InvalidRoleError(pub String);
+
+/// Something only some roles are allowed to do. Users can always act on their
+/// own account, so only operations on other accounts are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    ListUsers,
+    DeleteUsers,
+    ManageRoles,
+    ViewAuditLog,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Role {
+    #[default]
+    User,
+    Admin,
+}
+
+impl Role {
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::User => &[],
+            Role::Admin => &[
+                Permission::ListUsers,
+                Permission::DeleteUsers,
+                Permission::ManageRoles,
+                Permission::ViewAuditLog,
+            ],
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = InvalidRoleError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "user" => Ok(Role::User),
+            "admin" => Ok(Role::Admin),
+            other => Err(InvalidRoleError(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Permission::ListUsers => "list users",
+            Permission::DeleteUsers => "delete users",
+            Permission::ManageRoles => "manage roles",
+            Permission::ViewAuditLog => "view the audit log",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{InvalidRoleError, Permission, Role};
+
+    #[test]
+    fn only_admins_can_act_on_other_accounts() {
+        assert!(!Role::User.can(Permission::ListUsers));
+        assert!(Role::Admin.can(Permission::ListUsers));
+        assert!(Role::Admin.can(Permission::ManageRoles));
+    }
+
+    #[test]
+    fn parses_role_names() {
+        assert_eq!("admin".parse(), Ok(Role::Admin));
+        assert_eq!(Role::User.to_string().parse(), Ok(Role::User));
+        assert_eq!(
+            "root".parse::<Role>(),
+            Err(InvalidRoleError("root".to_string()))
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::application::{
+    account_delete_service::AccountDeleteService,
+    application_error::ApplicationError,
+    dtos::{AccountDeleteRequest, MessageResponse},
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct AccountDeleteController {
+    service: AccountDeleteService,
+}
+
+impl AccountDeleteController {
+    pub fn new(service: AccountDeleteService) -> Self {
+        AccountDeleteController { service }
+    }
+
+    pub async fn delete<T: HttpResponse<Result<MessageResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<AccountDeleteRequest>,
+        response: &mut T,
+    ) {
+        match self.service.delete(request.body).await {
+            Ok(delete_response) => response.status(200).json(Ok(delete_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            account_delete_service::AccountDeleteService,
+            application_error::ApplicationError,
+            dtos::{AccountDeleteRequest, MessageResponse},
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::AccountDeleteController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<MessageResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<MessageResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<MessageResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    async fn delete(password: &str) -> MockResponse {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        let _ = repo.save(user.clone()).await;
+        let controller = AccountDeleteController::new(AccountDeleteService::new(
+            repo,
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+        ));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .delete(
+                HttpRequest {
+                    body: AccountDeleteRequest {
+                        user_id: user.id(),
+                        password: password.to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn deletes_the_account() {
+        let response = delete("TestPass123_").await;
+
+        assert_eq!(response.status, 200);
+        assert!(response.data.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn responds_unauthorized_for_a_wrong_password() {
+        let response = delete("WrongPass123_").await;
+
+        assert_eq!(response.status, 401);
+    }
+}
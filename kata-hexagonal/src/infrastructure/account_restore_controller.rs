@@ -0,0 +1,117 @@
+use crate::application::{
+    account_restore_service::AccountRestoreService,
+    application_error::ApplicationError,
+    dtos::{AccountRestoreRequest, MessageResponse},
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct AccountRestoreController {
+    service: AccountRestoreService,
+}
+
+impl AccountRestoreController {
+    pub fn new(service: AccountRestoreService) -> Self {
+        AccountRestoreController { service }
+    }
+
+    pub async fn restore<T: HttpResponse<Result<MessageResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<AccountRestoreRequest>,
+        response: &mut T,
+    ) {
+        match self.service.restore(request.body).await {
+            Ok(restore_response) => response.status(200).json(Ok(restore_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            account_restore_service::AccountRestoreService,
+            application_error::ApplicationError,
+            dtos::{AccountRestoreRequest, MessageResponse},
+        },
+        domain::{
+            common::time,
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::AccountRestoreController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<MessageResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<MessageResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<MessageResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    async fn restore(email: &str) -> MockResponse {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.delete(time::now());
+        let _ = repo.save(user).await;
+        let controller = AccountRestoreController::new(AccountRestoreService::new(repo));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .restore(
+                HttpRequest {
+                    body: AccountRestoreRequest {
+                        email: email.to_string(),
+                        password: "TestPass123_".to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn restores_the_account() {
+        let response = restore("test@example.com").await;
+
+        assert_eq!(response.status, 200);
+        assert!(response.data.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn responds_unauthorized_for_an_unknown_account() {
+        let response = restore("other@example.com").await;
+
+        assert_eq!(response.status, 401);
+    }
+}
@@ -0,0 +1,142 @@
+use std::future::{ready, Ready};
+
+use actix_web::{
+    dev::Payload,
+    http::header::{self, HeaderValue},
+    web::Data,
+    FromRequest, HttpRequest, HttpResponse, ResponseError,
+};
+
+use crate::{application::ports::token_issuer::TokenIssuer, infrastructure::http::ErrorBody};
+
+/// Extractor for routes that require a valid bearer access token.
+/// Resolves to the id of the user the token was issued to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedUser {
+    pub user_id: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{0}")]
+pub struct AuthenticationError(String);
+
+impl ResponseError for AuthenticationError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::Unauthorized()
+            .insert_header((header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer")))
+            .json(ErrorBody::new("unauthorized", &self.0))
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = AuthenticationError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(authenticate(req))
+    }
+}
+
+fn authenticate(req: &HttpRequest) -> Result<AuthenticatedUser, AuthenticationError> {
+    let issuer = req
+        .app_data::<Data<dyn TokenIssuer>>()
+        .ok_or_else(|| AuthenticationError("Authentication is not configured".to_string()))?;
+
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| AuthenticationError("Missing bearer token".to_string()))?;
+
+    let user_id = issuer
+        .verify(token.trim())
+        .map_err(|e| AuthenticationError(e.to_string()))?;
+
+    Ok(AuthenticatedUser {
+        user_id: user_id.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use actix_web::{
+        get, http::header, http::StatusCode, test, web::Data, App, HttpResponse, Responder,
+    };
+
+    use crate::{
+        application::ports::token_issuer::TokenIssuer,
+        domain::{
+            entities::user::User,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::jwt_token_issuer::JwtTokenIssuer,
+    };
+
+    use super::AuthenticatedUser;
+
+    #[get("/protected")]
+    async fn protected(user: AuthenticatedUser) -> impl Responder {
+        HttpResponse::Ok().body(user.user_id)
+    }
+
+    fn issuer() -> Arc<dyn TokenIssuer> {
+        Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)))
+    }
+
+    #[actix_web::test]
+    async fn injects_the_user_id_from_a_valid_token() {
+        let issuer = issuer();
+        let user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::from_hash("hash".to_string()),
+        );
+        let token = issuer.issue(&user).unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::from(issuer.clone()))
+                .service(protected),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/protected")
+            .insert_header((header::AUTHORIZATION, format!("Bearer {}", token.token)))
+            .to_request();
+        let body = test::call_and_read_body(&app, req).await;
+
+        assert_eq!(body, user.id());
+    }
+
+    #[actix_web::test]
+    async fn rejects_requests_without_a_token() {
+        let app =
+            test::init_service(App::new().app_data(Data::from(issuer())).service(protected)).await;
+
+        let req = test::TestRequest::get().uri("/protected").to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            res.headers().get(header::WWW_AUTHENTICATE).unwrap(),
+            "Bearer"
+        );
+    }
+
+    #[actix_web::test]
+    async fn rejects_requests_with_an_invalid_token() {
+        let app =
+            test::init_service(App::new().app_data(Data::from(issuer())).service(protected)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/protected")
+            .insert_header((header::AUTHORIZATION, "Bearer not.a.token"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+}
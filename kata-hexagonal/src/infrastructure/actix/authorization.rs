@@ -0,0 +1,192 @@
+use std::{future::Future, marker::PhantomData, pin::Pin};
+
+use actix_web::{
+    dev::Payload, http::StatusCode, web::Data, FromRequest, HttpRequest, HttpResponse,
+    ResponseError,
+};
+
+use crate::{
+    application::{application_error::ApplicationError, authorizer::Requester},
+    domain::value_objects::role::Permission,
+    infrastructure::{
+        actix::auth::{AuthenticatedUser, AuthenticationError},
+        container::Container,
+        http::{status_code, ErrorBody},
+    },
+};
+
+/// Names the permission an `Authorized` route requires.
+pub trait RequiredPermission {
+    const PERMISSION: Permission;
+}
+
+pub struct ListUsers;
+pub struct DeleteUsers;
+pub struct ManageRoles;
+pub struct ViewAuditLog;
+
+impl RequiredPermission for ListUsers {
+    const PERMISSION: Permission = Permission::ListUsers;
+}
+
+impl RequiredPermission for DeleteUsers {
+    const PERMISSION: Permission = Permission::DeleteUsers;
+}
+
+impl RequiredPermission for ManageRoles {
+    const PERMISSION: Permission = Permission::ManageRoles;
+}
+
+impl RequiredPermission for ViewAuditLog {
+    const PERMISSION: Permission = Permission::ViewAuditLog;
+}
+
+/// Extractor for routes that require a permission, e.g.
+/// `Authorized<ManageRoles>`. Rejects the request before the handler runs,
+/// with 401 when unauthenticated and 403 when the role lacks the permission.
+#[derive(Debug)]
+pub struct Authorized<P> {
+    pub requester: Requester,
+    permission: PhantomData<P>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuthorizationError {
+    #[error(transparent)]
+    Unauthenticated(#[from] AuthenticationError),
+    #[error(transparent)]
+    Denied(#[from] ApplicationError),
+    #[error("Authorization is not configured")]
+    NotConfigured,
+}
+
+impl ResponseError for AuthorizationError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AuthorizationError::Unauthenticated(error) => error.status_code(),
+            AuthorizationError::Denied(error) => {
+                StatusCode::from_u16(status_code(error)).unwrap_or(StatusCode::FORBIDDEN)
+            }
+            AuthorizationError::NotConfigured => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            AuthorizationError::Unauthenticated(error) => error.error_response(),
+            AuthorizationError::Denied(error) => {
+                HttpResponse::build(self.status_code()).json(ErrorBody::from_error(error))
+            }
+            AuthorizationError::NotConfigured => {
+                log::error!("{}", self);
+                HttpResponse::InternalServerError()
+                    .json(ErrorBody::new("internal_error", "Internal server error"))
+            }
+        }
+    }
+}
+
+impl<P: RequiredPermission + 'static> FromRequest for Authorized<P> {
+    type Error = AuthorizationError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let user = AuthenticatedUser::from_request(req, payload).into_inner();
+        let container = req.app_data::<Data<Container>>().cloned();
+
+        Box::pin(async move {
+            let requester = Requester::User(user?.user_id);
+            let container = container.ok_or(AuthorizationError::NotConfigured)?;
+            container
+                .authorizer
+                .authorize(&requester, P::PERMISSION)
+                .await?;
+
+            Ok(Authorized {
+                requester,
+                permission: PhantomData,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use actix_web::{
+        get, http::header, http::StatusCode, test, web::Data, App, HttpResponse, Responder,
+    };
+
+    use crate::{
+        application::ports::token_issuer::TokenIssuer,
+        domain::{
+            entities::user::User,
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::{
+            config::Config,
+            container::{Container, Repositories},
+            in_memory_mailer::InMemoryMailer,
+            jwt_token_issuer::JwtTokenIssuer,
+        },
+    };
+
+    use super::{Authorized, ManageRoles};
+
+    #[get("/admin")]
+    async fn admin_only(_admin: Authorized<ManageRoles>) -> impl Responder {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn call(role: Option<Role>) -> StatusCode {
+        let issuer: Arc<dyn TokenIssuer> =
+            Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)));
+        let repositories = Repositories::in_memory();
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::from_hash("hash".to_string()),
+        );
+        let mut request = test::TestRequest::get().uri("/admin");
+        if let Some(role) = role {
+            user.assign_role(role);
+            repositories.users.save(user.clone()).await.unwrap();
+            let token = issuer.issue(&user).unwrap();
+            request =
+                request.insert_header((header::AUTHORIZATION, format!("Bearer {}", token.token)));
+        }
+        let container = Container::new(
+            &Config::default(),
+            repositories,
+            issuer.clone(),
+            Arc::new(InMemoryMailer::new()),
+        );
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(container))
+                .app_data(Data::from(issuer))
+                .service(admin_only),
+        )
+        .await;
+
+        test::call_service(&app, request.to_request())
+            .await
+            .status()
+    }
+
+    #[actix_web::test]
+    async fn lets_roles_with_the_permission_through() {
+        assert_eq!(call(Some(Role::Admin)).await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn forbids_roles_without_the_permission() {
+        assert_eq!(call(Some(Role::User)).await, StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn requires_authentication() {
+        assert_eq!(call(None).await, StatusCode::UNAUTHORIZED);
+    }
+}
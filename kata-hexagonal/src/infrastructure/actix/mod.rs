@@ -1,2 +1,6 @@
+pub mod auth;
+pub mod authorization;
+pub mod openapi;
+pub mod rate_limit;
 pub mod response;
 pub mod server;
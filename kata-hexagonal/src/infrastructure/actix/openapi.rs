@@ -0,0 +1,197 @@
+use utoipa::{
+    openapi::{
+        self,
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    },
+    Modify, OpenApi,
+};
+
+use super::server;
+
+macro_rules! api_doc {
+    ($($handler:ident),* $(,)?) => {
+        /// The OpenAPI document served at `/openapi.json`. Each operation
+        /// comes from the `#[utoipa::path]` on its handler, for every handler
+        /// listed in `server::with_handlers!`.
+        #[derive(OpenApi)]
+        #[openapi(
+            info(
+                title = "kata-hexagonal",
+                description = "User registration, authentication and administration."
+            ),
+            paths($(server::$handler),*),
+            modifiers(&BearerAuth),
+            tags(
+                (name = "health"),
+                (name = "auth", description = "Registration, login and credential recovery"),
+                (name = "users", description = "What users do with their own account"),
+                (name = "admin", description = "Requires a role with the matching permission"),
+            )
+        )]
+        pub struct ApiDoc;
+    };
+}
+server::with_handlers!(api_doc);
+
+/// Access tokens from `/login`, sent as `Authorization: Bearer <token>`.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+    use actix_web::{
+        dev::{Service, ServiceResponse},
+        http::{Method, StatusCode},
+        test::{call_and_read_body_json, call_service, init_service, read_body, TestRequest},
+        web::Data,
+        App,
+    };
+    use regex::Regex;
+    use serde_json::Value;
+    use utoipa::OpenApi;
+
+    use crate::{
+        application::ports::token_issuer::TokenIssuer,
+        infrastructure::{
+            actix::server::{self, configure},
+            config::Config,
+            container::{Container, Repositories},
+            in_memory_mailer::InMemoryMailer,
+            jwt_token_issuer::JwtTokenIssuer,
+        },
+    };
+
+    use super::ApiDoc;
+
+    async fn app(
+    ) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>
+    {
+        let token_issuer: Arc<dyn TokenIssuer> =
+            Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)));
+        let container = Container::new(
+            &Config::default(),
+            Repositories::in_memory(),
+            token_issuer.clone(),
+            Arc::new(InMemoryMailer::new()),
+        );
+
+        init_service(
+            App::new()
+                .app_data(Data::new(container))
+                .app_data(Data::from(token_issuer))
+                .configure(configure),
+        )
+        .await
+    }
+
+    fn listed_handlers() -> BTreeSet<String> {
+        macro_rules! names {
+            ($($handler:ident),* $(,)?) => {
+                BTreeSet::from([$(stringify!($handler).to_string()),*])
+            };
+        }
+        server::with_handlers!(names)
+    }
+
+    fn documented_operations() -> BTreeSet<String> {
+        let document = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        document["paths"]
+            .as_object()
+            .unwrap()
+            .values()
+            .flat_map(|item| item.as_object().unwrap().values())
+            .map(|operation| operation["operationId"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    fn documented_routes() -> BTreeSet<(String, String)> {
+        let document = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        document["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .unwrap()
+                    .keys()
+                    .map(move |method| (method.clone(), path.clone()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn documents_one_operation_per_handler() {
+        assert_eq!(documented_operations(), listed_handlers());
+        assert_eq!(documented_routes().len(), listed_handlers().len());
+    }
+
+    #[actix_web::test]
+    async fn registers_every_documented_route() {
+        let app = app().await;
+        let parameter = Regex::new(r"\{[^}]+\}").unwrap();
+
+        for (method, path) in documented_routes() {
+            let uri = parameter.replace_all(&path, "00000000-0000-0000-0000-000000000000");
+            let request = TestRequest::default()
+                .method(Method::from_bytes(method.to_uppercase().as_bytes()).unwrap())
+                .uri(&uri)
+                .to_request();
+            let response = call_service(&app, request).await;
+            let status = response.status();
+            let body = read_body(response).await;
+
+            // Unmatched routes fall through to actix's empty 404.
+            assert!(
+                status != StatusCode::NOT_FOUND || !body.is_empty(),
+                "{} {} is documented but not registered",
+                method,
+                path
+            );
+        }
+    }
+
+    #[actix_web::test]
+    async fn serves_the_document_and_the_swagger_ui() {
+        let app = app().await;
+
+        let request = TestRequest::get().uri("/openapi.json").to_request();
+        let document: Value = call_and_read_body_json(&app, request).await;
+        let request = TestRequest::get().uri("/swagger-ui/").to_request();
+        let ui = call_service(&app, request).await;
+
+        assert!(document["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(document["paths"]["/login"]["post"].is_object());
+        assert_eq!(ui.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn unknown_routes_get_an_empty_not_found() {
+        let app = app().await;
+
+        let request = TestRequest::post().uri("/unknown").to_request();
+        let response = call_service(&app, request).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(read_body(response).await.is_empty());
+    }
+}
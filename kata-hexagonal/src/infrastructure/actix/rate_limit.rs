@@ -0,0 +1,328 @@
+use std::{
+    collections::HashMap,
+    future::{ready, Future, Ready},
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+    web::Bytes,
+    Error, HttpResponse,
+};
+
+use crate::infrastructure::http::ErrorBody;
+
+/// A token bucket: up to `burst` requests at once, refilled evenly so that
+/// `burst` more are allowed every `period`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub period: Duration,
+}
+
+impl RateLimit {
+    pub fn new(burst: u32, period: Duration) -> Self {
+        RateLimit { burst, period }
+    }
+
+    pub fn refill_per_second(&self) -> f64 {
+        self.burst as f64 / self.period.as_secs_f64()
+    }
+}
+
+/// Limits applied to a single route. The per-email bucket is only consulted
+/// for JSON bodies with an `email` field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteLimit {
+    pub per_client: RateLimit,
+    pub per_email: Option<RateLimit>,
+}
+
+pub trait RateLimitStore: Send + Sync {
+    /// Takes one token from the bucket stored under `key`. When the bucket is
+    /// empty, returns how long until a token is available.
+    fn acquire(&self, key: &str, limit: &RateLimit, now: Instant) -> Result<(), Duration>;
+}
+
+/// Middleware rejecting requests over their route's limit with 429 and a
+/// `Retry-After` header. Routes without a limit pass through untouched.
+#[derive(Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    routes: Arc<HashMap<String, RouteLimit>>,
+}
+
+impl RateLimiter {
+    pub fn new(store: Arc<dyn RateLimitStore>) -> Self {
+        RateLimiter {
+            store,
+            routes: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Limits requests to `path`, either an exact path or a route pattern
+    /// such as `/users/{id}/password`, which shares one bucket per client
+    /// across every id.
+    pub fn route(mut self, path: &str, limit: RouteLimit) -> Self {
+        Arc::make_mut(&mut self.routes).insert(path.to_string(), limit);
+        self
+    }
+
+    /// The limited route `req` falls under, by exact path first so that
+    /// `/users/me` is not taken for `/users/{id}`.
+    fn limit_for(&self, req: &ServiceRequest) -> Option<(String, RouteLimit)> {
+        if let Some(limit) = self.routes.get(req.path()) {
+            return Some((req.path().to_string(), *limit));
+        }
+        let pattern = req.match_pattern()?;
+        let limit = self.routes.get(&pattern)?;
+        Some((pattern, *limit))
+    }
+
+    /// Returns how long the client must wait, or `None` when the request may proceed.
+    async fn check(
+        &self,
+        req: &mut ServiceRequest,
+        path: &str,
+        limit: &RouteLimit,
+    ) -> Result<Option<Duration>, Error> {
+        let now = Instant::now();
+        let client = req
+            .peer_addr()
+            .map_or_else(|| "unknown".to_string(), |addr| addr.ip().to_string());
+
+        let client_key = format!("{}|client|{}", path, client);
+        if let Err(retry_after) = self.store.acquire(&client_key, &limit.per_client, now) {
+            return Ok(Some(retry_after));
+        }
+
+        if let Some(per_email) = &limit.per_email {
+            if let Some(email) = peek_email(req).await? {
+                let email_key = format!("{}|email|{}", path, email);
+                if let Err(retry_after) = self.store.acquire(&email_key, per_email, now) {
+                    return Ok(Some(retry_after));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Reads the `email` field of a JSON body and puts the body back for the handler.
+async fn peek_email(req: &mut ServiceRequest) -> Result<Option<String>, Error> {
+    let body = req.extract::<Bytes>().await?;
+    let email = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| Some(json.get("email")?.as_str()?.trim().to_lowercase()));
+
+    let (_, mut payload) = actix_http::h1::Payload::create(true);
+    payload.unread_data(body);
+    req.set_payload(payload.into());
+
+    Ok(email)
+}
+
+fn too_many_requests(retry_after: Duration) -> HttpResponse {
+    let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+
+    HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, seconds.to_string()))
+        .json(ErrorBody::new(
+            "too_many_requests",
+            "Too many requests, try again later",
+        ))
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimiter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimiterMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimiterMiddleware {
+            service: Rc::new(service),
+            limiter: self.clone(),
+        }))
+    }
+}
+
+pub struct RateLimiterMiddleware<S> {
+    service: Rc<S>,
+    limiter: RateLimiter,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimiterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            if let Some((path, limit)) = limiter.limit_for(&req) {
+                if let Some(retry_after) = limiter.check(&mut req, &path, &limit).await? {
+                    log::warn!("rate limit exceeded on {}", req.path());
+                    return Ok(req
+                        .into_response(too_many_requests(retry_after))
+                        .map_into_right_body());
+                }
+            }
+
+            service
+                .call(req)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use actix_web::{
+        http::header, http::StatusCode, post, test, web, App, HttpResponse, Responder,
+    };
+    use serde::Deserialize;
+
+    use crate::infrastructure::in_memory_rate_limit_store::InMemoryRateLimitStore;
+
+    use super::{RateLimit, RateLimiter, RouteLimit};
+
+    #[derive(Deserialize)]
+    struct Form {
+        email: String,
+    }
+
+    #[post("/login")]
+    async fn login(form: web::Json<Form>) -> impl Responder {
+        HttpResponse::Ok().body(form.email.clone())
+    }
+
+    #[post("/other")]
+    async fn other() -> impl Responder {
+        HttpResponse::Ok().finish()
+    }
+
+    #[post("/users/{id}/password")]
+    async fn change_password() -> impl Responder {
+        HttpResponse::Ok().finish()
+    }
+
+    fn limiter(per_email: Option<RateLimit>) -> RateLimiter {
+        RateLimiter::new(Arc::new(InMemoryRateLimitStore::new())).route(
+            "/login",
+            RouteLimit {
+                per_client: RateLimit::new(2, Duration::from_secs(60)),
+                per_email,
+            },
+        )
+    }
+
+    fn request(path: &str, ip: &str, email: &str) -> actix_http::Request {
+        test::TestRequest::post()
+            .uri(path)
+            .peer_addr(format!("{}:1234", ip).parse().unwrap())
+            .set_json(serde_json::json!({ "email": email }))
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn rejects_a_client_over_its_limit_with_retry_after() {
+        let app = test::init_service(App::new().wrap(limiter(None)).service(login)).await;
+
+        for _ in 0..2 {
+            let res =
+                test::call_service(&app, request("/login", "10.0.0.1", "a@example.com")).await;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+        let res = test::call_service(&app, request("/login", "10.0.0.1", "a@example.com")).await;
+
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers().get(header::RETRY_AFTER).unwrap(), "30");
+    }
+
+    #[actix_web::test]
+    async fn keeps_a_separate_bucket_per_client() {
+        let app = test::init_service(App::new().wrap(limiter(None)).service(login)).await;
+
+        for _ in 0..2 {
+            test::call_service(&app, request("/login", "10.0.0.1", "a@example.com")).await;
+        }
+        let res = test::call_service(&app, request("/login", "10.0.0.2", "a@example.com")).await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn limits_a_route_pattern_across_its_paths() {
+        let limiter = RateLimiter::new(Arc::new(InMemoryRateLimitStore::new())).route(
+            "/users/{id}/password",
+            RouteLimit {
+                per_client: RateLimit::new(2, Duration::from_secs(60)),
+                per_email: None,
+            },
+        );
+        let app = test::init_service(App::new().wrap(limiter).service(change_password)).await;
+
+        for id in ["a", "b"] {
+            let path = format!("/users/{}/password", id);
+            let res = test::call_service(&app, request(&path, "10.0.0.1", "a@example.com")).await;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+        let res = test::call_service(
+            &app,
+            request("/users/c/password", "10.0.0.1", "a@example.com"),
+        )
+        .await;
+
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[actix_web::test]
+    async fn does_not_limit_other_routes() {
+        let app = test::init_service(App::new().wrap(limiter(None)).service(other)).await;
+
+        for _ in 0..5 {
+            let res =
+                test::call_service(&app, request("/other", "10.0.0.1", "a@example.com")).await;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    #[actix_web::test]
+    async fn limits_an_email_across_clients_and_keeps_the_body_readable() {
+        let per_email = Some(RateLimit::new(1, Duration::from_secs(60)));
+        let app = test::init_service(App::new().wrap(limiter(per_email)).service(login)).await;
+
+        let body =
+            test::call_and_read_body(&app, request("/login", "10.0.0.1", "A@example.com")).await;
+        let res = test::call_service(&app, request("/login", "10.0.0.2", "a@example.com")).await;
+        let other_email =
+            test::call_service(&app, request("/login", "10.0.0.3", "b@example.com")).await;
+
+        assert_eq!(body, "A@example.com");
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(other_email.status(), StatusCode::OK);
+    }
+}
@@ -1,15 +1,17 @@
-use std::{error::Error, fmt::Display};
-
 use actix_web::{http::StatusCode, HttpResponse};
+use serde::Serialize;
 
-use crate::infrastructure::http;
+use crate::{
+    application::application_error::ApplicationError,
+    infrastructure::http::{self, ErrorBody},
+};
 
 pub struct ActixHttpResponse<T> {
     status: Option<StatusCode>,
-    data: Option<Result<T, Box<dyn Error>>>,
+    data: Option<Result<T, ApplicationError>>,
 }
 
-impl<T: Display> ActixHttpResponse<T> {
+impl<T: Serialize> ActixHttpResponse<T> {
     pub fn new() -> Self {
         ActixHttpResponse {
             status: None,
@@ -19,15 +21,27 @@ impl<T: Display> ActixHttpResponse<T> {
 
     pub fn response(&self) -> HttpResponse {
         match (self.status, &self.data) {
-            (Some(status), Some(Ok(data))) => HttpResponse::build(status).json(format!("{}", data)),
-            (Some(status), Some(Err(error))) => HttpResponse::build(status).json(error.to_string()),
+            (Some(status), Some(Ok(data))) => HttpResponse::build(status).json(data),
+            (Some(status), Some(Err(error))) => {
+                if status.is_server_error() {
+                    log::error!("request failed: {}", error);
+                }
+                HttpResponse::build(status).json(ErrorBody::from_error(error))
+            }
             (Some(status), None) => HttpResponse::new(status),
-            _other => HttpResponse::InternalServerError().body("Unknown error".to_string()),
+            _other => HttpResponse::InternalServerError()
+                .json(ErrorBody::new("internal_error", "Internal server error")),
         }
     }
 }
 
-impl<T> http::HttpResponse<Result<T, Box<dyn Error>>> for ActixHttpResponse<T> {
+impl<T: Serialize> Default for ActixHttpResponse<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> http::HttpResponse<Result<T, ApplicationError>> for ActixHttpResponse<T> {
     fn status(&mut self, code: u16) -> &mut Self {
         if let Ok(status) = StatusCode::from_u16(code) {
             self.status = Some(status);
@@ -35,8 +49,64 @@ impl<T> http::HttpResponse<Result<T, Box<dyn Error>>> for ActixHttpResponse<T> {
         self
     }
 
-    fn json(&mut self, data: Result<T, Box<dyn Error>>) -> &mut Self {
+    fn json(&mut self, data: Result<T, ApplicationError>) -> &mut Self {
         self.data = Some(data);
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use actix_web::body::to_bytes;
+    use serde_json::{json, Value};
+
+    use crate::{
+        application::dtos::UserRegisterResponse, domain::value_objects::password::Password,
+        infrastructure::http::HttpResponse,
+    };
+
+    use super::ActixHttpResponse;
+
+    async fn body_of(response: ActixHttpResponse<UserRegisterResponse>) -> (u16, Value) {
+        let response = response.response();
+        let status = response.status().as_u16();
+        let bytes = to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn serializes_data_as_a_json_object() {
+        let mut response = ActixHttpResponse::new();
+        response.status(201).json(Ok(UserRegisterResponse {
+            id: "an-id".to_string(),
+            email: "test@example.com".to_string(),
+        }));
+
+        assert_eq!(
+            body_of(response).await,
+            (201, json!({ "id": "an-id", "email": "test@example.com" }))
+        );
+    }
+
+    #[actix_web::test]
+    async fn wraps_errors_in_an_envelope() {
+        let mut response = ActixHttpResponse::new();
+        let error = Password::new("abcdef_".to_string()).unwrap_err();
+        response.status(422).json(Err(error.into()));
+
+        assert_eq!(
+            body_of(response).await,
+            (
+                422,
+                json!({
+                    "code": "invalid_password",
+                    "message": "Password must contain a number, must contain a uppercase",
+                    "details": [
+                        { "code": "must_contain_number", "message": "must contain a number" },
+                        { "code": "must_contain_uppercase", "message": "must contain a uppercase" }
+                    ]
+                })
+            )
+        );
+    }
+}
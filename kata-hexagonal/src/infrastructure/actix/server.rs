@@ -1,87 +1,1341 @@
+use std::{sync::Arc, time::Duration};
+
 use actix_web::{
-    get, middleware, post,
+    delete,
+    error::{InternalError, JsonPayloadError, QueryPayloadError},
+    get, middleware, post, put,
     web::{self, Data},
     App, HttpResponse, HttpServer, Responder,
 };
+use rand::distributions::DistString;
 use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
     application::{
-        dtos::{UserLoginRequest, UserRegisterRequest},
-        user_login_service::UserLoginService,
-        user_register_service::UserRegisterService,
+        authorizer::Requester,
+        dtos::{
+            AccountDeleteRequest, AccountRestoreRequest, AuditLogQueryRequest, AuditLogResponse,
+            EmailVerificationResendRequest, EmailVerifyRequest, MessageResponse,
+            PasswordForgotRequest, PasswordResetRequest, TokenRefreshRequest, TokenRefreshResponse,
+            TwoFactorConfirmRequest, TwoFactorConfirmResponse, TwoFactorEnrollRequest,
+            TwoFactorEnrollResponse, TwoFactorLoginRequest, UserChangePasswordRequest,
+            UserChangePasswordResponse, UserDeleteRequest, UserFindRequest, UserFindResponse,
+            UserLoginOutcome, UserLoginRequest, UserLoginResponse, UserPageResponse,
+            UserRegisterRequest, UserRegisterResponse, UserRoleAssignRequest, UserSearchRequest,
+        },
+        ports::{mailer::Mailer, token_issuer::TokenIssuer},
     },
     infrastructure::{
-        actix::response::ActixHttpResponse, http::HttpRequest, sqlite_user_repository::Sqlite,
-        user_login_controller::UserLoginController,
-        user_register_controller::UserRegisterController,
+        actix::{
+            auth::AuthenticatedUser,
+            authorization::{Authorized, DeleteUsers, ListUsers, ManageRoles, ViewAuditLog},
+            openapi::ApiDoc,
+            rate_limit::{RateLimit, RateLimiter, RouteLimit},
+            response::ActixHttpResponse,
+        },
+        config::{AuthConfig, Config, MailConfig, RateLimitConfig},
+        container::{Container, Repositories},
+        http::{ErrorBody, HttpRequest},
+        in_memory_rate_limit_store::InMemoryRateLimitStore,
+        jwt_token_issuer::JwtTokenIssuer,
+        maildir_mailer::MaildirMailer,
     },
 };
 
-#[derive(Deserialize)]
-struct FormData {
+#[derive(Deserialize, ToSchema)]
+struct CredentialsFormData {
+    email: String,
+    password: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct TwoFactorLoginFormData {
+    /// From the `/login` response.
+    challenge_token: String,
+    /// A TOTP code or one of the recovery codes.
+    code: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct TwoFactorCodeFormData {
+    code: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct RefreshFormData {
+    refresh_token: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ChangePasswordFormData {
+    current_password: String,
+    new_password: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct VerifyQuery {
+    token: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct EmailFormData {
     email: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct PasswordResetFormData {
+    token: String,
+    new_password: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct UserListQuery {
+    /// Part of the email address.
+    email: Option<String>,
+    /// The whole domain of the email address.
+    domain: Option<String>,
+    /// `verified`, `unverified` or `locked`.
+    status: Option<String>,
+    /// `created_at` (the default) or `email`.
+    sort: Option<String>,
+    /// `asc` (the default) or `desc`.
+    order: Option<String>,
+    /// The `next_cursor` of the previous page.
+    cursor: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AuditLogQuery {
+    user_id: Option<String>,
+    /// `registered`, `login_succeeded`, `login_failed` or `password_changed`.
+    event_type: Option<String>,
+    /// Seconds since the Unix epoch, inclusive.
+    from: Option<u64>,
+    /// Seconds since the Unix epoch, exclusive.
+    until: Option<u64>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct RoleFormData {
+    role: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct PasswordFormData {
     password: String,
 }
 
+/// The address of the connecting socket. Proxy headers are not trusted, so
+/// behind a reverse proxy this is the proxy's address.
+fn client_ip(req: &actix_web::HttpRequest) -> Option<String> {
+    req.peer_addr().map(|addr| addr.ip().to_string())
+}
+
+#[utoipa::path(
+    tag = "health",
+    responses(
+        (status = 200, description = "The server is up", body = String, content_type = "text/plain"),
+    )
+)]
 #[get("/")]
 async fn hello() -> impl Responder {
     HttpResponse::Ok().body("Hello world!")
 }
 
+#[utoipa::path(
+    tag = "auth",
+    request_body = CredentialsFormData,
+    responses(
+        (status = 201, description = "User registered, verification email sent", body = UserRegisterResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 409, description = "Email already registered or pending deletion", body = ErrorBody),
+        (status = 422, description = "Invalid email or weak password", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    )
+)]
 #[post("/register")]
-async fn register(repo: Data<Sqlite>, form: web::Json<FormData>) -> impl Responder {
-    log::info!("register!!!");
-    let service = UserRegisterService::new(repo.into_inner());
-    let controller = UserRegisterController::new(service);
+async fn register(
+    req: actix_web::HttpRequest,
+    container: Data<Container>,
+    form: web::Json<CredentialsFormData>,
+) -> impl Responder {
     let request = HttpRequest {
         body: UserRegisterRequest {
             email: form.email.clone(),
             password: form.password.clone(),
+            client_ip: client_ip(&req),
         },
     };
     let mut response = ActixHttpResponse::new();
 
-    controller.register(request, &mut response).await;
+    container
+        .user_register
+        .register(request, &mut response)
+        .await;
 
     response.response()
 }
 
+#[utoipa::path(
+    tag = "auth",
+    request_body = CredentialsFormData,
+    responses(
+        (status = 200, description = "Tokens, or a challenge when two-factor authentication is enabled", body = UserLoginOutcome),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "Invalid email or password", body = ErrorBody),
+        (status = 403, description = "Email address not verified", body = ErrorBody),
+        (status = 422, description = "Invalid email", body = ErrorBody),
+        (status = 429, description = "Account locked or rate limited", body = ErrorBody),
+    )
+)]
 #[post("/login")]
-async fn login(repo: Data<Sqlite>, form: web::Json<FormData>) -> impl Responder {
-    let service = UserLoginService::new(repo.into_inner());
-    let controller = UserLoginController::new(service);
+async fn login(
+    req: actix_web::HttpRequest,
+    container: Data<Container>,
+    form: web::Json<CredentialsFormData>,
+) -> impl Responder {
     let request = HttpRequest {
         body: UserLoginRequest {
             email: form.email.clone(),
             password: form.password.clone(),
+            client_ip: client_ip(&req),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container.user_login.login(request, &mut response).await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "auth",
+    request_body = TwoFactorLoginFormData,
+    responses(
+        (status = 200, description = "Logged in", body = UserLoginResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "Invalid challenge or code", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    )
+)]
+#[post("/login/2fa")]
+async fn login_second_factor(
+    req: actix_web::HttpRequest,
+    container: Data<Container>,
+    form: web::Json<TwoFactorLoginFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: TwoFactorLoginRequest {
+            challenge_token: form.challenge_token.clone(),
+            code: form.code.clone(),
+            client_ip: client_ip(&req),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .user_login
+        .login_second_factor(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "auth",
+    request_body = RefreshFormData,
+    responses(
+        (status = 200, description = "A new access and refresh token", body = TokenRefreshResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "Invalid, expired or reused refresh token", body = ErrorBody),
+    )
+)]
+#[post("/token/refresh")]
+async fn refresh_token(
+    container: Data<Container>,
+    form: web::Json<RefreshFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: TokenRefreshRequest {
+            refresh_token: form.refresh_token.clone(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .token_refresh
+        .refresh(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "admin",
+    params(UserListQuery),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "A page of users", body = UserPageResponse),
+        (status = 400, description = "Invalid filter, sort or cursor", body = ErrorBody),
+        (status = 401, description = "Missing or invalid access token", body = ErrorBody),
+        (status = 403, description = "Permission denied", body = ErrorBody),
+    )
+)]
+#[get("/users")]
+async fn list_users(
+    container: Data<Container>,
+    admin: Authorized<ListUsers>,
+    query: web::Query<UserListQuery>,
+) -> impl Responder {
+    let query = query.into_inner();
+    let request = HttpRequest {
+        body: UserSearchRequest {
+            requester: admin.requester,
+            email_contains: query.email,
+            email_domain: query.domain,
+            status: query.status,
+            sort: query.sort,
+            order: query.order,
+            cursor: query.cursor,
+            limit: query.limit,
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container.user_list.search(request, &mut response).await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "users",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The user", body = UserFindResponse),
+        (status = 401, description = "Missing or invalid access token", body = ErrorBody),
+        (status = 403, description = "Another user's account, without permission to list users", body = ErrorBody),
+        (status = 404, description = "User not found or invalid id", body = ErrorBody),
+    )
+)]
+#[get("/users/{id}")]
+async fn find_user(
+    container: Data<Container>,
+    user: AuthenticatedUser,
+    path: web::Path<String>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: UserFindRequest {
+            requester: Requester::User(user.user_id),
+            id: path.into_inner(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container.user_find.find_by_id(request, &mut response).await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "users",
+    request_body = ChangePasswordFormData,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Password changed", body = UserChangePasswordResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "Missing token or wrong current password", body = ErrorBody),
+        (status = 403, description = "Not the requester's own account", body = ErrorBody),
+        (status = 404, description = "User not found", body = ErrorBody),
+        (status = 409, description = "New password equals the current one", body = ErrorBody),
+        (status = 422, description = "Weak password", body = ErrorBody),
+        (status = 429, description = "Account locked or rate limited", body = ErrorBody),
+    )
+)]
+#[put("/users/{id}/password")]
+async fn change_password(
+    req: actix_web::HttpRequest,
+    container: Data<Container>,
+    user: AuthenticatedUser,
+    path: web::Path<String>,
+    form: web::Json<ChangePasswordFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: UserChangePasswordRequest {
+            requester_id: user.user_id,
+            user_id: path.into_inner(),
+            current_password: form.current_password.clone(),
+            new_password: form.new_password.clone(),
+            client_ip: client_ip(&req),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .user_change_password
+        .change_password(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "auth",
+    params(VerifyQuery),
+    responses(
+        (status = 200, description = "Email verified", body = MessageResponse),
+        (status = 400, description = "Missing, invalid or expired token", body = ErrorBody),
+    )
+)]
+#[get("/verify")]
+async fn verify_email(
+    container: Data<Container>,
+    query: web::Query<VerifyQuery>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: EmailVerifyRequest {
+            token: query.into_inner().token,
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container.email_verify.verify(request, &mut response).await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "auth",
+    request_body = EmailFormData,
+    responses(
+        (status = 200, description = "Sent if the account exists and is unverified", body = MessageResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 422, description = "Invalid email", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    )
+)]
+#[post("/verify/resend")]
+async fn resend_verification(
+    container: Data<Container>,
+    form: web::Json<EmailFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: EmailVerificationResendRequest {
+            email: form.email.clone(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .email_verification_resend
+        .resend(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "auth",
+    request_body = EmailFormData,
+    responses(
+        (status = 200, description = "Sent if the account exists", body = MessageResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 422, description = "Invalid email", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    )
+)]
+#[post("/password/forgot")]
+async fn forgot_password(
+    container: Data<Container>,
+    form: web::Json<EmailFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: PasswordForgotRequest {
+            email: form.email.clone(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .password_forgot
+        .forgot(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "auth",
+    request_body = PasswordResetFormData,
+    responses(
+        (status = 200, description = "Password changed", body = MessageResponse),
+        (status = 400, description = "Malformed request, invalid or expired token", body = ErrorBody),
+        (status = 422, description = "Weak password", body = ErrorBody),
+    )
+)]
+#[post("/password/reset")]
+async fn reset_password(
+    req: actix_web::HttpRequest,
+    container: Data<Container>,
+    form: web::Json<PasswordResetFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: PasswordResetRequest {
+            token: form.token.clone(),
+            new_password: form.new_password.clone(),
+            client_ip: client_ip(&req),
         },
     };
     let mut response = ActixHttpResponse::new();
 
-    controller.login(request, &mut response).await;
+    container.password_reset.reset(request, &mut response).await;
 
     response.response()
 }
 
-pub async fn create_server(host: &str, port: u16) -> std::io::Result<()> {
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+#[utoipa::path(
+    tag = "users",
+    request_body = PasswordFormData,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Account deleted; it can be restored for a while", body = MessageResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "Missing token or wrong password", body = ErrorBody),
+        (status = 404, description = "User not found", body = ErrorBody),
+        (status = 429, description = "Account locked or rate limited", body = ErrorBody),
+    )
+)]
+#[delete("/users/me")]
+async fn delete_account(
+    container: Data<Container>,
+    user: AuthenticatedUser,
+    form: web::Json<PasswordFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: AccountDeleteRequest {
+            user_id: user.user_id,
+            password: form.password.clone(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .account_delete
+        .delete(request, &mut response)
+        .await;
+
+    response.response()
+}
 
-    log::info!("starting HTTP server at http://localhost:8080");
+#[utoipa::path(
+    tag = "users",
+    request_body = PasswordFormData,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "A new secret to add to an authenticator", body = TwoFactorEnrollResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "Missing token or wrong password", body = ErrorBody),
+        (status = 404, description = "User not found", body = ErrorBody),
+        (status = 409, description = "Two-factor authentication already enabled", body = ErrorBody),
+        (status = 429, description = "Account locked or rate limited", body = ErrorBody),
+    )
+)]
+#[post("/users/me/2fa")]
+async fn enroll_two_factor(
+    container: Data<Container>,
+    user: AuthenticatedUser,
+    form: web::Json<PasswordFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: TwoFactorEnrollRequest {
+            user_id: user.user_id,
+            password: form.password.clone(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
 
-    let repository = Sqlite::new("users.db").await.unwrap();
+    container
+        .two_factor_enroll
+        .enroll(request, &mut response)
+        .await;
 
-    let repo = Data::new(repository);
+    response.response()
+}
 
-    HttpServer::new(move || {
+#[utoipa::path(
+    tag = "users",
+    request_body = TwoFactorCodeFormData,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Two-factor authentication enabled", body = TwoFactorConfirmResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "Missing token or invalid code", body = ErrorBody),
+        (status = 409, description = "Not enrolled or already enabled", body = ErrorBody),
+    )
+)]
+#[post("/users/me/2fa/confirm")]
+async fn confirm_two_factor(
+    container: Data<Container>,
+    user: AuthenticatedUser,
+    form: web::Json<TwoFactorCodeFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: TwoFactorConfirmRequest {
+            user_id: user.user_id,
+            code: form.code.clone(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .two_factor_confirm
+        .confirm(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "users",
+    request_body = CredentialsFormData,
+    responses(
+        (status = 200, description = "Account restored", body = MessageResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "No deleted account with these credentials", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    )
+)]
+#[post("/users/restore")]
+async fn restore_account(
+    container: Data<Container>,
+    form: web::Json<CredentialsFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: AccountRestoreRequest {
+            email: form.email.clone(),
+            password: form.password.clone(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .account_restore
+        .restore(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "User deleted", body = MessageResponse),
+        (status = 401, description = "Missing or invalid access token", body = ErrorBody),
+        (status = 403, description = "Permission denied", body = ErrorBody),
+        (status = 404, description = "User not found or invalid id", body = ErrorBody),
+    )
+)]
+#[delete("/users/{id}")]
+async fn delete_user(
+    container: Data<Container>,
+    admin: Authorized<DeleteUsers>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: UserDeleteRequest {
+            requester: admin.requester,
+            id: path.into_inner(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container.user_delete.delete(request, &mut response).await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "admin",
+    request_body = RoleFormData,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The user with the new role", body = UserFindResponse),
+        (status = 400, description = "Malformed request", body = ErrorBody),
+        (status = 401, description = "Missing or invalid access token", body = ErrorBody),
+        (status = 403, description = "Permission denied", body = ErrorBody),
+        (status = 404, description = "User not found or invalid id", body = ErrorBody),
+        (status = 409, description = "Would demote the last admin", body = ErrorBody),
+        (status = 422, description = "Unknown role", body = ErrorBody),
+    )
+)]
+#[put("/users/{id}/role")]
+async fn assign_role(
+    container: Data<Container>,
+    admin: Authorized<ManageRoles>,
+    path: web::Path<String>,
+    form: web::Json<RoleFormData>,
+) -> impl Responder {
+    let request = HttpRequest {
+        body: UserRoleAssignRequest {
+            requester: admin.requester,
+            user_id: path.into_inner(),
+            role: form.role.clone(),
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .user_role_assign
+        .assign(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+#[utoipa::path(
+    tag = "admin",
+    params(AuditLogQuery),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Matching entries, newest first", body = AuditLogResponse),
+        (status = 400, description = "Invalid filter", body = ErrorBody),
+        (status = 401, description = "Missing or invalid access token", body = ErrorBody),
+        (status = 403, description = "Permission denied", body = ErrorBody),
+    )
+)]
+#[get("/audit")]
+async fn audit_log(
+    container: Data<Container>,
+    admin: Authorized<ViewAuditLog>,
+    query: web::Query<AuditLogQuery>,
+) -> impl Responder {
+    let query = query.into_inner();
+    let request = HttpRequest {
+        body: AuditLogQueryRequest {
+            requester: admin.requester,
+            user_id: query.user_id,
+            event_type: query.event_type,
+            from: query.from,
+            until: query.until,
+            limit: query.limit,
+        },
+    };
+    let mut response = ActixHttpResponse::new();
+
+    container
+        .audit_log_query
+        .query(request, &mut response)
+        .await;
+
+    response.response()
+}
+
+/// Calls `$then!` with every handler of the API. It is the one list both
+/// [`configure`] registers and the OpenAPI document describes, so a handler
+/// cannot be served undocumented or documented but not served.
+macro_rules! with_handlers {
+    ($then:ident) => {
+        $then! {
+            hello,
+            register,
+            login,
+            login_second_factor,
+            refresh_token,
+            list_users,
+            find_user,
+            change_password,
+            verify_email,
+            resend_verification,
+            forgot_password,
+            reset_password,
+            delete_account,
+            enroll_two_factor,
+            confirm_two_factor,
+            restore_account,
+            delete_user,
+            assign_role,
+            audit_log,
+        }
+    };
+}
+pub(crate) use with_handlers;
+
+/// Registers every route. Expects a `Data<Container>` and a
+/// `Data<dyn TokenIssuer>` in the application data.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::JsonConfig::default().error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error));
+    macro_rules! register {
+        ($($handler:ident),* $(,)?) => {
+            $(cfg.service($handler);)*
+        };
+    }
+    with_handlers!(register);
+    cfg.service(SwaggerUi::new("/swagger-ui/{_:.*}").url("/openapi.json", ApiDoc::openapi()));
+}
+
+pub async fn create_server(config: Config) -> std::io::Result<()> {
+    env_logger::Builder::new()
+        .parse_filters(&config.log.filter)
+        .init();
+
+    let (host, port) = (config.server.host.clone(), config.server.port);
+    log::info!("starting HTTP server at http://{}:{}", host, port);
+
+    let repositories = Repositories::open(&config.database)
+        .await
+        .map_err(|e| std::io::Error::other(format!("could not open database: {:#}", e)))?
+        .with_audit_log(&config.audit)
+        .map_err(|e| std::io::Error::other(format!("could not open audit log: {}", e)))?;
+    let token_issuer = create_token_issuer(&config.auth);
+    let mailer = create_mailer(&config.mail)?;
+    let container = Data::new(Container::new(
+        &config,
+        repositories,
+        token_issuer.clone(),
+        mailer,
+    ));
+    if let Some(relay) = container.outbox_relay.clone() {
+        actix_web::rt::spawn(async move { relay.run().await });
+    }
+    let token_issuer: Data<dyn TokenIssuer> = Data::from(token_issuer);
+    let rate_limiter = create_rate_limiter(&config.rate_limit);
+
+    let mut server = HttpServer::new(move || {
         App::new()
-            .app_data(repo.clone())
+            .app_data(container.clone())
+            .app_data(token_issuer.clone())
+            .wrap(rate_limiter.clone())
             .wrap(middleware::Logger::default())
-            .service(hello)
-            .service(register)
-            .service(login)
-    })
-    .bind((host, port))?
-    .run()
-    .await
+            .configure(configure)
+    });
+    if let Some(workers) = config.server.workers {
+        server = server.workers(workers);
+    }
+
+    server.bind((host, port))?.run().await
+}
+
+fn json_error(error: JsonPayloadError, _: &actix_web::HttpRequest) -> actix_web::Error {
+    invalid_request(error.to_string())
+}
+
+fn query_error(error: QueryPayloadError, _: &actix_web::HttpRequest) -> actix_web::Error {
+    invalid_request(error.to_string())
+}
+
+fn invalid_request(message: String) -> actix_web::Error {
+    let body = ErrorBody::new("invalid_request", &message);
+    InternalError::from_response(message, HttpResponse::BadRequest().json(body)).into()
+}
+
+fn create_token_issuer(config: &AuthConfig) -> Arc<dyn TokenIssuer> {
+    let secret = config.jwt_secret.clone().unwrap_or_else(|| {
+        log::warn!(
+            "auth.jwt_secret is not set, using a random key: tokens will not survive a restart"
+        );
+        rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 64)
+    });
+
+    Arc::new(JwtTokenIssuer::new(
+        secret.as_bytes(),
+        Duration::from_secs(config.access_token_ttl_seconds),
+    ))
+}
+
+fn create_mailer(config: &MailConfig) -> std::io::Result<Arc<dyn Mailer>> {
+    log::info!("delivering outgoing email to {}", config.maildir);
+
+    Ok(Arc::new(MaildirMailer::new(&config.maildir, &config.from)?))
+}
+
+fn create_rate_limiter(config: &RateLimitConfig) -> RateLimiter {
+    let minute = Duration::from_secs(60);
+
+    RateLimiter::new(Arc::new(InMemoryRateLimitStore::new()))
+        .route(
+            "/login",
+            RouteLimit {
+                per_client: RateLimit::new(config.login_per_minute, minute),
+                per_email: Some(RateLimit::new(config.login_per_email_per_minute, minute)),
+            },
+        )
+        .route(
+            "/login/2fa",
+            RouteLimit {
+                per_client: RateLimit::new(config.login_per_minute, minute),
+                per_email: None,
+            },
+        )
+        .route(
+            "/users/restore",
+            RouteLimit {
+                per_client: RateLimit::new(config.login_per_minute, minute),
+                per_email: Some(RateLimit::new(config.login_per_email_per_minute, minute)),
+            },
+        )
+        .route(
+            "/users/me",
+            RouteLimit {
+                per_client: RateLimit::new(config.login_per_minute, minute),
+                per_email: None,
+            },
+        )
+        .route(
+            "/users/me/2fa",
+            RouteLimit {
+                per_client: RateLimit::new(config.login_per_minute, minute),
+                per_email: None,
+            },
+        )
+        .route(
+            "/users/{id}/password",
+            RouteLimit {
+                per_client: RateLimit::new(config.login_per_minute, minute),
+                per_email: None,
+            },
+        )
+        .route(
+            "/register",
+            RouteLimit {
+                per_client: RateLimit::new(config.register_per_minute, minute),
+                per_email: None,
+            },
+        )
+        .route(
+            "/password/forgot",
+            RouteLimit {
+                per_client: RateLimit::new(config.mail_per_minute, minute),
+                per_email: Some(RateLimit::new(config.mail_per_email_per_minute, minute)),
+            },
+        )
+        .route(
+            "/verify/resend",
+            RouteLimit {
+                per_client: RateLimit::new(config.mail_per_minute, minute),
+                per_email: Some(RateLimit::new(config.mail_per_email_per_minute, minute)),
+            },
+        )
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use actix_web::{
+        dev::{Service, ServiceResponse},
+        http::{header, StatusCode},
+        test,
+        web::Data,
+        App,
+    };
+    use serde_json::{json, Value};
+
+    use crate::{
+        application::ports::token_issuer::TokenIssuer,
+        domain::{
+            common::{time, totp},
+            value_objects::{email::Email, role::Role},
+        },
+        infrastructure::{
+            config::{Config, RateLimitConfig},
+            container::{Container, Repositories},
+            in_memory_mailer::InMemoryMailer,
+            jwt_token_issuer::JwtTokenIssuer,
+        },
+    };
+
+    use super::{configure, create_rate_limiter};
+
+    async fn app(
+        mailer: Arc<InMemoryMailer>,
+    ) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>
+    {
+        app_with(mailer, Repositories::in_memory()).await
+    }
+
+    async fn app_with(
+        mailer: Arc<InMemoryMailer>,
+        repositories: Repositories,
+    ) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>
+    {
+        let token_issuer: Arc<dyn TokenIssuer> =
+            Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)));
+        let container = Container::new(
+            &Config::default(),
+            repositories,
+            token_issuer.clone(),
+            mailer,
+        );
+
+        test::init_service(
+            App::new()
+                .app_data(Data::new(container))
+                .app_data(Data::from(token_issuer))
+                .configure(configure),
+        )
+        .await
+    }
+
+    async fn post(
+        app: &impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let request = test::TestRequest::post()
+            .uri(uri)
+            .set_json(body)
+            .to_request();
+        let response = test::call_service(app, request).await;
+        (response.status(), test::read_body_json(response).await)
+    }
+
+    async fn verify(
+        app: &impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+        mailer: &InMemoryMailer,
+    ) -> StatusCode {
+        let body = mailer.sent().pop().unwrap().body;
+        let token = body
+            .split("?token=")
+            .nth(1)
+            .unwrap()
+            .split_whitespace()
+            .next();
+        let request = test::TestRequest::get()
+            .uri(&format!("/verify?token={}", token.unwrap()))
+            .to_request();
+        test::call_service(app, request).await.status()
+    }
+
+    fn credentials() -> Value {
+        json!({ "email": "test@example.com", "password": "TestPass123_" })
+    }
+
+    #[actix_web::test]
+    async fn registers_verifies_and_logs_in_against_in_memory_storage() {
+        let mailer = Arc::new(InMemoryMailer::new());
+        let app = app(mailer.clone()).await;
+
+        let (status, registered) = post(&app, "/register", credentials()).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _) = post(&app, "/login", credentials()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        assert_eq!(verify(&app, &mailer).await, StatusCode::OK);
+
+        let (status, login) = post(&app, "/login", credentials()).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/users/{}", registered["id"].as_str().unwrap()))
+            .insert_header((
+                header::AUTHORIZATION,
+                format!("Bearer {}", login["access_token"].as_str().unwrap()),
+            ))
+            .to_request();
+        let found: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(found["email"], "test@example.com");
+    }
+
+    #[actix_web::test]
+    async fn rejects_a_duplicate_registration_with_an_error_envelope() {
+        let app = app(Arc::new(InMemoryMailer::new())).await;
+
+        post(&app, "/register", credentials()).await;
+        let (status, body) = post(&app, "/register", credentials()).await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "user_already_exists");
+    }
+
+    #[actix_web::test]
+    async fn rejects_a_malformed_body_with_an_error_envelope() {
+        let app = app(Arc::new(InMemoryMailer::new())).await;
+
+        let (status, body) = post(&app, "/login", json!({ "email": "x" })).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "invalid_request");
+    }
+
+    #[actix_web::test]
+    async fn deleted_accounts_cannot_log_in_until_restored() {
+        let mailer = Arc::new(InMemoryMailer::new());
+        let app = app(mailer.clone()).await;
+        post(&app, "/register", credentials()).await;
+        verify(&app, &mailer).await;
+        let (_, login) = post(&app, "/login", credentials()).await;
+
+        let request = test::TestRequest::delete()
+            .uri("/users/me")
+            .insert_header((
+                header::AUTHORIZATION,
+                format!("Bearer {}", login["access_token"].as_str().unwrap()),
+            ))
+            .set_json(json!({ "password": "TestPass123_" }))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, request).await.status(),
+            StatusCode::OK
+        );
+
+        let (status, _) = post(&app, "/login", credentials()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = post(&app, "/users/restore", credentials()).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = post(&app, "/login", credentials()).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn only_admins_can_list_users() {
+        let mailer = Arc::new(InMemoryMailer::new());
+        let repositories = Repositories::in_memory();
+        let app = app_with(mailer.clone(), repositories.clone()).await;
+        post(&app, "/register", credentials()).await;
+        verify(&app, &mailer).await;
+        let (_, login) = post(&app, "/login", credentials()).await;
+        let list = || {
+            test::TestRequest::get()
+                .uri("/users?sort=email&limit=10")
+                .insert_header((
+                    header::AUTHORIZATION,
+                    format!("Bearer {}", login["access_token"].as_str().unwrap()),
+                ))
+                .to_request()
+        };
+
+        let response = test::call_service(&app, list()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let mut user = repositories
+            .users
+            .find_by_email(email)
+            .await
+            .unwrap()
+            .unwrap();
+        user.assign_role(Role::Admin);
+        repositories.users.save(user).await.unwrap();
+
+        let page: Value = test::call_and_read_body_json(&app, list()).await;
+        assert_eq!(page["users"][0]["role"], "admin");
+        assert_eq!(page["next_cursor"], Value::Null);
+    }
+
+    #[actix_web::test]
+    async fn users_can_only_look_up_themselves() {
+        let mailer = Arc::new(InMemoryMailer::new());
+        let app = app(mailer.clone()).await;
+        let (_, registered) = post(&app, "/register", credentials()).await;
+        verify(&app, &mailer).await;
+        let other = json!({ "email": "other@example.com", "password": "TestPass123_" });
+        let (_, other_registered) = post(&app, "/register", other.clone()).await;
+        verify(&app, &mailer).await;
+        let (_, login) = post(&app, "/login", other).await;
+        let find = |id: &Value| {
+            test::TestRequest::get()
+                .uri(&format!("/users/{}", id.as_str().unwrap()))
+                .insert_header((
+                    header::AUTHORIZATION,
+                    format!("Bearer {}", login["access_token"].as_str().unwrap()),
+                ))
+                .to_request()
+        };
+
+        let response = test::call_service(&app, find(&registered["id"])).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = test::call_service(&app, find(&other_registered["id"])).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn records_logins_in_the_audit_log() {
+        let mailer = Arc::new(InMemoryMailer::new());
+        let repositories = Repositories::in_memory();
+        let app = app_with(mailer.clone(), repositories.clone()).await;
+        post(&app, "/register", credentials()).await;
+        verify(&app, &mailer).await;
+        let failed = test::TestRequest::post()
+            .uri("/login")
+            .peer_addr("203.0.113.7:4321".parse().unwrap())
+            .set_json(json!({ "email": "test@example.com", "password": "WrongPass123_" }))
+            .to_request();
+        test::call_service(&app, failed).await;
+
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let mut user = repositories
+            .users
+            .find_by_email(email)
+            .await
+            .unwrap()
+            .unwrap();
+        user.assign_role(Role::Admin);
+        repositories.users.save(user.clone()).await.unwrap();
+        let (_, login) = post(&app, "/login", credentials()).await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!(
+                "/audit?user_id={}&event_type=login_failed",
+                user.id()
+            ))
+            .insert_header((
+                header::AUTHORIZATION,
+                format!("Bearer {}", login["access_token"].as_str().unwrap()),
+            ))
+            .to_request();
+        let audit: Value = test::call_and_read_body_json(&app, request).await;
+
+        assert_eq!(audit["entries"].as_array().unwrap().len(), 1);
+        assert_eq!(audit["entries"][0]["ip"], "203.0.113.7");
+        assert_eq!(audit["entries"][0]["email"], "test@example.com");
+    }
+
+    #[actix_web::test]
+    async fn logs_in_with_a_second_factor_once_enabled() {
+        let mailer = Arc::new(InMemoryMailer::new());
+        let app = app(mailer.clone()).await;
+        post(&app, "/register", credentials()).await;
+        verify(&app, &mailer).await;
+        let (_, login) = post(&app, "/login", credentials()).await;
+        let bearer = format!("Bearer {}", login["access_token"].as_str().unwrap());
+
+        let request = test::TestRequest::post()
+            .uri("/users/me/2fa")
+            .insert_header((header::AUTHORIZATION, bearer.clone()))
+            .set_json(json!({ "password": "WrongPass123_" }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let request = test::TestRequest::post()
+            .uri("/users/me/2fa")
+            .insert_header((header::AUTHORIZATION, bearer.clone()))
+            .set_json(json!({ "password": "TestPass123_" }))
+            .to_request();
+        let enrolled: Value = test::call_and_read_body_json(&app, request).await;
+        let secret = totp::decode_secret(enrolled["secret"].as_str().unwrap()).unwrap();
+        assert!(enrolled["otpauth_uri"]
+            .as_str()
+            .unwrap()
+            .starts_with("otpauth://totp/"));
+
+        let previous_step = totp::step(time::now()) - 1;
+        let request = test::TestRequest::post()
+            .uri("/users/me/2fa/confirm")
+            .insert_header((header::AUTHORIZATION, bearer))
+            .set_json(json!({ "code": format!("{:06}", totp::code(&secret, previous_step)) }))
+            .to_request();
+        let confirmed: Value = test::call_and_read_body_json(&app, request).await;
+        let recovery_code = confirmed["recovery_codes"][0].as_str().unwrap().to_string();
+
+        let (status, challenge) = post(&app, "/login", credentials()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(challenge["second_factor_required"], true);
+        assert!(challenge.get("access_token").is_none());
+
+        let challenge_token = challenge["challenge_token"].as_str().unwrap();
+        let (status, body) = post(
+            &app,
+            "/login/2fa",
+            json!({ "challenge_token": challenge_token, "code": "000000x" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["code"], "invalid_two_factor_code");
+
+        let (status, tokens) = post(
+            &app,
+            "/login/2fa",
+            json!({ "challenge_token": challenge_token, "code": recovery_code }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(tokens["access_token"].is_string());
+    }
+
+    #[actix_web::test]
+    async fn limits_requests_that_send_mail() {
+        let config = RateLimitConfig::default();
+        let token_issuer: Arc<dyn TokenIssuer> =
+            Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)));
+        let container = Container::new(
+            &Config::default(),
+            Repositories::in_memory(),
+            token_issuer.clone(),
+            Arc::new(InMemoryMailer::new()),
+        );
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(container))
+                .app_data(Data::from(token_issuer))
+                .wrap(create_rate_limiter(&config))
+                .configure(configure),
+        )
+        .await;
+
+        for path in ["/password/forgot", "/verify/resend"] {
+            let mut statuses = Vec::new();
+            for client in 0..=config.mail_per_email_per_minute {
+                let request = test::TestRequest::post()
+                    .uri(path)
+                    .peer_addr(format!("10.0.0.{}:1234", client).parse().unwrap())
+                    .set_json(json!({ "email": "test@example.com" }))
+                    .to_request();
+                statuses.push(test::call_service(&app, request).await.status());
+            }
+
+            let (last, allowed) = statuses.split_last().unwrap();
+            assert!(allowed.iter().all(|status| status.is_success()));
+            assert_eq!(*last, StatusCode::TOO_MANY_REQUESTS);
+        }
+    }
+
+    #[actix_web::test]
+    async fn limits_account_deletion_attempts() {
+        let config = RateLimitConfig::default();
+        let token_issuer: Arc<dyn TokenIssuer> =
+            Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)));
+        let container = Container::new(
+            &Config::default(),
+            Repositories::in_memory(),
+            token_issuer.clone(),
+            Arc::new(InMemoryMailer::new()),
+        );
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(container))
+                .app_data(Data::from(token_issuer))
+                .wrap(create_rate_limiter(&config))
+                .configure(configure),
+        )
+        .await;
+
+        let mut statuses = Vec::new();
+        for _ in 0..=config.login_per_minute {
+            let request = test::TestRequest::delete()
+                .uri("/users/me")
+                .peer_addr("10.0.0.1:1234".parse().unwrap())
+                .set_json(json!({ "password": "WrongPass123_" }))
+                .to_request();
+            statuses.push(test::call_service(&app, request).await.status());
+        }
+
+        let (last, refused) = statuses.split_last().unwrap();
+        assert!(refused
+            .iter()
+            .all(|status| *status == StatusCode::UNAUTHORIZED));
+        assert_eq!(*last, StatusCode::TOO_MANY_REQUESTS);
+    }
 }
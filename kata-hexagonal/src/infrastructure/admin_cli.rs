@@ -0,0 +1,572 @@
+use std::{
+    io::{self, BufRead},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::application::{
+    account_purge_service::AccountPurgeService,
+    application_error::ApplicationError,
+    authorizer::Requester,
+    dtos::{
+        PasswordForceResetRequest, UserDeleteRequest, UserFindByEmailRequest, UserFindRequest,
+        UserFindResponse, UserRegisterRequest, UserRoleAssignRequest,
+    },
+    email_verification_sender::EmailVerificationSender,
+    password_force_reset_service::PasswordForceResetService,
+    password_reset_sender::PasswordResetSender,
+    ports::mailer::Mailer,
+    user_delete_service::UserDeleteService,
+    user_find_service::UserFindService,
+    user_list_service::UserListService,
+    user_register_service::UserRegisterService,
+    user_role_assign_service::UserRoleAssignService,
+};
+
+use super::{
+    config::{Cli, Config},
+    container::Repositories,
+    in_memory_event_bus::InMemoryEventBus,
+};
+
+/// The request was refused: invalid input, unknown user, duplicate email.
+pub const EXIT_REJECTED: u8 = 1;
+/// Bad arguments or configuration.
+pub const EXIT_USAGE: u8 = 2;
+/// Storage, mail or file system failure.
+pub const EXIT_UNAVAILABLE: u8 = 3;
+
+/// Command line of the `users-admin` tool. Users are given by id or email.
+#[derive(Parser, Debug)]
+#[command(
+    name = "users-admin",
+    version,
+    about = "Manage the accounts of the user service"
+)]
+pub struct AdminCli {
+    /// Path to a TOML config file [env: KATA_CONFIG]
+    #[arg(short, long, global = true)]
+    pub config: Option<PathBuf>,
+    /// SQLite database file
+    #[arg(long, global = true)]
+    pub database: Option<String>,
+    /// How results are printed
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    pub format: OutputFormat,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+impl AdminCli {
+    /// The server flags that also apply here, for loading the shared config.
+    pub fn server_cli(&self) -> Cli {
+        Cli {
+            config: self.config.clone(),
+            database: self.database.clone(),
+            ..Cli::default()
+        }
+    }
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Create a user, reading the password from standard input
+    Create { email: String },
+    /// List every user
+    List,
+    /// Show a single user
+    Show { user: String },
+    /// Permanently delete a user, skipping the restore window
+    Delete { user: String },
+    /// Permanently remove accounts deleted longer ago than the restore window
+    Purge,
+    /// Give a user the `user` or `admin` role
+    SetRole { user: String, role: String },
+    /// Revoke a user's password and email them a reset link
+    ResetPassword { user: String },
+    /// Write every user as JSON, to a file or standard output
+    Export {
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AdminError {
+    #[error(transparent)]
+    Application(#[from] ApplicationError),
+    #[error("Could not read the password: {0}")]
+    Input(#[from] io::Error),
+}
+
+impl AdminError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AdminError::Application(ApplicationError::Repository(_))
+            | AdminError::Application(ApplicationError::Token(_)) => EXIT_UNAVAILABLE,
+            AdminError::Application(_) => EXIT_REJECTED,
+            AdminError::Input(_) => EXIT_USAGE,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Output {
+    User(UserFindResponse),
+    Users(Vec<UserFindResponse>),
+    Export(Vec<UserFindResponse>),
+    Message(String),
+}
+
+impl Output {
+    /// Exports are always JSON, whatever the format asked for.
+    pub fn render(&self, format: OutputFormat) -> String {
+        match (self, format) {
+            (Output::Export(users), _) => to_json(users),
+            (Output::User(user), OutputFormat::Table) => render_table(std::slice::from_ref(user)),
+            (Output::User(user), OutputFormat::Json) => to_json(user),
+            (Output::Users(users), OutputFormat::Table) => render_table(users),
+            (Output::Users(users), OutputFormat::Json) => to_json(users),
+            (Output::Message(message), OutputFormat::Table) => message.clone(),
+            (Output::Message(message), OutputFormat::Json) => {
+                to_json(&serde_json::json!({ "message": message }))
+            }
+        }
+    }
+}
+
+pub struct Admin {
+    register: UserRegisterService,
+    find: UserFindService,
+    list: UserListService,
+    delete: UserDeleteService,
+    force_reset: PasswordForceResetService,
+    purge: AccountPurgeService,
+    assign_role: UserRoleAssignService,
+}
+
+impl Admin {
+    pub fn new(config: &Config, repositories: Repositories, mailer: Arc<dyn Mailer>) -> Self {
+        // The relay is not run here: events stay in the outbox until the
+        // server delivers them.
+        let events = Arc::new(InMemoryEventBus::new());
+        events.forward_to(Arc::new(PasswordResetSender::new(
+            repositories.password_reset_tokens.clone(),
+            mailer.clone(),
+            config.mail.password_reset_url.clone(),
+        )));
+        let (event_publisher, _) = repositories.event_publisher(events);
+        let users = repositories.users;
+        let verification_sender = EmailVerificationSender::new(
+            repositories.email_verification_tokens,
+            mailer,
+            config.mail.verify_url.clone(),
+        );
+
+        Admin {
+            register: UserRegisterService::new(
+                users.clone(),
+                verification_sender,
+                event_publisher.clone(),
+                repositories.audit_log.clone(),
+            ),
+            find: UserFindService::new(users.clone()),
+            list: UserListService::new(users.clone()),
+            delete: UserDeleteService::new(users.clone(), event_publisher.clone()),
+            force_reset: PasswordForceResetService::new(
+                users.clone(),
+                repositories.refresh_tokens.clone(),
+                event_publisher,
+            ),
+            purge: AccountPurgeService::new(users.clone()),
+            assign_role: UserRoleAssignService::new(users),
+        }
+    }
+
+    pub async fn run(
+        &self,
+        command: &Command,
+        input: &mut dyn BufRead,
+    ) -> Result<Output, AdminError> {
+        match command {
+            Command::Create { email } => {
+                let password = read_password(input)?;
+                let created = self
+                    .register
+                    .register(UserRegisterRequest {
+                        email: email.clone(),
+                        password,
+                        client_ip: None,
+                    })
+                    .await?;
+                Ok(Output::User(self.find_user(&created.id).await?))
+            }
+            Command::List => Ok(Output::Users(self.list.list().await?.users)),
+            Command::Show { user } => Ok(Output::User(self.find_user(user).await?)),
+            Command::Delete { user } => {
+                let id = self.find_user(user).await?.id;
+                let response = self
+                    .delete
+                    .delete(UserDeleteRequest {
+                        requester: Requester::System,
+                        id,
+                    })
+                    .await?;
+                Ok(Output::Message(response.message))
+            }
+            Command::SetRole { user, role } => {
+                let id = self.find_user(user).await?.id;
+                let updated = self
+                    .assign_role
+                    .assign(UserRoleAssignRequest {
+                        requester: Requester::System,
+                        user_id: id,
+                        role: role.clone(),
+                    })
+                    .await?;
+                Ok(Output::User(updated))
+            }
+            Command::ResetPassword { user } => {
+                let id = self.find_user(user).await?.id;
+                let response = self
+                    .force_reset
+                    .force_reset(PasswordForceResetRequest { id })
+                    .await?;
+                Ok(Output::Message(response.message))
+            }
+            Command::Purge => {
+                let response = self.purge.purge().await?;
+                Ok(Output::Message(format!(
+                    "Purged {} deleted accounts",
+                    response.purged
+                )))
+            }
+            Command::Export { .. } => Ok(Output::Export(self.list.list().await?.users)),
+        }
+    }
+
+    async fn find_user(&self, user: &str) -> Result<UserFindResponse, ApplicationError> {
+        if user.contains('@') {
+            self.find
+                .find_by_email(UserFindByEmailRequest {
+                    email: user.to_string(),
+                })
+                .await
+        } else {
+            self.find
+                .find_by_id(UserFindRequest {
+                    requester: Requester::System,
+                    id: user.to_string(),
+                })
+                .await
+        }
+    }
+}
+
+/// Reads a single line so the password never shows up in the process list.
+fn read_password(input: &mut dyn BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    let password = line.trim_end_matches(['\r', '\n']).to_string();
+
+    if password.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "expected a password on standard input",
+        ));
+    }
+    Ok(password)
+}
+
+fn render_table(users: &[UserFindResponse]) -> String {
+    let id_width = users.iter().map(|u| u.id.len()).max().unwrap_or(0).max(2);
+    let email_width = users
+        .iter()
+        .map(|u| u.email.len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+
+    let mut lines = vec![format!(
+        "{:id_width$}  {:email_width$}  {:5}  VERIFIED",
+        "ID", "EMAIL", "ROLE"
+    )];
+    for user in users {
+        lines.push(format!(
+            "{:id_width$}  {:email_width$}  {:5}  {}",
+            user.id,
+            user.email,
+            user.role,
+            if user.email_verified { "yes" } else { "no" }
+        ));
+    }
+    lines.join("\n")
+}
+
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("DTOs always serialize")
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use clap::Parser;
+
+    use crate::{
+        application::application_error::ApplicationError,
+        domain::{
+            common::time,
+            entities::user::{User, ACCOUNT_RESTORE_WINDOW},
+            repositories::repository_error::RepositoryError,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            config::Config, container::Repositories, in_memory_mailer::InMemoryMailer,
+        },
+    };
+
+    use super::{
+        Admin, AdminCli, AdminError, Command, Output, OutputFormat, EXIT_REJECTED,
+        EXIT_UNAVAILABLE, EXIT_USAGE,
+    };
+
+    fn admin() -> (Admin, Arc<InMemoryMailer>) {
+        admin_with(Repositories::in_memory())
+    }
+
+    fn admin_with(repositories: Repositories) -> (Admin, Arc<InMemoryMailer>) {
+        let mailer = Arc::new(InMemoryMailer::new());
+        let admin = Admin::new(&Config::default(), repositories, mailer.clone());
+        (admin, mailer)
+    }
+
+    async fn create(admin: &Admin, email: &str) -> Output {
+        admin
+            .run(
+                &Command::Create {
+                    email: email.to_string(),
+                },
+                &mut "TestPass123_\n".as_bytes(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn creates_a_user_and_finds_it_by_id_or_email() {
+        let (admin, _) = admin();
+
+        let Output::User(created) = create(&admin, "test@example.com").await else {
+            panic!("expected a user");
+        };
+        let by_id = admin
+            .run(
+                &Command::Show {
+                    user: created.id.clone(),
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap();
+        let by_email = admin
+            .run(
+                &Command::Show {
+                    user: "test@example.com".to_string(),
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(by_id, Output::User(created.clone()));
+        assert_eq!(by_email, Output::User(created));
+    }
+
+    #[tokio::test]
+    async fn refuses_to_create_without_a_password() {
+        let (admin, _) = admin();
+
+        let error = admin
+            .run(
+                &Command::Create {
+                    email: "test@example.com".to_string(),
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.exit_code(), EXIT_USAGE);
+    }
+
+    #[tokio::test]
+    async fn deletes_a_user() {
+        let (admin, _) = admin();
+        create(&admin, "test@example.com").await;
+
+        admin
+            .run(
+                &Command::Delete {
+                    user: "test@example.com".to_string(),
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap();
+        let error = admin
+            .run(
+                &Command::Show {
+                    user: "test@example.com".to_string(),
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            AdminError::Application(ApplicationError::UserNotFound(_))
+        ));
+        assert_eq!(error.exit_code(), EXIT_REJECTED);
+    }
+
+    #[tokio::test]
+    async fn purges_accounts_past_the_restore_window() {
+        let repositories = Repositories::in_memory();
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.delete(time::now() - ACCOUNT_RESTORE_WINDOW - 1);
+        repositories.users.save(user).await.unwrap();
+        let (admin, _) = admin_with(repositories);
+
+        let output = admin
+            .run(&Command::Purge, &mut "".as_bytes())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output,
+            Output::Message("Purged 1 deleted accounts".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn assigns_a_role() {
+        let (admin, _) = admin();
+        create(&admin, "test@example.com").await;
+
+        let output = admin
+            .run(
+                &Command::SetRole {
+                    user: "test@example.com".to_string(),
+                    role: "admin".to_string(),
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        let Output::User(user) = output else {
+            panic!("expected the updated user");
+        };
+        assert_eq!(user.role, "admin");
+    }
+
+    #[tokio::test]
+    async fn forces_a_password_reset() {
+        let (admin, mailer) = admin();
+        create(&admin, "test@example.com").await;
+
+        admin
+            .run(
+                &Command::ResetPassword {
+                    user: "test@example.com".to_string(),
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        let reset_mail = mailer.sent().pop().unwrap();
+        assert_eq!(reset_mail.subject, "Reset your password");
+    }
+
+    #[tokio::test]
+    async fn lists_users_as_a_table_and_exports_them_as_json() {
+        let (admin, _) = admin();
+        create(&admin, "test@example.com").await;
+
+        let list = admin.run(&Command::List, &mut "".as_bytes()).await.unwrap();
+        let export = admin
+            .run(&Command::Export { output: None }, &mut "".as_bytes())
+            .await
+            .unwrap();
+
+        let table = list.render(OutputFormat::Table);
+        assert!(table.starts_with("ID"));
+        assert!(table
+            .lines()
+            .nth(1)
+            .unwrap()
+            .contains("test@example.com  user   no"));
+        let json: serde_json::Value =
+            serde_json::from_str(&export.render(OutputFormat::Table)).unwrap();
+        assert_eq!(json[0]["email"], "test@example.com");
+        assert_eq!(json[0]["email_verified"], false);
+    }
+
+    #[test]
+    fn renders_messages_in_both_formats() {
+        let output = Output::Message("User deleted".to_string());
+
+        assert_eq!(output.render(OutputFormat::Table), "User deleted");
+        assert_eq!(
+            output.render(OutputFormat::Json),
+            "{\n  \"message\": \"User deleted\"\n}"
+        );
+    }
+
+    #[test]
+    fn reports_storage_failures_with_their_own_exit_code() {
+        let error = AdminError::Application(
+            RepositoryError::Unavailable("database is locked".to_string()).into(),
+        );
+
+        assert_eq!(error.exit_code(), EXIT_UNAVAILABLE);
+    }
+
+    #[test]
+    fn parses_global_flags_after_the_subcommand() {
+        let cli = AdminCli::try_parse_from([
+            "users-admin",
+            "show",
+            "test@example.com",
+            "--format",
+            "json",
+            "--database",
+            "other.db",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cli.command,
+            Command::Show {
+                user: "test@example.com".to_string()
+            }
+        );
+        assert_eq!(cli.format, OutputFormat::Json);
+        assert_eq!(cli.server_cli().database, Some("other.db".to_string()));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::application::{
+    application_error::ApplicationError,
+    audit_log_query_service::AuditLogQueryService,
+    dtos::{AuditLogQueryRequest, AuditLogResponse},
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct AuditLogQueryController {
+    service: AuditLogQueryService,
+}
+
+impl AuditLogQueryController {
+    pub fn new(service: AuditLogQueryService) -> Self {
+        AuditLogQueryController { service }
+    }
+
+    pub async fn query<T: HttpResponse<Result<AuditLogResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<AuditLogQueryRequest>,
+        response: &mut T,
+    ) {
+        match self.service.query(request.body).await {
+            Ok(entries) => response.status(200).json(Ok(entries)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            audit_log_query_service::AuditLogQueryService,
+            authorizer::Requester,
+            dtos::{AuditLogQueryRequest, AuditLogResponse},
+            ports::audit_log::{AuditEntry, AuditEventType, AuditLog},
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_audit_log::InMemoryAuditLog,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::AuditLogQueryController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<AuditLogResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<AuditLogResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<AuditLogResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    async fn query(requester_role: Role, event_type: Option<&str>) -> MockResponse {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.assign_role(requester_role);
+        let _ = repo.save(user.clone()).await;
+        let audit_log = Arc::new(InMemoryAuditLog::new());
+        let _ = audit_log
+            .record(AuditEntry::new(
+                AuditEventType::LoginFailed,
+                user.email(),
+                None,
+                10,
+            ))
+            .await;
+        let controller = AuditLogQueryController::new(AuditLogQueryService::new(repo, audit_log));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .query(
+                HttpRequest {
+                    body: AuditLogQueryRequest {
+                        event_type: event_type.map(str::to_string),
+                        ..AuditLogQueryRequest::new(Requester::User(user.id()))
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn returns_the_entries() {
+        let response = query(Role::Admin, None).await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.data.unwrap().unwrap().entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn responds_forbidden_to_other_users() {
+        let response = query(Role::User, None).await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    #[tokio::test]
+    async fn responds_bad_request_for_an_unknown_event_type() {
+        let response = query(Role::Admin, Some("logout")).await;
+
+        assert_eq!(response.status, 400);
+    }
+}
@@ -0,0 +1,758 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use clap::Parser;
+use serde::Deserialize;
+
+use crate::domain::value_objects::lockout_policy::LockoutPolicy;
+
+/// Read when no `--config` or `KATA_CONFIG` is given, if present.
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+const MIN_JWT_SECRET_LENGTH: usize = 32;
+
+/// A lockout is added to the current time, so it has to stay far from
+/// overflowing; a year is longer than any account should stay locked.
+const MAX_LOCKOUT_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Could not read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("Invalid value {value:?} for environment variable {name}: {reason}")]
+    Env {
+        name: String,
+        value: String,
+        reason: String,
+    },
+    #[error("Invalid configuration:\n  - {}", .0.join("\n  - "))]
+    Invalid(Vec<String>),
+}
+
+/// Command line flags. They take precedence over environment variables,
+/// which take precedence over the config file. Secrets are deliberately
+/// not accepted here since arguments are visible to other processes.
+#[derive(Parser, Debug, Default)]
+#[command(version, about = "User management HTTP server")]
+pub struct Cli {
+    /// Path to a TOML config file [env: KATA_CONFIG]
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+    /// Address to bind to
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Port to bind to
+    #[arg(short, long)]
+    pub port: Option<u16>,
+    /// SQLite database file
+    #[arg(long)]
+    pub database: Option<String>,
+    /// Keep all data in memory; nothing survives a restart
+    #[arg(long)]
+    pub in_memory: bool,
+    /// Log filter, e.g. `info` or `kata_hexagonal=debug,actix_web=info`
+    #[arg(long)]
+    pub log: Option<String>,
+    /// Number of HTTP worker threads
+    #[arg(long)]
+    pub workers: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub log: LogConfig,
+    pub auth: AuthConfig,
+    pub mail: MailConfig,
+    pub rate_limit: RateLimitConfig,
+    pub audit: AuditConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    /// Defaults to the number of physical CPUs.
+    pub workers: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseBackend {
+    Sqlite,
+    Memory,
+}
+
+impl FromStr for DatabaseBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "sqlite" => Ok(DatabaseBackend::Sqlite),
+            "memory" => Ok(DatabaseBackend::Memory),
+            other => Err(format!(
+                "expected \"sqlite\" or \"memory\", got {:?}",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub backend: DatabaseBackend,
+    pub path: String,
+    /// Connections kept open to the SQLite file.
+    pub pool_size: u32,
+    pub busy_timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub filter: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// HMAC key for access tokens. A random key is generated when unset,
+    /// which invalidates every token on restart.
+    pub jwt_secret: Option<String>,
+    pub access_token_ttl_seconds: u64,
+    pub require_email_verification: bool,
+    /// Name authenticator apps show next to the account.
+    pub totp_issuer: String,
+    pub lockout: LockoutConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LockoutConfig {
+    pub max_attempts: u32,
+    pub lockout_seconds: u64,
+    pub max_lockout_seconds: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+    pub maildir: String,
+    pub from: String,
+    pub verify_url: String,
+    pub password_reset_url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditBackend {
+    /// Alongside the users, in the configured database.
+    Database,
+    /// Appended as JSON lines to `audit.path`.
+    File,
+}
+
+impl FromStr for AuditBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "database" => Ok(AuditBackend::Database),
+            "file" => Ok(AuditBackend::File),
+            other => Err(format!(
+                "expected \"database\" or \"file\", got {:?}",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditConfig {
+    pub backend: AuditBackend,
+    pub path: String,
+}
+
+/// Requests per minute allowed on the rate limited routes.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub login_per_minute: u32,
+    pub login_per_email_per_minute: u32,
+    pub register_per_minute: u32,
+    /// Requests that email a link: password forgot and verification resend.
+    pub mail_per_minute: u32,
+    pub mail_per_email_per_minute: u32,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            workers: None,
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            backend: DatabaseBackend::Sqlite,
+            path: "users.db".to_string(),
+            pool_size: 8,
+            busy_timeout_ms: 5000,
+        }
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        AuditConfig {
+            backend: AuditBackend::Database,
+            path: "audit.log".to_string(),
+        }
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            filter: "info".to_string(),
+        }
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            jwt_secret: None,
+            access_token_ttl_seconds: 15 * 60,
+            require_email_verification: true,
+            totp_issuer: "kata-hexagonal".to_string(),
+            lockout: LockoutConfig::default(),
+        }
+    }
+}
+
+impl Default for LockoutConfig {
+    fn default() -> Self {
+        let policy = LockoutPolicy::default();
+        LockoutConfig {
+            max_attempts: policy.max_attempts,
+            lockout_seconds: policy.lockout_seconds,
+            max_lockout_seconds: policy.max_lockout_seconds,
+        }
+    }
+}
+
+impl LockoutConfig {
+    pub fn to_policy(&self) -> LockoutPolicy {
+        LockoutPolicy {
+            max_attempts: self.max_attempts,
+            lockout_seconds: self.lockout_seconds,
+            max_lockout_seconds: self.max_lockout_seconds,
+        }
+    }
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        MailConfig {
+            maildir: "maildir".to_string(),
+            from: "no-reply@localhost".to_string(),
+            verify_url: "http://localhost:8080/verify".to_string(),
+            password_reset_url: "http://localhost:8080/password/reset".to_string(),
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            login_per_minute: 10,
+            login_per_email_per_minute: 5,
+            register_per_minute: 5,
+            mail_per_minute: 5,
+            mail_per_email_per_minute: 2,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file, then applies environment variables and command
+    /// line flags on top, and validates the result.
+    pub fn load(cli: &Cli) -> Result<Config, ConfigError> {
+        Self::load_with_env(cli, |name| std::env::var(name).ok())
+    }
+
+    pub fn load_with_env(
+        cli: &Cli,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Config, ConfigError> {
+        let path = cli
+            .config
+            .clone()
+            .or_else(|| env("KATA_CONFIG").map(PathBuf::from));
+        let mut config = match path {
+            Some(path) => Self::from_file(&path)?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_PATH))?
+            }
+            None => Config::default(),
+        };
+
+        config.apply_env(&env)?;
+        config.apply_cli(cli);
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Config, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn apply_env(&mut self, env: &impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        override_from(env, "KATA_SERVER_HOST", &mut self.server.host)?;
+        override_from(env, "KATA_SERVER_PORT", &mut self.server.port)?;
+        if let Some(workers) = parse_env(env, "KATA_SERVER_WORKERS")? {
+            self.server.workers = Some(workers);
+        }
+        override_from(env, "KATA_DATABASE_BACKEND", &mut self.database.backend)?;
+        override_from(env, "KATA_DATABASE_PATH", &mut self.database.path)?;
+        override_from(env, "KATA_DATABASE_POOL_SIZE", &mut self.database.pool_size)?;
+        override_from(
+            env,
+            "KATA_DATABASE_BUSY_TIMEOUT_MS",
+            &mut self.database.busy_timeout_ms,
+        )?;
+        override_from(env, "KATA_LOG_FILTER", &mut self.log.filter)?;
+        if let Some(secret) = env("KATA_AUTH_JWT_SECRET") {
+            self.auth.jwt_secret = Some(secret);
+        }
+        override_from(
+            env,
+            "KATA_AUTH_ACCESS_TOKEN_TTL_SECONDS",
+            &mut self.auth.access_token_ttl_seconds,
+        )?;
+        override_from(
+            env,
+            "KATA_AUTH_REQUIRE_EMAIL_VERIFICATION",
+            &mut self.auth.require_email_verification,
+        )?;
+        override_from(env, "KATA_AUTH_TOTP_ISSUER", &mut self.auth.totp_issuer)?;
+        override_from(
+            env,
+            "KATA_AUTH_LOCKOUT_MAX_ATTEMPTS",
+            &mut self.auth.lockout.max_attempts,
+        )?;
+        override_from(
+            env,
+            "KATA_AUTH_LOCKOUT_LOCKOUT_SECONDS",
+            &mut self.auth.lockout.lockout_seconds,
+        )?;
+        override_from(
+            env,
+            "KATA_AUTH_LOCKOUT_MAX_LOCKOUT_SECONDS",
+            &mut self.auth.lockout.max_lockout_seconds,
+        )?;
+        override_from(env, "KATA_MAIL_MAILDIR", &mut self.mail.maildir)?;
+        override_from(env, "KATA_MAIL_FROM", &mut self.mail.from)?;
+        override_from(env, "KATA_MAIL_VERIFY_URL", &mut self.mail.verify_url)?;
+        override_from(
+            env,
+            "KATA_MAIL_PASSWORD_RESET_URL",
+            &mut self.mail.password_reset_url,
+        )?;
+        override_from(
+            env,
+            "KATA_RATE_LIMIT_LOGIN_PER_MINUTE",
+            &mut self.rate_limit.login_per_minute,
+        )?;
+        override_from(
+            env,
+            "KATA_RATE_LIMIT_LOGIN_PER_EMAIL_PER_MINUTE",
+            &mut self.rate_limit.login_per_email_per_minute,
+        )?;
+        override_from(
+            env,
+            "KATA_RATE_LIMIT_REGISTER_PER_MINUTE",
+            &mut self.rate_limit.register_per_minute,
+        )?;
+        override_from(
+            env,
+            "KATA_RATE_LIMIT_MAIL_PER_MINUTE",
+            &mut self.rate_limit.mail_per_minute,
+        )?;
+        override_from(
+            env,
+            "KATA_RATE_LIMIT_MAIL_PER_EMAIL_PER_MINUTE",
+            &mut self.rate_limit.mail_per_email_per_minute,
+        )?;
+        override_from(env, "KATA_AUDIT_BACKEND", &mut self.audit.backend)?;
+        override_from(env, "KATA_AUDIT_PATH", &mut self.audit.path)?;
+
+        Ok(())
+    }
+
+    fn apply_cli(&mut self, cli: &Cli) {
+        if let Some(host) = &cli.host {
+            self.server.host = host.clone();
+        }
+        if let Some(port) = cli.port {
+            self.server.port = port;
+        }
+        if let Some(workers) = cli.workers {
+            self.server.workers = Some(workers);
+        }
+        if let Some(path) = &cli.database {
+            self.database.backend = DatabaseBackend::Sqlite;
+            self.database.path = path.clone();
+        }
+        if cli.in_memory {
+            self.database.backend = DatabaseBackend::Memory;
+        }
+        if let Some(filter) = &cli.log {
+            self.log.filter = filter.clone();
+        }
+    }
+
+    /// Reports every problem at once rather than stopping at the first.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+
+        if self.server.host.trim().is_empty() {
+            errors.push("server.host must not be empty".to_string());
+        }
+        if self.server.port == 0 {
+            errors.push("server.port must be between 1 and 65535".to_string());
+        }
+        if self.server.workers == Some(0) {
+            errors.push("server.workers must be at least 1".to_string());
+        }
+        if self.database.backend == DatabaseBackend::Sqlite && self.database.path.trim().is_empty()
+        {
+            errors.push("database.path must not be empty when using sqlite".to_string());
+        }
+        if self.database.pool_size == 0 {
+            errors.push("database.pool_size must be at least 1".to_string());
+        }
+        if self.audit.backend == AuditBackend::File && self.audit.path.trim().is_empty() {
+            errors.push("audit.path must not be empty when using file".to_string());
+        }
+        if self.log.filter.trim().is_empty() {
+            errors.push("log.filter must not be empty".to_string());
+        }
+        if let Some(secret) = &self.auth.jwt_secret {
+            if secret.len() < MIN_JWT_SECRET_LENGTH {
+                errors.push(format!(
+                    "auth.jwt_secret must be at least {} characters",
+                    MIN_JWT_SECRET_LENGTH
+                ));
+            }
+        }
+        if self.auth.access_token_ttl_seconds == 0 {
+            errors.push("auth.access_token_ttl_seconds must be positive".to_string());
+        }
+        if self.auth.totp_issuer.trim().is_empty() {
+            errors.push("auth.totp_issuer must not be empty".to_string());
+        }
+        if self.auth.lockout.max_attempts > 0 && self.auth.lockout.lockout_seconds == 0 {
+            errors.push("auth.lockout.lockout_seconds must be positive".to_string());
+        }
+        if self.auth.lockout.max_lockout_seconds < self.auth.lockout.lockout_seconds {
+            errors.push(
+                "auth.lockout.max_lockout_seconds must not be below lockout_seconds".to_string(),
+            );
+        }
+        if self.auth.lockout.max_lockout_seconds > MAX_LOCKOUT_SECONDS {
+            errors.push(format!(
+                "auth.lockout.max_lockout_seconds must not exceed {}",
+                MAX_LOCKOUT_SECONDS
+            ));
+        }
+        for (name, value) in [
+            ("mail.maildir", &self.mail.maildir),
+            ("mail.from", &self.mail.from),
+            ("mail.verify_url", &self.mail.verify_url),
+            ("mail.password_reset_url", &self.mail.password_reset_url),
+        ] {
+            if value.trim().is_empty() {
+                errors.push(format!("{} must not be empty", name));
+            }
+        }
+        for (name, value) in [
+            (
+                "rate_limit.login_per_minute",
+                self.rate_limit.login_per_minute,
+            ),
+            (
+                "rate_limit.login_per_email_per_minute",
+                self.rate_limit.login_per_email_per_minute,
+            ),
+            (
+                "rate_limit.register_per_minute",
+                self.rate_limit.register_per_minute,
+            ),
+            (
+                "rate_limit.mail_per_minute",
+                self.rate_limit.mail_per_minute,
+            ),
+            (
+                "rate_limit.mail_per_email_per_minute",
+                self.rate_limit.mail_per_email_per_minute,
+            ),
+        ] {
+            if value == 0 {
+                errors.push(format!("{} must be at least 1", name));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
+    }
+}
+
+fn parse_env<T>(env: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<T>, ConfigError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    env(name)
+        .map(|value| {
+            value.parse().map_err(|e: T::Err| ConfigError::Env {
+                name: name.to_string(),
+                value: value.clone(),
+                reason: e.to_string(),
+            })
+        })
+        .transpose()
+}
+
+fn override_from<T>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    target: &mut T,
+) -> Result<(), ConfigError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Some(value) = parse_env(env, name)? {
+        *target = value;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, io::Write};
+
+    use super::{AuditBackend, Cli, Config, ConfigError, DatabaseBackend};
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    fn config_file(content: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn parses_a_partial_toml_file_over_the_defaults() {
+        let file = config_file(
+            r#"
+            [server]
+            port = 9090
+
+            [database]
+            backend = "memory"
+
+            [auth.lockout]
+            max_attempts = 3
+            "#,
+        );
+
+        let config = Config::from_file(file.path()).unwrap();
+
+        assert_eq!(config.server.port, 9090);
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.database.backend, DatabaseBackend::Memory);
+        assert_eq!(config.auth.lockout.max_attempts, 3);
+        assert_eq!(config.auth.lockout.lockout_seconds, 60);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let file = config_file("[server]\nprot = 9090\n");
+
+        assert!(matches!(
+            Config::from_file(file.path()),
+            Err(ConfigError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn reports_a_missing_config_file() {
+        let cli = Cli {
+            config: Some("does-not-exist.toml".into()),
+            ..Cli::default()
+        };
+
+        assert!(matches!(
+            Config::load_with_env(&cli, env(&[])),
+            Err(ConfigError::Read { .. })
+        ));
+    }
+
+    #[test]
+    fn environment_overrides_the_file_and_flags_override_the_environment() {
+        let file = config_file("[server]\nhost = \"0.0.0.0\"\nport = 9090\n");
+        let cli = Cli {
+            config: Some(file.path().to_path_buf()),
+            port: Some(7070),
+            ..Cli::default()
+        };
+
+        let config = Config::load_with_env(
+            &cli,
+            env(&[
+                ("KATA_SERVER_HOST", "10.0.0.1"),
+                ("KATA_SERVER_PORT", "8000"),
+                ("KATA_LOG_FILTER", "debug"),
+                ("KATA_AUTH_LOCKOUT_LOCKOUT_SECONDS", "30"),
+                ("KATA_AUTH_LOCKOUT_MAX_LOCKOUT_SECONDS", "600"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(config.server.host, "10.0.0.1");
+        assert_eq!(config.server.port, 7070);
+        assert_eq!(config.log.filter, "debug");
+        assert_eq!(config.auth.lockout.lockout_seconds, 30);
+        assert_eq!(config.auth.lockout.max_lockout_seconds, 600);
+    }
+
+    #[test]
+    fn reads_the_config_path_from_the_environment() {
+        let file = config_file("[server]\nport = 9090\n");
+
+        let config = Config::load_with_env(
+            &Cli::default(),
+            env(&[("KATA_CONFIG", file.path().to_str().unwrap())]),
+        )
+        .unwrap();
+
+        assert_eq!(config.server.port, 9090);
+    }
+
+    #[test]
+    fn reports_unparsable_environment_values() {
+        let error = Config::load_with_env(&Cli::default(), env(&[("KATA_SERVER_PORT", "eighty")]))
+            .unwrap_err();
+
+        assert!(error.to_string().contains("KATA_SERVER_PORT"));
+    }
+
+    #[test]
+    fn in_memory_flag_selects_the_memory_backend() {
+        let cli = Cli {
+            in_memory: true,
+            ..Cli::default()
+        };
+
+        let config = Config::load_with_env(&cli, env(&[])).unwrap();
+
+        assert_eq!(config.database.backend, DatabaseBackend::Memory);
+    }
+
+    #[test]
+    fn lists_every_validation_error() {
+        let mut config = Config::default();
+        config.server.port = 0;
+        config.server.workers = Some(0);
+        config.auth.jwt_secret = Some("short".to_string());
+
+        let Err(ConfigError::Invalid(errors)) = config.validate() else {
+            panic!("expected validation errors");
+        };
+
+        assert_eq!(errors.len(), 3);
+        assert!(errors[2].contains("auth.jwt_secret"));
+    }
+
+    #[test]
+    fn requires_at_least_one_database_connection() {
+        let config =
+            Config::load_with_env(&Cli::default(), env(&[("KATA_DATABASE_POOL_SIZE", "0")]));
+
+        assert!(
+            matches!(config, Err(ConfigError::Invalid(errors)) if errors[0].contains("pool_size"))
+        );
+    }
+
+    #[test]
+    fn rejects_a_lockout_longer_than_a_year() {
+        let config = Config::load_with_env(
+            &Cli::default(),
+            env(&[(
+                "KATA_AUTH_LOCKOUT_MAX_LOCKOUT_SECONDS",
+                "18446744073709551615",
+            )]),
+        );
+
+        assert!(
+            matches!(config, Err(ConfigError::Invalid(errors)) if errors[0].contains("max_lockout_seconds"))
+        );
+    }
+
+    #[test]
+    fn selects_the_audit_file_from_the_environment() {
+        let config = Config::load_with_env(
+            &Cli::default(),
+            env(&[
+                ("KATA_AUDIT_BACKEND", "file"),
+                ("KATA_AUDIT_PATH", "/var/log/kata/audit.log"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(config.audit.backend, AuditBackend::File);
+        assert_eq!(config.audit.path, "/var/log/kata/audit.log");
+    }
+}
@@ -0,0 +1,329 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    application::{
+        account_delete_service::AccountDeleteService,
+        account_restore_service::AccountRestoreService,
+        audit_log_query_service::AuditLogQueryService,
+        authorizer::Authorizer,
+        email_verification_resend_service::EmailVerificationResendService,
+        email_verification_sender::EmailVerificationSender,
+        email_verify_service::EmailVerifyService,
+        outbox_dispatcher::OutboxDispatcher,
+        password_forgot_service::PasswordForgotService,
+        password_reset_sender::PasswordResetSender,
+        password_reset_service::PasswordResetService,
+        ports::{
+            audit_log::AuditLog, event_publisher::EventPublisher, mailer::Mailer,
+            token_issuer::TokenIssuer,
+        },
+        token_refresh_service::TokenRefreshService,
+        two_factor_confirm_service::TwoFactorConfirmService,
+        two_factor_enroll_service::TwoFactorEnrollService,
+        user_change_password_service::UserChangePasswordService,
+        user_delete_service::UserDeleteService,
+        user_find_service::UserFindService,
+        user_list_service::UserListService,
+        user_login_service::UserLoginService,
+        user_register_service::UserRegisterService,
+        user_role_assign_service::UserRoleAssignService,
+    },
+    domain::repositories::{
+        email_verification_token_repository::EmailVerificationTokenRepository,
+        login_challenge_repository::LoginChallengeRepository, outbox_repository::OutboxRepository,
+        password_reset_token_repository::PasswordResetTokenRepository,
+        refresh_token_repository::RefreshTokenRepository,
+        two_factor_repository::TwoFactorRepository, user_repository::UserRepository,
+    },
+};
+
+use super::{
+    account_delete_controller::AccountDeleteController,
+    account_restore_controller::AccountRestoreController,
+    audit_log_query_controller::AuditLogQueryController,
+    config::{AuditBackend, AuditConfig, Config, DatabaseBackend, DatabaseConfig},
+    email_verification_resend_controller::EmailVerificationResendController,
+    email_verify_controller::EmailVerifyController,
+    in_memory_audit_log::InMemoryAuditLog,
+    in_memory_email_verification_token_repository::InMemoryEmailVerificationTokenRepository,
+    in_memory_event_bus::InMemoryEventBus,
+    in_memory_login_challenge_repository::InMemoryLoginChallengeRepository,
+    in_memory_password_reset_token_repository::InMemoryPasswordResetTokenRepository,
+    in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+    in_memory_two_factor_repository::InMemoryTwoFactorRepository,
+    in_memory_user_repository::InMemoryUserRepository,
+    json_lines_audit_log::JsonLinesAuditLog,
+    outbox_relay::OutboxRelay,
+    password_forgot_controller::PasswordForgotController,
+    password_reset_controller::PasswordResetController,
+    sqlite_user_repository::{PoolOptions, Sqlite},
+    token_refresh_controller::TokenRefreshController,
+    two_factor_confirm_controller::TwoFactorConfirmController,
+    two_factor_enroll_controller::TwoFactorEnrollController,
+    user_change_password_controller::UserChangePasswordController,
+    user_delete_controller::UserDeleteController,
+    user_find_controller::UserFindController,
+    user_list_controller::UserListController,
+    user_login_controller::UserLoginController,
+    user_register_controller::UserRegisterController,
+    user_role_assign_controller::UserRoleAssignController,
+};
+
+/// The storage adapters the use cases run against.
+#[derive(Clone)]
+pub struct Repositories {
+    pub users: Arc<dyn UserRepository>,
+    pub refresh_tokens: Arc<dyn RefreshTokenRepository>,
+    pub password_reset_tokens: Arc<dyn PasswordResetTokenRepository>,
+    pub email_verification_tokens: Arc<dyn EmailVerificationTokenRepository>,
+    pub two_factors: Arc<dyn TwoFactorRepository>,
+    pub login_challenges: Arc<dyn LoginChallengeRepository>,
+    /// Set when users are saved along with their events.
+    pub outbox: Option<Arc<dyn OutboxRepository>>,
+    pub audit_log: Arc<dyn AuditLog>,
+}
+
+impl Repositories {
+    /// Opens the backend selected in the configuration.
+    pub async fn open(config: &DatabaseConfig) -> anyhow::Result<Self> {
+        match config.backend {
+            DatabaseBackend::Sqlite => {
+                let options = PoolOptions {
+                    max_connections: config.pool_size,
+                    busy_timeout: Duration::from_millis(config.busy_timeout_ms),
+                };
+                Ok(Repositories::sqlite(Arc::new(
+                    Sqlite::with_pool(&config.path, options).await?,
+                )))
+            }
+            DatabaseBackend::Memory => {
+                log::warn!("using in-memory storage: data will not survive a restart");
+                Ok(Repositories::in_memory())
+            }
+        }
+    }
+
+    pub fn sqlite(sqlite: Arc<Sqlite>) -> Self {
+        Repositories {
+            users: sqlite.clone(),
+            refresh_tokens: sqlite.clone(),
+            password_reset_tokens: sqlite.clone(),
+            email_verification_tokens: sqlite.clone(),
+            two_factors: sqlite.clone(),
+            login_challenges: sqlite.clone(),
+            outbox: Some(sqlite.clone()),
+            audit_log: sqlite,
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        let refresh_tokens = Arc::new(InMemoryRefreshTokenRepository::new());
+        let password_reset_tokens = Arc::new(InMemoryPasswordResetTokenRepository::new());
+        let email_verification_tokens = Arc::new(InMemoryEmailVerificationTokenRepository::new());
+        let two_factors = Arc::new(InMemoryTwoFactorRepository::new());
+        let login_challenges = Arc::new(InMemoryLoginChallengeRepository::new());
+        let users = InMemoryUserRepository::new()
+            .owning(refresh_tokens.clone())
+            .owning(password_reset_tokens.clone())
+            .owning(email_verification_tokens.clone())
+            .owning(two_factors.clone())
+            .owning(login_challenges.clone());
+
+        Repositories {
+            users: Arc::new(users),
+            refresh_tokens,
+            password_reset_tokens,
+            email_verification_tokens,
+            two_factors,
+            login_challenges,
+            outbox: None,
+            audit_log: Arc::new(InMemoryAuditLog::new()),
+        }
+    }
+
+    /// Keeps the audit log in the database unless a file is configured.
+    pub fn with_audit_log(mut self, config: &AuditConfig) -> std::io::Result<Self> {
+        if config.backend == AuditBackend::File {
+            self.audit_log = Arc::new(JsonLinesAuditLog::open(&config.path)?);
+        }
+        Ok(self)
+    }
+
+    /// Where the use cases publish events: straight to `bus`, or, when the
+    /// events are stored in an outbox, to a relay that delivers it to `bus`.
+    pub fn event_publisher(
+        &self,
+        bus: Arc<dyn EventPublisher>,
+    ) -> (Arc<dyn EventPublisher>, Option<Arc<OutboxRelay>>) {
+        match &self.outbox {
+            Some(outbox) => {
+                let relay = Arc::new(OutboxRelay::new(OutboxDispatcher::new(outbox.clone(), bus)));
+                (relay.clone(), Some(relay))
+            }
+            None => (bus, None),
+        }
+    }
+}
+
+/// Composition root: every use case wired once at startup and shared by all
+/// workers, whichever adapters it was built with.
+pub struct Container {
+    pub repositories: Repositories,
+    /// For route guards; the use cases check their own permissions too.
+    pub authorizer: Authorizer,
+    /// Receives every domain event once it is stored; subscribe to react.
+    pub events: Arc<InMemoryEventBus>,
+    /// Delivers the outbox to `events`, to be run in the background.
+    pub outbox_relay: Option<Arc<OutboxRelay>>,
+    pub user_register: UserRegisterController,
+    pub user_login: UserLoginController,
+    pub token_refresh: TokenRefreshController,
+    pub two_factor_enroll: TwoFactorEnrollController,
+    pub two_factor_confirm: TwoFactorConfirmController,
+    pub user_find: UserFindController,
+    pub user_list: UserListController,
+    pub user_delete: UserDeleteController,
+    pub user_role_assign: UserRoleAssignController,
+    pub user_change_password: UserChangePasswordController,
+    pub email_verify: EmailVerifyController,
+    pub email_verification_resend: EmailVerificationResendController,
+    pub password_forgot: PasswordForgotController,
+    pub password_reset: PasswordResetController,
+    pub account_delete: AccountDeleteController,
+    pub account_restore: AccountRestoreController,
+    pub audit_log_query: AuditLogQueryController,
+}
+
+impl Container {
+    pub fn new(
+        config: &Config,
+        repositories: Repositories,
+        token_issuer: Arc<dyn TokenIssuer>,
+        mailer: Arc<dyn Mailer>,
+    ) -> Self {
+        let users = repositories.users.clone();
+        let events = Arc::new(InMemoryEventBus::new());
+        events.forward_to(Arc::new(PasswordResetSender::new(
+            repositories.password_reset_tokens.clone(),
+            mailer.clone(),
+            config.mail.password_reset_url.clone(),
+        )));
+        let (event_publisher, outbox_relay) = repositories.event_publisher(events.clone());
+        let verification_sender = || {
+            EmailVerificationSender::new(
+                repositories.email_verification_tokens.clone(),
+                mailer.clone(),
+                config.mail.verify_url.clone(),
+            )
+        };
+
+        let user_register = UserRegisterController::new(UserRegisterService::new(
+            users.clone(),
+            verification_sender(),
+            event_publisher.clone(),
+            repositories.audit_log.clone(),
+        ));
+        let user_login = UserLoginController::new(
+            UserLoginService::new(
+                users.clone(),
+                token_issuer.clone(),
+                repositories.refresh_tokens.clone(),
+                repositories.two_factors.clone(),
+                repositories.login_challenges.clone(),
+                event_publisher.clone(),
+                repositories.audit_log.clone(),
+            )
+            .require_verified_email(config.auth.require_email_verification)
+            .with_lockout_policy(config.auth.lockout.to_policy()),
+        );
+        let token_refresh = TokenRefreshController::new(TokenRefreshService::new(
+            users.clone(),
+            repositories.refresh_tokens.clone(),
+            token_issuer,
+        ));
+        let two_factor_enroll = TwoFactorEnrollController::new(
+            TwoFactorEnrollService::new(
+                users.clone(),
+                repositories.two_factors.clone(),
+                config.auth.totp_issuer.clone(),
+            )
+            .with_lockout_policy(config.auth.lockout.to_policy()),
+        );
+        let two_factor_confirm = TwoFactorConfirmController::new(TwoFactorConfirmService::new(
+            repositories.two_factors.clone(),
+        ));
+        let user_find = UserFindController::new(UserFindService::new(users.clone()));
+        let user_list = UserListController::new(UserListService::new(users.clone()));
+        let user_delete = UserDeleteController::new(UserDeleteService::new(
+            users.clone(),
+            event_publisher.clone(),
+        ));
+        let user_role_assign =
+            UserRoleAssignController::new(UserRoleAssignService::new(users.clone()));
+        let user_change_password = UserChangePasswordController::new(
+            UserChangePasswordService::new(
+                users.clone(),
+                repositories.refresh_tokens.clone(),
+                event_publisher.clone(),
+                repositories.audit_log.clone(),
+            )
+            .with_lockout_policy(config.auth.lockout.to_policy()),
+        );
+        let email_verify = EmailVerifyController::new(EmailVerifyService::new(
+            users.clone(),
+            repositories.email_verification_tokens.clone(),
+        ));
+        let email_verification_resend = EmailVerificationResendController::new(
+            EmailVerificationResendService::new(users.clone(), verification_sender()),
+        );
+        let password_forgot = PasswordForgotController::new(PasswordForgotService::new(
+            users.clone(),
+            event_publisher.clone(),
+        ));
+        let password_reset = PasswordResetController::new(PasswordResetService::new(
+            users.clone(),
+            repositories.password_reset_tokens.clone(),
+            repositories.refresh_tokens.clone(),
+            event_publisher.clone(),
+            repositories.audit_log.clone(),
+        ));
+        let account_delete = AccountDeleteController::new(
+            AccountDeleteService::new(
+                users.clone(),
+                repositories.refresh_tokens.clone(),
+                event_publisher.clone(),
+            )
+            .with_lockout_policy(config.auth.lockout.to_policy()),
+        );
+        let account_restore =
+            AccountRestoreController::new(AccountRestoreService::new(users.clone()));
+        let audit_log_query = AuditLogQueryController::new(AuditLogQueryService::new(
+            users.clone(),
+            repositories.audit_log.clone(),
+        ));
+
+        Container {
+            authorizer: Authorizer::new(users),
+            events,
+            outbox_relay,
+            repositories,
+            user_register,
+            user_login,
+            token_refresh,
+            two_factor_enroll,
+            two_factor_confirm,
+            user_find,
+            user_list,
+            user_delete,
+            user_role_assign,
+            user_change_password,
+            email_verify,
+            email_verification_resend,
+            password_forgot,
+            password_reset,
+            account_delete,
+            account_restore,
+            audit_log_query,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{EmailVerificationResendRequest, MessageResponse},
+    email_verification_resend_service::EmailVerificationResendService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct EmailVerificationResendController {
+    service: EmailVerificationResendService,
+}
+
+impl EmailVerificationResendController {
+    pub fn new(service: EmailVerificationResendService) -> Self {
+        EmailVerificationResendController { service }
+    }
+
+    pub async fn resend<T: HttpResponse<Result<MessageResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<EmailVerificationResendRequest>,
+        response: &mut T,
+    ) {
+        match self.service.resend(request.body).await {
+            Ok(resend_response) => response.status(200).json(Ok(resend_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            dtos::{EmailVerificationResendRequest, MessageResponse},
+            email_verification_resend_service::EmailVerificationResendService,
+            email_verification_sender::EmailVerificationSender,
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_email_verification_token_repository::InMemoryEmailVerificationTokenRepository,
+            in_memory_mailer::InMemoryMailer,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::EmailVerificationResendController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<MessageResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<MessageResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<MessageResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    async fn resend(email: &str) -> MockResponse {
+        let controller =
+            EmailVerificationResendController::new(EmailVerificationResendService::new(
+                Arc::new(InMemoryUserRepository::new()),
+                EmailVerificationSender::new(
+                    Arc::new(InMemoryEmailVerificationTokenRepository::new()),
+                    Arc::new(InMemoryMailer::new()),
+                    "http://localhost/verify".to_string(),
+                ),
+            ));
+        let mut response = MockResponse {
+            status: 0,
+            data: None,
+        };
+
+        controller
+            .resend(
+                HttpRequest {
+                    body: EmailVerificationResendRequest {
+                        email: email.to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn accepts_unknown_emails() {
+        let response = resend("unknown@example.com").await;
+
+        assert_eq!(response.status, 200);
+        assert!(response.data.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_a_malformed_email() {
+        let response = resend("not-an-email").await;
+
+        assert_eq!(response.status, 422);
+        assert!(response.data.unwrap().is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{EmailVerifyRequest, MessageResponse},
+    email_verify_service::EmailVerifyService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct EmailVerifyController {
+    service: EmailVerifyService,
+}
+
+impl EmailVerifyController {
+    pub fn new(service: EmailVerifyService) -> Self {
+        EmailVerifyController { service }
+    }
+
+    pub async fn verify<T: HttpResponse<Result<MessageResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<EmailVerifyRequest>,
+        response: &mut T,
+    ) {
+        match self.service.verify(request.body).await {
+            Ok(verify_response) => response.status(200).json(Ok(verify_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            dtos::{EmailVerifyRequest, MessageResponse},
+            email_verify_service::EmailVerifyService,
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_email_verification_token_repository::InMemoryEmailVerificationTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::EmailVerifyController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<MessageResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<MessageResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<MessageResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unknown_token_as_bad_request() {
+        let controller = EmailVerifyController::new(EmailVerifyService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            Arc::new(InMemoryEmailVerificationTokenRepository::new()),
+        ));
+        let mut response = MockResponse {
+            status: 0,
+            data: None,
+        };
+
+        controller
+            .verify(
+                HttpRequest {
+                    body: EmailVerifyRequest {
+                        token: "unknown".to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 400);
+        assert!(matches!(
+            response.data.unwrap().unwrap_err(),
+            ApplicationError::InvalidVerificationToken(_)
+        ));
+    }
+}
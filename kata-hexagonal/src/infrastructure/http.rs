@@ -1,3 +1,8 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::application::application_error::ApplicationError;
+
 pub struct HttpRequest<T> {
     pub body: T,
 }
@@ -6,3 +11,199 @@ pub trait HttpResponse<T> {
     fn status(&mut self, code: u16) -> &mut Self;
     fn json(&mut self, data: T) -> &mut Self;
 }
+
+/// The body of every error response: a stable `code` to match on, a human
+/// readable `message`, and per-rule `details` where there are several.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub code: String,
+    pub message: String,
+    pub details: Vec<ErrorDetail>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ErrorDetail {
+    pub code: String,
+    pub message: String,
+}
+
+impl ErrorBody {
+    pub fn new(code: &str, message: &str) -> Self {
+        ErrorBody {
+            code: code.to_string(),
+            message: message.to_string(),
+            details: Vec::new(),
+        }
+    }
+
+    /// Describes `error` for a client. Server errors are reduced to a generic
+    /// message so internals never leak.
+    pub fn from_error(error: &ApplicationError) -> Self {
+        if status_code(error) >= 500 {
+            return ErrorBody::new("internal_error", "Internal server error");
+        }
+
+        let mut body = ErrorBody::new(error_code(error), &error.to_string());
+        if let ApplicationError::InvalidPassword(password_error) = error {
+            body.details = password_error
+                .errors()
+                .iter()
+                .map(|rule| ErrorDetail {
+                    code: rule.code().to_string(),
+                    message: rule.to_string(),
+                })
+                .collect();
+        }
+        body
+    }
+}
+
+/// The HTTP status every application error is reported with.
+pub fn status_code(error: &ApplicationError) -> u16 {
+    match error {
+        ApplicationError::InvalidResetToken(_)
+        | ApplicationError::InvalidVerificationToken(_)
+        | ApplicationError::InvalidUserQuery(_)
+        | ApplicationError::InvalidAuditQuery(_) => 400,
+        ApplicationError::InvalidCredentials(_)
+        | ApplicationError::InvalidCurrentPassword(_)
+        | ApplicationError::InvalidRefreshToken(_)
+        | ApplicationError::RefreshTokenReuse(_)
+        | ApplicationError::InvalidLoginChallenge(_)
+        | ApplicationError::InvalidTwoFactorCode(_) => 401,
+        ApplicationError::UnverifiedEmail(_)
+        | ApplicationError::ForbiddenPasswordChange(_)
+        | ApplicationError::Forbidden(_) => 403,
+        ApplicationError::UserNotFound(_) | ApplicationError::InvalidId(_) => 404,
+        ApplicationError::ExistingUser(_)
+        | ApplicationError::AccountPendingDeletion(_)
+        | ApplicationError::EqualPassword(_)
+        | ApplicationError::TwoFactorAlreadyEnabled(_)
+        | ApplicationError::TwoFactorNotEnrolled(_)
+        | ApplicationError::LastAdmin(_) => 409,
+        ApplicationError::InvalidEmail(_)
+        | ApplicationError::InvalidPassword(_)
+        | ApplicationError::InvalidRole(_) => 422,
+        ApplicationError::AccountLocked(_) => 429,
+        ApplicationError::Token(_)
+        | ApplicationError::AuditLog(_)
+        | ApplicationError::Repository(_) => 500,
+    }
+}
+
+fn error_code(error: &ApplicationError) -> &'static str {
+    match error {
+        ApplicationError::InvalidEmail(_) => "invalid_email",
+        ApplicationError::InvalidPassword(_) => "invalid_password",
+        ApplicationError::InvalidId(_) => "invalid_id",
+        ApplicationError::InvalidRole(_) => "invalid_role",
+        ApplicationError::EqualPassword(_) => "equal_password",
+        ApplicationError::ExistingUser(_) => "user_already_exists",
+        ApplicationError::AccountPendingDeletion(_) => "account_pending_deletion",
+        ApplicationError::UserNotFound(_) => "user_not_found",
+        ApplicationError::InvalidCredentials(_) => "invalid_credentials",
+        ApplicationError::InvalidCurrentPassword(_) => "invalid_current_password",
+        ApplicationError::UnverifiedEmail(_) => "email_not_verified",
+        ApplicationError::AccountLocked(_) => "account_locked",
+        ApplicationError::InvalidLoginChallenge(_) => "invalid_login_challenge",
+        ApplicationError::InvalidTwoFactorCode(_) => "invalid_two_factor_code",
+        ApplicationError::TwoFactorAlreadyEnabled(_) => "two_factor_already_enabled",
+        ApplicationError::TwoFactorNotEnrolled(_) => "two_factor_not_enrolled",
+        ApplicationError::LastAdmin(_) => "last_admin",
+        ApplicationError::ForbiddenPasswordChange(_) | ApplicationError::Forbidden(_) => {
+            "forbidden"
+        }
+        ApplicationError::InvalidUserQuery(_) | ApplicationError::InvalidAuditQuery(_) => {
+            "invalid_query"
+        }
+        ApplicationError::InvalidRefreshToken(_) => "invalid_refresh_token",
+        ApplicationError::RefreshTokenReuse(_) => "refresh_token_reused",
+        ApplicationError::InvalidResetToken(_) => "invalid_reset_token",
+        ApplicationError::InvalidVerificationToken(_) => "invalid_verification_token",
+        ApplicationError::Token(_)
+        | ApplicationError::AuditLog(_)
+        | ApplicationError::Repository(_) => "internal_error",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        application::{
+            user_login_service::InvalidCredentialsError, user_register_service::ExistingUserError,
+        },
+        domain::{
+            repositories::repository_error::RepositoryError,
+            value_objects::{email::Email, password::Password},
+        },
+    };
+
+    use super::{status_code, ErrorBody, ErrorDetail};
+
+    #[test]
+    fn maps_errors_to_their_status() {
+        let invalid_email = Email::new("not-an-email".to_string()).unwrap_err();
+        let weak_password = Password::new("abc".to_string()).unwrap_err();
+
+        assert_eq!(status_code(&ExistingUserError {}.into()), 409);
+        assert_eq!(status_code(&InvalidCredentialsError {}.into()), 401);
+        assert_eq!(status_code(&invalid_email.into()), 422);
+        assert_eq!(status_code(&weak_password.into()), 422);
+        assert_eq!(
+            status_code(&RepositoryError::Unavailable("down".to_string()).into()),
+            500
+        );
+    }
+
+    #[test]
+    fn lists_each_unmet_password_rule() {
+        let error = Password::new("abc".to_string()).unwrap_err();
+
+        let body = ErrorBody::from_error(&error.into());
+
+        assert_eq!(body.code, "invalid_password");
+        assert_eq!(
+            body.details,
+            vec![
+                ErrorDetail {
+                    code: "too_short".to_string(),
+                    message: "is too short".to_string(),
+                },
+                ErrorDetail {
+                    code: "must_contain_number".to_string(),
+                    message: "must contain a number".to_string(),
+                },
+                ErrorDetail {
+                    code: "must_contain_uppercase".to_string(),
+                    message: "must contain a uppercase".to_string(),
+                },
+                ErrorDetail {
+                    code: "must_contain_underscore".to_string(),
+                    message: "must contain an underscore".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn names_known_errors() {
+        let body = ErrorBody::from_error(&ExistingUserError {}.into());
+
+        assert_eq!(
+            body,
+            ErrorBody::new("user_already_exists", "User already exists with this email")
+        );
+    }
+
+    #[test]
+    fn hides_the_cause_of_server_errors() {
+        let error = RepositoryError::Unavailable("disk I/O error at /var/db".to_string());
+
+        let body = ErrorBody::from_error(&error.into());
+
+        assert_eq!(
+            body,
+            ErrorBody::new("internal_error", "Internal server error")
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::{cmp::Reverse, sync::Mutex};
+
+use async_trait::async_trait;
+
+use crate::application::ports::audit_log::{AuditEntry, AuditLog, AuditLogError, AuditQuery};
+
+#[derive(Default)]
+pub struct InMemoryAuditLog {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl InMemoryAuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AuditLog for InMemoryAuditLog {
+    async fn record(&self, entry: AuditEntry) -> Result<(), AuditLogError> {
+        let mut entries = match self.entries.lock() {
+            Ok(lock) => lock,
+            _ => return Err(AuditLogError("Could not unlock".to_string())),
+        };
+        entries.push(entry);
+        Ok(())
+    }
+
+    async fn query(&self, query: AuditQuery) -> Result<Vec<AuditEntry>, AuditLogError> {
+        let entries = match self.entries.lock() {
+            Ok(lock) => lock,
+            _ => return Err(AuditLogError("Could not unlock".to_string())),
+        };
+        let mut found: Vec<AuditEntry> = entries
+            .iter()
+            .filter(|entry| entry.matches(&query))
+            .cloned()
+            .collect();
+        // Stable, so entries recorded within the same second stay newest first.
+        found.reverse();
+        found.sort_by_key(|entry| Reverse(entry.occurred_at));
+        found.truncate(query.limit);
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        application::ports::audit_log::{AuditEntry, AuditEventType, AuditLog, AuditQuery},
+        infrastructure::in_memory_audit_log::InMemoryAuditLog,
+    };
+
+    #[tokio::test]
+    async fn returns_the_newest_matching_entries_up_to_the_limit() {
+        let log = InMemoryAuditLog::new();
+        for (event_type, occurred_at) in [
+            (AuditEventType::LoginFailed, 10),
+            (AuditEventType::LoginSucceeded, 20),
+            (AuditEventType::LoginFailed, 30),
+            (AuditEventType::LoginFailed, 40),
+        ] {
+            let _ = log
+                .record(AuditEntry::new(
+                    event_type,
+                    "test@example.com".to_string(),
+                    None,
+                    occurred_at,
+                ))
+                .await;
+        }
+
+        let found = log
+            .query(AuditQuery {
+                user_id: None,
+                event_type: Some(AuditEventType::LoginFailed),
+                from: None,
+                until: None,
+                limit: 2,
+            })
+            .await
+            .unwrap();
+
+        let times: Vec<u64> = found.iter().map(|entry| entry.occurred_at).collect();
+        assert_eq!(times, vec![40, 30]);
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    entities::email_verification_token::EmailVerificationToken,
+    repositories::{
+        email_verification_token_repository::EmailVerificationTokenRepository,
+        repository_error::RepositoryError,
+    },
+    value_objects::id::Id,
+};
+use crate::infrastructure::in_memory_user_repository::UserOwnedRows;
+
+#[derive(Debug)]
+pub struct InMemoryEmailVerificationTokenRepository {
+    tokens: Mutex<Vec<EmailVerificationToken>>,
+}
+
+impl InMemoryEmailVerificationTokenRepository {
+    pub fn new() -> Self {
+        InMemoryEmailVerificationTokenRepository {
+            tokens: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Default for InMemoryEmailVerificationTokenRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EmailVerificationTokenRepository for InMemoryEmailVerificationTokenRepository {
+    async fn save(&self, token: EmailVerificationToken) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        if let Some(pos) = tokens.iter().position(|t| t.id() == token.id()) {
+            tokens[pos] = token;
+        } else {
+            tokens.push(token);
+        }
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<EmailVerificationToken>, RepositoryError> {
+        let tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        Ok(tokens
+            .iter()
+            .find(|t| t.token_hash() == token_hash)
+            .cloned())
+    }
+
+    async fn find_latest_for_user(
+        &self,
+        user_id: &Id,
+    ) -> Result<Option<EmailVerificationToken>, RepositoryError> {
+        let tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        Ok(tokens
+            .iter()
+            .filter(|t| t.user_id() == user_id)
+            .max_by_key(|t| t.issued_at())
+            .cloned())
+    }
+
+    async fn mark_as_used(&self, token: &EmailVerificationToken) -> Result<bool, RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let stored = tokens
+            .iter_mut()
+            .find(|t| t.id() == token.id())
+            .ok_or(RepositoryError::NotFound)?;
+
+        if stored.is_used() {
+            return Ok(false);
+        }
+
+        stored.mark_used();
+        Ok(true)
+    }
+
+    async fn invalidate_for_user(&self, user_id: &Id) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        tokens
+            .iter_mut()
+            .filter(|t| t.user_id() == user_id)
+            .for_each(EmailVerificationToken::mark_used);
+        Ok(())
+    }
+}
+
+impl UserOwnedRows for InMemoryEmailVerificationTokenRepository {
+    fn remove_for_users(&self, user_ids: &[Id]) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        tokens.retain(|row| !user_ids.contains(row.user_id()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::{
+        entities::email_verification_token::EmailVerificationToken,
+        repositories::email_verification_token_repository::EmailVerificationTokenRepository,
+        value_objects::id::Id,
+    };
+
+    use super::InMemoryEmailVerificationTokenRepository;
+
+    #[tokio::test]
+    async fn marks_a_token_as_used_only_once() {
+        let repo = InMemoryEmailVerificationTokenRepository::new();
+        let (token, _) = EmailVerificationToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(token.clone()).await;
+
+        assert_eq!(repo.mark_as_used(&token).await, Ok(true));
+        assert_eq!(repo.mark_as_used(&token).await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn finds_the_latest_token_of_a_user() {
+        let repo = InMemoryEmailVerificationTokenRepository::new();
+        let user_id = Id::generate_unique_identifier();
+        let (older, _) = EmailVerificationToken::issue(user_id.clone(), 60, 10);
+        let (latest, _) = EmailVerificationToken::issue(user_id.clone(), 60, 20);
+        let (other, _) = EmailVerificationToken::issue(Id::generate_unique_identifier(), 60, 30);
+        let _ = repo.save(latest.clone()).await;
+        let _ = repo.save(older).await;
+        let _ = repo.save(other).await;
+
+        assert_eq!(repo.find_latest_for_user(&user_id).await, Ok(Some(latest)));
+    }
+}
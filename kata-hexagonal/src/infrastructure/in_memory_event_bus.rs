@@ -0,0 +1,126 @@
+use std::sync::{Arc, Mutex, RwLock};
+
+use async_trait::async_trait;
+
+use crate::{
+    application::ports::event_publisher::{EventPublisher, PublishError},
+    domain::events::user_event::UserEvent,
+};
+
+type Subscriber = Box<dyn Fn(&UserEvent) + Send + Sync>;
+
+/// Hands every published event to the subscribers, in process, and keeps
+/// them so tests can inspect what was published.
+#[derive(Default)]
+pub struct InMemoryEventBus {
+    subscribers: RwLock<Vec<Subscriber>>,
+    handlers: RwLock<Vec<Arc<dyn EventPublisher>>>,
+    published: Mutex<Vec<UserEvent>>,
+}
+
+impl InMemoryEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&self, subscriber: impl Fn(&UserEvent) + Send + Sync + 'static) {
+        if let Ok(mut subscribers) = self.subscribers.write() {
+            subscribers.push(Box::new(subscriber));
+        }
+    }
+
+    /// Publishes every event to `handler` too, for reactions that have to be
+    /// awaited. A failing handler fails the publish, so an outbox retries.
+    pub fn forward_to(&self, handler: Arc<dyn EventPublisher>) {
+        if let Ok(mut handlers) = self.handlers.write() {
+            handlers.push(handler);
+        }
+    }
+
+    pub fn published(&self) -> Vec<UserEvent> {
+        self.published
+            .lock()
+            .map(|published| published.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for InMemoryEventBus {
+    async fn publish(&self, events: Vec<UserEvent>) -> Result<(), PublishError> {
+        {
+            let subscribers = self
+                .subscribers
+                .read()
+                .map_err(|e| PublishError(e.to_string()))?;
+            for event in &events {
+                log::debug!("{} for user {}", event.name(), event.user_id());
+                for subscriber in subscribers.iter() {
+                    subscriber(event);
+                }
+            }
+        }
+
+        let handlers = self
+            .handlers
+            .read()
+            .map_err(|e| PublishError(e.to_string()))?
+            .clone();
+        for handler in handlers {
+            handler.publish(events.clone()).await?;
+        }
+
+        self.published
+            .lock()
+            .map_err(|e| PublishError(e.to_string()))?
+            .extend(events);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        application::ports::event_publisher::EventPublisher, domain::events::user_event::UserEvent,
+        infrastructure::in_memory_event_bus::InMemoryEventBus,
+    };
+
+    #[tokio::test]
+    async fn delivers_events_to_every_subscriber() {
+        let bus = InMemoryEventBus::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        for _ in 0..2 {
+            let received = received.clone();
+            bus.subscribe(move |event| received.lock().unwrap().push(event.clone()));
+        }
+        let event = UserEvent::UserLoggedIn {
+            user_id: "id".to_string(),
+            occurred_at: 1,
+        };
+
+        let _ = bus.publish(vec![event.clone()]).await;
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![event.clone(), event.clone()]
+        );
+        assert_eq!(bus.published(), vec![event]);
+    }
+
+    #[tokio::test]
+    async fn forwards_events_to_handlers() {
+        let bus = InMemoryEventBus::new();
+        let handler = Arc::new(InMemoryEventBus::new());
+        bus.forward_to(handler.clone());
+        let event = UserEvent::UserLoggedIn {
+            user_id: "id".to_string(),
+            occurred_at: 1,
+        };
+
+        let _ = bus.publish(vec![event.clone()]).await;
+
+        assert_eq!(handler.published(), vec![event]);
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    entities::login_challenge::LoginChallenge,
+    repositories::{
+        login_challenge_repository::LoginChallengeRepository, repository_error::RepositoryError,
+    },
+    value_objects::id::Id,
+};
+use crate::infrastructure::in_memory_user_repository::UserOwnedRows;
+
+#[derive(Debug, Default)]
+pub struct InMemoryLoginChallengeRepository {
+    challenges: Mutex<Vec<LoginChallenge>>,
+}
+
+impl InMemoryLoginChallengeRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl LoginChallengeRepository for InMemoryLoginChallengeRepository {
+    async fn save(&self, challenge: LoginChallenge) -> Result<(), RepositoryError> {
+        let mut challenges = match self.challenges.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        if challenges.iter().any(|c| c.id() == challenge.id()) {
+            return Err(RepositoryError::ConstraintViolation(
+                "Login challenge already exists".to_string(),
+            ));
+        }
+        challenges.push(challenge);
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<LoginChallenge>, RepositoryError> {
+        let challenges = match self.challenges.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        Ok(challenges
+            .iter()
+            .find(|c| c.token_hash() == token_hash)
+            .cloned())
+    }
+
+    async fn claim_attempt(&self, id: &Id) -> Result<bool, RepositoryError> {
+        let mut challenges = match self.challenges.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        Ok(challenges
+            .iter_mut()
+            .find(|c| c.id() == id)
+            .is_some_and(LoginChallenge::claim_attempt))
+    }
+
+    async fn remove(&self, id: &Id) -> Result<bool, RepositoryError> {
+        let mut challenges = match self.challenges.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let before = challenges.len();
+        challenges.retain(|c| c.id() != id);
+        Ok(challenges.len() < before)
+    }
+}
+
+impl UserOwnedRows for InMemoryLoginChallengeRepository {
+    fn remove_for_users(&self, user_ids: &[Id]) -> Result<(), RepositoryError> {
+        let mut challenges = match self.challenges.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        challenges.retain(|row| !user_ids.contains(row.user_id()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::{
+            entities::login_challenge::{LoginChallenge, MAX_LOGIN_CHALLENGE_ATTEMPTS},
+            repositories::login_challenge_repository::LoginChallengeRepository,
+            value_objects::id::Id,
+        },
+        infrastructure::in_memory_login_challenge_repository::InMemoryLoginChallengeRepository,
+    };
+
+    #[tokio::test]
+    async fn removes_a_challenge_once() {
+        let repo = InMemoryLoginChallengeRepository::new();
+        let (challenge, plaintext) = LoginChallenge::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(challenge.clone()).await;
+
+        assert_eq!(
+            repo.find_by_hash(&LoginChallenge::hash(&plaintext)).await,
+            Ok(Some(challenge.clone()))
+        );
+        assert_eq!(repo.remove(challenge.id()).await, Ok(true));
+        assert_eq!(repo.remove(challenge.id()).await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn claims_attempts_only_on_stored_challenges() {
+        let repo = InMemoryLoginChallengeRepository::new();
+        let (challenge, _) = LoginChallenge::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(challenge.clone()).await;
+
+        for _ in 0..MAX_LOGIN_CHALLENGE_ATTEMPTS {
+            assert_eq!(repo.claim_attempt(challenge.id()).await, Ok(true));
+        }
+        assert_eq!(repo.claim_attempt(challenge.id()).await, Ok(false));
+
+        let _ = repo.remove(challenge.id()).await;
+        assert_eq!(repo.claim_attempt(challenge.id()).await, Ok(false));
+    }
+}
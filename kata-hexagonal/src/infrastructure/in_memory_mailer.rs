@@ -0,0 +1,43 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::application::ports::mailer::{EmailMessage, Mailer, MailerError};
+
+/// Keeps every sent message in memory so tests can inspect them.
+#[derive(Debug)]
+pub struct InMemoryMailer {
+    messages: Mutex<Vec<EmailMessage>>,
+}
+
+impl InMemoryMailer {
+    pub fn new() -> Self {
+        InMemoryMailer {
+            messages: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn sent(&self) -> Vec<EmailMessage> {
+        self.messages
+            .lock()
+            .map(|messages| messages.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for InMemoryMailer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Mailer for InMemoryMailer {
+    async fn send(&self, message: EmailMessage) -> Result<(), MailerError> {
+        self.messages
+            .lock()
+            .map_err(|e| MailerError(e.to_string()))?
+            .push(message);
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    entities::password_reset_token::PasswordResetToken,
+    repositories::{
+        password_reset_token_repository::PasswordResetTokenRepository,
+        repository_error::RepositoryError,
+    },
+    value_objects::id::Id,
+};
+use crate::infrastructure::in_memory_user_repository::UserOwnedRows;
+
+#[derive(Debug)]
+pub struct InMemoryPasswordResetTokenRepository {
+    tokens: Mutex<Vec<PasswordResetToken>>,
+}
+
+impl InMemoryPasswordResetTokenRepository {
+    pub fn new() -> Self {
+        InMemoryPasswordResetTokenRepository {
+            tokens: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Default for InMemoryPasswordResetTokenRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PasswordResetTokenRepository for InMemoryPasswordResetTokenRepository {
+    async fn save(&self, token: PasswordResetToken) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        if let Some(pos) = tokens.iter().position(|t| t.id() == token.id()) {
+            tokens[pos] = token;
+        } else {
+            tokens.push(token);
+        }
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PasswordResetToken>, RepositoryError> {
+        let tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        Ok(tokens
+            .iter()
+            .find(|t| t.token_hash() == token_hash)
+            .cloned())
+    }
+
+    async fn mark_as_used(&self, token: &PasswordResetToken) -> Result<bool, RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let stored = tokens
+            .iter_mut()
+            .find(|t| t.id() == token.id())
+            .ok_or(RepositoryError::NotFound)?;
+
+        if stored.is_used() {
+            return Ok(false);
+        }
+
+        stored.mark_used();
+        Ok(true)
+    }
+
+    async fn invalidate_for_user(&self, user_id: &Id) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        tokens
+            .iter_mut()
+            .filter(|t| t.user_id() == user_id)
+            .for_each(PasswordResetToken::mark_used);
+        Ok(())
+    }
+}
+
+impl UserOwnedRows for InMemoryPasswordResetTokenRepository {
+    fn remove_for_users(&self, user_ids: &[Id]) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        tokens.retain(|row| !user_ids.contains(row.user_id()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::{
+        entities::password_reset_token::PasswordResetToken,
+        repositories::password_reset_token_repository::PasswordResetTokenRepository,
+        value_objects::id::Id,
+    };
+
+    use super::InMemoryPasswordResetTokenRepository;
+
+    #[tokio::test]
+    async fn marks_a_token_as_used_only_once() {
+        let repo = InMemoryPasswordResetTokenRepository::new();
+        let (token, _) = PasswordResetToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(token.clone()).await;
+
+        assert_eq!(repo.mark_as_used(&token).await, Ok(true));
+        assert_eq!(repo.mark_as_used(&token).await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn invalidates_every_token_of_a_user() {
+        let repo = InMemoryPasswordResetTokenRepository::new();
+        let user_id = Id::generate_unique_identifier();
+        let (a_token, a_plaintext) = PasswordResetToken::issue(user_id.clone(), 60, 0);
+        let (another_token, another_plaintext) =
+            PasswordResetToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(a_token).await;
+        let _ = repo.save(another_token).await;
+
+        let _ = repo.invalidate_for_user(&user_id).await;
+
+        let a_token = repo
+            .find_by_hash(&PasswordResetToken::hash(&a_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        let another_token = repo
+            .find_by_hash(&PasswordResetToken::hash(&another_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(a_token.is_used());
+        assert!(!another_token.is_used());
+    }
+}
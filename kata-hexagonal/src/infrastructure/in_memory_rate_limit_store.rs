@@ -0,0 +1,154 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::actix::rate_limit::{RateLimit, RateLimitStore};
+
+/// Buckets tracked before full ones are dropped, and then the least
+/// recently used, so a flood of distinct keys cannot grow the map.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    full_at: Instant,
+}
+
+/// Process-local token buckets. Limits are per instance: several server
+/// processes each allow the full rate.
+#[derive(Debug)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    capacity: usize,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_TRACKED_KEYS)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        InMemoryRateLimitStore {
+            buckets: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+}
+
+impl Default for InMemoryRateLimitStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn acquire(&self, key: &str, limit: &RateLimit, now: Instant) -> Result<(), Duration> {
+        if limit.burst == 0 {
+            return Err(limit.period);
+        }
+
+        // A poisoned lock only means another request panicked mid-update;
+        // the counters are still usable.
+        let mut buckets = match self.buckets.lock() {
+            Ok(lock) => lock,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if !buckets.contains_key(key) && buckets.len() >= self.capacity {
+            buckets.retain(|_, bucket| bucket.full_at > now);
+            if buckets.len() >= self.capacity {
+                let least_recently_used = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.updated_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(key) = least_recently_used {
+                    buckets.remove(&key);
+                }
+            }
+        }
+
+        let capacity = limit.burst as f64;
+        let rate = limit.refill_per_second();
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+            full_at: now,
+        });
+
+        let elapsed = now
+            .saturating_duration_since(bucket.updated_at)
+            .as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated_at = now;
+
+        let result = if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        };
+        bucket.full_at = now + Duration::from_secs_f64((capacity - bucket.tokens) / rate);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::infrastructure::actix::rate_limit::{RateLimit, RateLimitStore};
+
+    use super::InMemoryRateLimitStore;
+
+    #[test]
+    fn allows_a_burst_then_refills_over_time() {
+        let store = InMemoryRateLimitStore::new();
+        let limit = RateLimit::new(2, Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert_eq!(store.acquire("key", &limit, start), Ok(()));
+        assert_eq!(store.acquire("key", &limit, start), Ok(()));
+        assert_eq!(
+            store.acquire("key", &limit, start),
+            Err(Duration::from_secs(5))
+        );
+        assert_eq!(
+            store.acquire("key", &limit, start + Duration::from_secs(5)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn keeps_keys_independent() {
+        let store = InMemoryRateLimitStore::new();
+        let limit = RateLimit::new(1, Duration::from_secs(10));
+        let now = Instant::now();
+
+        assert_eq!(store.acquire("a", &limit, now), Ok(()));
+        assert_eq!(store.acquire("b", &limit, now), Ok(()));
+        assert!(store.acquire("a", &limit, now).is_err());
+    }
+
+    #[test]
+    fn stays_bounded_when_flooded_with_distinct_keys() {
+        let store = InMemoryRateLimitStore::with_capacity(100);
+        let limit = RateLimit::new(1, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert_eq!(store.acquire("victim", &limit, start), Ok(()));
+        for i in 0..10_000 {
+            let now = start + Duration::from_millis(i + 1);
+            let _ = store.acquire(&format!("flood-{}", i), &limit, now);
+            if i % 10 == 0 {
+                // Kept in use, so never the least recently used.
+                assert!(store.acquire("victim", &limit, now).is_err());
+            }
+        }
+
+        assert_eq!(store.buckets.lock().unwrap().len(), 100);
+    }
+}
@@ -0,0 +1,206 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    entities::refresh_token::RefreshToken,
+    repositories::{
+        refresh_token_repository::RefreshTokenRepository, repository_error::RepositoryError,
+    },
+    value_objects::id::Id,
+};
+use crate::infrastructure::in_memory_user_repository::UserOwnedRows;
+
+#[derive(Debug)]
+pub struct InMemoryRefreshTokenRepository {
+    tokens: Mutex<Vec<RefreshToken>>,
+}
+
+impl InMemoryRefreshTokenRepository {
+    pub fn new() -> Self {
+        InMemoryRefreshTokenRepository {
+            tokens: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Default for InMemoryRefreshTokenRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for InMemoryRefreshTokenRepository {
+    async fn save(&self, token: RefreshToken) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        if let Some(pos) = tokens.iter().position(|t| t.id() == token.id()) {
+            tokens[pos] = token;
+        } else {
+            tokens.push(token);
+        }
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<RefreshToken>, RepositoryError> {
+        let tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        Ok(tokens
+            .iter()
+            .find(|t| t.token_hash() == token_hash)
+            .cloned())
+    }
+
+    async fn mark_as_used(&self, token: &RefreshToken) -> Result<bool, RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let stored = tokens
+            .iter_mut()
+            .find(|t| t.id() == token.id())
+            .ok_or(RepositoryError::NotFound)?;
+
+        if stored.is_used() {
+            return Ok(false);
+        }
+
+        stored.mark_used();
+        Ok(true)
+    }
+
+    async fn revoke_family(&self, family_id: &Id) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        tokens
+            .iter_mut()
+            .filter(|t| t.family_id() == family_id)
+            .for_each(RefreshToken::revoke);
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: &Id) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        tokens
+            .iter_mut()
+            .filter(|t| t.user_id() == user_id)
+            .for_each(RefreshToken::revoke);
+        Ok(())
+    }
+}
+
+impl UserOwnedRows for InMemoryRefreshTokenRepository {
+    fn remove_for_users(&self, user_ids: &[Id]) -> Result<(), RepositoryError> {
+        let mut tokens = match self.tokens.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        tokens.retain(|row| !user_ids.contains(row.user_id()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::{
+        entities::refresh_token::RefreshToken,
+        repositories::refresh_token_repository::RefreshTokenRepository, value_objects::id::Id,
+    };
+
+    use super::InMemoryRefreshTokenRepository;
+
+    #[tokio::test]
+    async fn finds_a_token_by_hash() {
+        let repo = InMemoryRefreshTokenRepository::new();
+        let (token, plaintext) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(token.clone()).await;
+
+        let found = repo.find_by_hash(&RefreshToken::hash(&plaintext)).await;
+
+        assert_eq!(found, Ok(Some(token)));
+    }
+
+    #[tokio::test]
+    async fn marks_a_token_as_used_only_once() {
+        let repo = InMemoryRefreshTokenRepository::new();
+        let (token, _) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(token.clone()).await;
+
+        assert_eq!(repo.mark_as_used(&token).await, Ok(true));
+        assert_eq!(repo.mark_as_used(&token).await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn revokes_every_token_in_a_family() {
+        let repo = InMemoryRefreshTokenRepository::new();
+        let (token, _) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let (rotated, rotated_plaintext) = token.rotate(60, 0);
+        let (unrelated, unrelated_plaintext) =
+            RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(token.clone()).await;
+        let _ = repo.save(rotated).await;
+        let _ = repo.save(unrelated).await;
+
+        let _ = repo.revoke_family(token.family_id()).await;
+
+        let rotated = repo
+            .find_by_hash(&RefreshToken::hash(&rotated_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        let unrelated = repo
+            .find_by_hash(&RefreshToken::hash(&unrelated_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(rotated.is_revoked());
+        assert!(!unrelated.is_revoked());
+    }
+
+    #[tokio::test]
+    async fn revokes_every_token_of_a_user() {
+        let repo = InMemoryRefreshTokenRepository::new();
+        let user_id = Id::generate_unique_identifier();
+        let (token, plaintext) = RefreshToken::issue(user_id.clone(), 60, 0);
+        let (other_session, other_plaintext) = RefreshToken::issue(user_id.clone(), 60, 0);
+        let (unrelated, unrelated_plaintext) =
+            RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = repo.save(token).await;
+        let _ = repo.save(other_session).await;
+        let _ = repo.save(unrelated).await;
+
+        let _ = repo.revoke_all_for_user(&user_id).await;
+
+        for (plaintext, revoked) in [
+            (plaintext, true),
+            (other_plaintext, true),
+            (unrelated_plaintext, false),
+        ] {
+            let stored = repo
+                .find_by_hash(&RefreshToken::hash(&plaintext))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.is_revoked(), revoked);
+        }
+    }
+}
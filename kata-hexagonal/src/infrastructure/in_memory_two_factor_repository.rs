@@ -0,0 +1,141 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    entities::two_factor::TwoFactor,
+    repositories::{repository_error::RepositoryError, two_factor_repository::TwoFactorRepository},
+    value_objects::id::Id,
+};
+use crate::infrastructure::in_memory_user_repository::UserOwnedRows;
+
+#[derive(Debug, Default)]
+pub struct InMemoryTwoFactorRepository {
+    enrollments: Mutex<Vec<TwoFactor>>,
+}
+
+impl InMemoryTwoFactorRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TwoFactorRepository for InMemoryTwoFactorRepository {
+    async fn save(&self, two_factor: TwoFactor) -> Result<(), RepositoryError> {
+        let mut enrollments = match self.enrollments.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        if let Some(pos) = enrollments
+            .iter()
+            .position(|t| t.user_id() == two_factor.user_id())
+        {
+            enrollments[pos] = two_factor;
+        } else {
+            enrollments.push(two_factor);
+        }
+        Ok(())
+    }
+
+    async fn find_by_user_id(&self, user_id: &Id) -> Result<Option<TwoFactor>, RepositoryError> {
+        let enrollments = match self.enrollments.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        Ok(enrollments.iter().find(|t| t.user_id() == user_id).cloned())
+    }
+
+    async fn claim_step(&self, user_id: &Id, step: u64) -> Result<bool, RepositoryError> {
+        let mut enrollments = match self.enrollments.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let stored = enrollments
+            .iter_mut()
+            .find(|t| t.user_id() == user_id)
+            .ok_or(RepositoryError::NotFound)?;
+        Ok(stored.claim_step(step))
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        user_id: &Id,
+        code_hash: &str,
+    ) -> Result<bool, RepositoryError> {
+        let mut enrollments = match self.enrollments.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let stored = enrollments
+            .iter_mut()
+            .find(|t| t.user_id() == user_id)
+            .ok_or(RepositoryError::NotFound)?;
+        Ok(stored.remove_recovery_code(code_hash))
+    }
+}
+
+impl UserOwnedRows for InMemoryTwoFactorRepository {
+    fn remove_for_users(&self, user_ids: &[Id]) -> Result<(), RepositoryError> {
+        let mut enrollments = match self.enrollments.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        enrollments.retain(|row| !user_ids.contains(row.user_id()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::{
+            entities::two_factor::TwoFactor,
+            repositories::two_factor_repository::TwoFactorRepository, value_objects::id::Id,
+        },
+        infrastructure::in_memory_two_factor_repository::InMemoryTwoFactorRepository,
+    };
+
+    #[tokio::test]
+    async fn claims_each_step_once() {
+        let repo = InMemoryTwoFactorRepository::new();
+        let two_factor = TwoFactor::enroll(Id::generate_unique_identifier());
+        let user_id = two_factor.user_id().clone();
+        let _ = repo.save(two_factor).await;
+
+        assert_eq!(repo.claim_step(&user_id, 10).await, Ok(true));
+        assert_eq!(repo.claim_step(&user_id, 10).await, Ok(false));
+        assert_eq!(repo.claim_step(&user_id, 9).await, Ok(false));
+        assert_eq!(repo.claim_step(&user_id, 11).await, Ok(true));
+    }
+
+    #[tokio::test]
+    async fn consumes_each_recovery_code_once() {
+        let repo = InMemoryTwoFactorRepository::new();
+        let user_id = Id::generate_unique_identifier();
+        let code_hash = TwoFactor::hash("abcde-fghij");
+        let _ = repo
+            .save(TwoFactor::new(
+                user_id.clone(),
+                vec![1; 20],
+                true,
+                None,
+                vec![code_hash.clone()],
+            ))
+            .await;
+
+        assert_eq!(
+            repo.consume_recovery_code(&user_id, &code_hash).await,
+            Ok(true)
+        );
+        assert_eq!(
+            repo.consume_recovery_code(&user_id, &code_hash).await,
+            Ok(false)
+        );
+    }
+}
@@ -1,79 +1,344 @@
-use std::sync::Mutex;
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
 
 use async_trait::async_trait;
 
 use crate::domain::{
     entities::user::User,
-    repositories::user_repository::UserRepository,
-    value_objects::{email::Email, id::Id},
+    repositories::{
+        repository_error::RepositoryError,
+        user_query::{UserCursor, UserPage, UserQuery},
+        user_repository::UserRepository,
+    },
+    value_objects::{
+        email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password, role::Role,
+    },
 };
 
+/// In-memory rows that belong to a user, such as their tokens. They are
+/// dropped along with the user, as the SQLite adapter deletes them.
+pub trait UserOwnedRows: Send + Sync + Debug {
+    fn remove_for_users(&self, user_ids: &[Id]) -> Result<(), RepositoryError>;
+}
+
 #[derive(Debug)]
 pub struct InMemoryUserRepository {
     users: Mutex<Vec<User>>,
+    owned_rows: Vec<Arc<dyn UserOwnedRows>>,
 }
 
 impl InMemoryUserRepository {
     pub fn new() -> Self {
         InMemoryUserRepository {
             users: Mutex::new(Vec::new()),
+            owned_rows: Vec::new(),
         }
     }
+
+    /// Removes the user's rows in `rows` whenever a user is removed or purged.
+    pub fn owning(mut self, rows: Arc<dyn UserOwnedRows>) -> Self {
+        self.owned_rows.push(rows);
+        self
+    }
+
+    fn remove_owned_rows(&self, user_ids: &[Id]) -> Result<(), RepositoryError> {
+        self.owned_rows
+            .iter()
+            .try_for_each(|rows| rows.remove_for_users(user_ids))
+    }
+}
+
+impl Default for InMemoryUserRepository {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[async_trait]
 impl UserRepository for InMemoryUserRepository {
-    async fn save(&self, user: User) -> Result<(), String> {
-        if let Ok(mut lock) = self.users.lock() {
-            if let Some(pos) = lock.iter().position(|u| *u == user) {
-                lock[pos] = user;
-            } else {
-                lock.push(user);
-            }
+    async fn save(&self, mut user: User) -> Result<(), RepositoryError> {
+        // Without an outbox, the use cases publish the events themselves.
+        user.pull_events();
+        let mut users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        if users
+            .iter()
+            .any(|u| *u != user && u.email() == user.email())
+        {
+            return Err(RepositoryError::ConstraintViolation(
+                "Email already in use".to_string(),
+            ));
+        }
+
+        if let Some(pos) = users.iter().position(|u| *u == user) {
+            users[pos] = user;
+        } else {
+            users.push(user);
         }
         Ok(())
     }
 
-    async fn find_by_email(&self, email: Email) -> Result<Option<User>, String> {
+    async fn find_by_id(&self, id: Id) -> Result<Option<User>, RepositoryError> {
         let users = match self.users.lock() {
             Ok(lock) => lock,
-            _ => return Err("Could not unlock".to_string()),
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
         };
 
-        let user = users.iter().find(|u| u.is_matching_email(&email));
+        let user = users
+            .iter()
+            .find(|u| u.is_matching_id(&id) && !u.is_deleted());
 
         Ok(user.cloned())
     }
 
-    async fn find_all(&self) -> Result<Vec<User>, String> {
+    async fn find_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError> {
         let users = match self.users.lock() {
             Ok(lock) => lock,
-            _ => return Err("Could not unlock".to_string()),
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
         };
-        Ok(users.clone())
+
+        let user = users
+            .iter()
+            .find(|u| u.is_matching_email(&email) && !u.is_deleted());
+
+        Ok(user.cloned())
+    }
+
+    async fn find_deleted_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError> {
+        let users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let user = users
+            .iter()
+            .find(|u| u.is_matching_email(&email) && u.is_deleted());
+
+        Ok(user.cloned())
     }
 
-    async fn remove(&self, user: User) -> Result<(), String> {
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+        let users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+        Ok(users.iter().filter(|u| !u.is_deleted()).cloned().collect())
+    }
+
+    async fn find_page(&self, query: UserQuery) -> Result<UserPage, RepositoryError> {
+        let users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let mut matching: Vec<&User> = users
+            .iter()
+            .filter(|u| query.matches(u))
+            .filter(|u| {
+                query.after.as_ref().is_none_or(|after| {
+                    query.order(&UserCursor::after(u), after) == Ordering::Greater
+                })
+            })
+            .collect();
+        matching.sort_by(|a, b| query.order(&UserCursor::after(a), &UserCursor::after(b)));
+
+        let page = matching
+            .into_iter()
+            .take(query.limit + 1)
+            .cloned()
+            .collect();
+        Ok(UserPage::from_overfetched(page, query.limit))
+    }
+
+    async fn remove(&self, user: User) -> Result<(), RepositoryError> {
+        {
+            let mut users = match self.users.lock() {
+                Ok(lock) => lock,
+                _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+            };
+            let count = users.len();
+            users.retain(|u| *u != user);
+
+            if users.len() == count {
+                return Err(RepositoryError::NotFound);
+            }
+        }
+
+        let id = Id::from(user.id()).map_err(|e| RepositoryError::CorruptData(e.to_string()))?;
+        self.remove_owned_rows(&[id])
+    }
+
+    async fn register_failed_login(
+        &self,
+        id: Id,
+        policy: LockoutPolicy,
+        now: u64,
+    ) -> Result<(), RepositoryError> {
         let mut users = match self.users.lock() {
             Ok(lock) => lock,
-            _ => return Err("Could not unlock".to_string()),
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
         };
-        users.retain(|u| *u != user);
+
+        if let Some(user) = users
+            .iter_mut()
+            .find(|u| u.is_matching_id(&id) && !u.is_deleted())
+        {
+            user.register_failed_login(&policy, now);
+        }
         Ok(())
     }
 
-    // add code here
+    async fn register_successful_login(&self, mut user: User) -> Result<bool, RepositoryError> {
+        // Without an outbox, the use cases publish the events themselves.
+        user.pull_events();
+        let mut users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        match users
+            .iter_mut()
+            .find(|u| **u == user && !u.is_deleted() && u.password() == user.password())
+        {
+            Some(stored) => {
+                stored.register_successful_login();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn upgrade_password_hash(
+        &self,
+        id: Id,
+        previous_hash: String,
+        upgraded_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        let mut users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        match users
+            .iter_mut()
+            .find(|u| u.is_matching_id(&id) && !u.is_deleted() && u.password() == previous_hash)
+        {
+            Some(stored) => {
+                *stored = stored
+                    .clone()
+                    .with_password(Password::from_hash(upgraded_hash));
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn replace_password(
+        &self,
+        mut user: User,
+        previous_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        user.pull_events();
+        let mut users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        match users
+            .iter_mut()
+            .find(|u| **u == user && !u.is_deleted() && u.password() == previous_hash)
+        {
+            Some(stored) => {
+                stored.reset_password(Password::from_hash(user.password()));
+                stored.pull_events();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn mark_deleted(&self, mut user: User) -> Result<bool, RepositoryError> {
+        user.pull_events();
+        let mut users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        match users.iter_mut().find(|u| **u == user && !u.is_deleted()) {
+            Some(stored) => {
+                *stored = stored.clone().with_deleted_at(user.deleted_at());
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn assign_role(&self, id: Id, role: Role) -> Result<bool, RepositoryError> {
+        let mut users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+
+        let other_admin = users
+            .iter()
+            .any(|u| u.role() == Role::Admin && !u.is_deleted() && !u.is_matching_id(&id));
+        let Some(user) = users
+            .iter_mut()
+            .find(|u| u.is_matching_id(&id) && !u.is_deleted())
+        else {
+            return Err(RepositoryError::NotFound);
+        };
+
+        if user.role() == Role::Admin && role != Role::Admin && !other_admin {
+            return Ok(false);
+        }
+        user.assign_role(role);
+        Ok(true)
+    }
+
+    async fn purge_deleted_before(&self, cutoff: u64) -> Result<u64, RepositoryError> {
+        let mut users = match self.users.lock() {
+            Ok(lock) => lock,
+            _ => return Err(RepositoryError::Unavailable("Could not unlock".to_string())),
+        };
+        let (purged, kept) = users.drain(..).partition::<Vec<_>, _>(|u| {
+            u.deleted_at().is_some_and(|deleted_at| deleted_at < cutoff)
+        });
+        *users = kept;
+        drop(users);
+
+        let purged_ids = purged
+            .iter()
+            .map(|u| Id::from(u.id()).map_err(|e| RepositoryError::CorruptData(e.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.remove_owned_rows(&purged_ids)?;
+        Ok(purged.len() as u64)
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use crate::domain::{
-        entities::user::User,
-        repositories::user_repository::UserRepository,
-        value_objects::{email::Email, id::Id, password::Password},
+        entities::{refresh_token::RefreshToken, user::User},
+        repositories::{
+            refresh_token_repository::RefreshTokenRepository,
+            repository_error::RepositoryError,
+            user_query::{SortDirection, UserQuery, UserSort, UserStatus},
+            user_repository::UserRepository,
+        },
+        value_objects::{email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password},
     };
 
     use super::InMemoryUserRepository;
+    use crate::infrastructure::in_memory_refresh_token_repository::InMemoryRefreshTokenRepository;
 
     #[tokio::test]
     async fn find_user_by_id() {
@@ -175,6 +440,207 @@ mod test {
         assert_eq!(users, Ok(vec![a_user.clone()]));
     }
 
+    #[tokio::test]
+    async fn does_not_remove_non_existing_user() {
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let repo = InMemoryUserRepository::new();
+
+        assert_eq!(repo.remove(user).await, Err(RepositoryError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn does_not_allow_two_users_with_same_email() {
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let repo = InMemoryUserRepository::new();
+
+        let _ = repo.save(create_user_by_email(email.clone())).await;
+        let res = repo.save(create_user_by_email(email.clone())).await;
+
+        assert!(matches!(res, Err(RepositoryError::ConstraintViolation(_))));
+    }
+
+    #[tokio::test]
+    async fn registers_failed_logins_in_place() {
+        let repo = InMemoryUserRepository::new();
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let _ = repo.save(user.clone()).await;
+        let policy = LockoutPolicy::default();
+
+        for _ in 0..policy.max_attempts {
+            let _ = repo
+                .register_failed_login(Id::from(user.id()).unwrap(), policy, 100)
+                .await;
+        }
+        let stored = repo.find_all().await.unwrap().remove(0);
+
+        assert_eq!(stored.failed_login_attempts(), policy.max_attempts);
+        assert!(stored.is_locked(100));
+    }
+
+    #[tokio::test]
+    async fn hides_deleted_users_from_the_finders() {
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let mut user = create_user_by_email(email.clone());
+        let repo = InMemoryUserRepository::new();
+        user.delete(100);
+
+        let _ = repo.save(user.clone()).await;
+
+        assert_eq!(repo.find_by_email(email.clone()).await, Ok(None));
+        assert_eq!(
+            repo.find_by_id(Id::from(user.id()).unwrap()).await,
+            Ok(None)
+        );
+        assert_eq!(repo.find_all().await, Ok(vec![]));
+        assert_eq!(repo.find_deleted_by_email(email).await, Ok(Some(user)));
+    }
+
+    #[tokio::test]
+    async fn purges_only_users_deleted_before_the_cutoff() {
+        let mut old = create_user_by_email(Email::new("old@example.com".to_string()).unwrap());
+        let mut recent =
+            create_user_by_email(Email::new("recent@example.com".to_string()).unwrap());
+        let repo = InMemoryUserRepository::new();
+        old.delete(100);
+        recent.delete(200);
+        let _ = repo.save(old).await;
+        let _ = repo.save(recent.clone()).await;
+
+        assert_eq!(repo.purge_deleted_before(150).await, Ok(1));
+        assert_eq!(
+            repo.find_deleted_by_email(Email::new("recent@example.com".to_string()).unwrap())
+                .await,
+            Ok(Some(recent))
+        );
+    }
+
+    #[tokio::test]
+    async fn drops_the_rows_owned_by_removed_and_purged_users() {
+        let tokens = Arc::new(InMemoryRefreshTokenRepository::new());
+        let repo = InMemoryUserRepository::new().owning(tokens.clone());
+        let mut old = create_user_by_email(Email::new("old@example.com".to_string()).unwrap());
+        let removed = create_user_by_email(Email::new("removed@example.com".to_string()).unwrap());
+        let active = create_user_by_email(Email::new("active@example.com".to_string()).unwrap());
+        old.delete(100);
+        let mut plaintexts = Vec::new();
+        for user in [&old, &removed, &active] {
+            let _ = repo.save(user.clone()).await;
+            let (token, plaintext) = RefreshToken::issue(Id::from(user.id()).unwrap(), 60, 0);
+            let _ = tokens.save(token).await;
+            plaintexts.push(plaintext);
+        }
+
+        let _ = repo.purge_deleted_before(150).await;
+        let _ = repo.remove(removed).await;
+
+        let mut remaining = Vec::new();
+        for plaintext in plaintexts {
+            remaining.push(
+                tokens
+                    .find_by_hash(&RefreshToken::hash(&plaintext))
+                    .await
+                    .unwrap()
+                    .is_some(),
+            );
+        }
+        assert_eq!(remaining, vec![false, false, true]);
+    }
+
+    #[tokio::test]
+    async fn pages_through_users_in_sort_order() {
+        let repo = InMemoryUserRepository::new();
+        for (email, created_at) in [
+            ("a@example.com", 3),
+            ("b@example.com", 1),
+            ("c@example.com", 2),
+        ] {
+            let user = create_user_by_email(Email::new(email.to_string()).unwrap())
+                .with_created_at(created_at);
+            let _ = repo.save(user).await;
+        }
+        let mut deleted = create_user_by_email(Email::new("d@example.com".to_string()).unwrap());
+        deleted.delete(100);
+        let _ = repo.save(deleted).await;
+        let by_email = UserQuery {
+            sort: UserSort::Email,
+            limit: 2,
+            ..UserQuery::default()
+        };
+
+        let first = repo.find_page(by_email.clone()).await.unwrap();
+        let second = repo
+            .find_page(UserQuery {
+                after: first.next.clone(),
+                ..by_email
+            })
+            .await
+            .unwrap();
+        let newest = repo
+            .find_page(UserQuery {
+                direction: SortDirection::Descending,
+                ..UserQuery::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(emails(&first.users), vec!["a@example.com", "b@example.com"]);
+        assert_eq!(emails(&second.users), vec!["c@example.com"]);
+        assert_eq!(second.next, None);
+        assert_eq!(
+            emails(&newest.users),
+            vec!["a@example.com", "c@example.com", "b@example.com"]
+        );
+    }
+
+    #[tokio::test]
+    async fn filters_users_by_email_and_status() {
+        let repo = InMemoryUserRepository::new();
+        let mut jane =
+            create_user_by_email(Email::new("jane_doe@example.com".to_string()).unwrap());
+        jane.verify_email();
+        let janex = create_user_by_email(Email::new("janexdoe@example.com".to_string()).unwrap());
+        let john = create_user_by_email(Email::new("john@other.org".to_string()).unwrap())
+            .with_login_attempts(5, Some(200));
+        for user in [jane, janex, john] {
+            let _ = repo.save(user).await;
+        }
+        let find = |query: UserQuery| {
+            let repo = &repo;
+            async move { emails(&repo.find_page(query).await.unwrap().users) }
+        };
+
+        assert_eq!(
+            find(UserQuery {
+                email_contains: Some("E_D".to_string()),
+                ..UserQuery::default()
+            })
+            .await,
+            vec!["jane_doe@example.com"]
+        );
+        assert_eq!(
+            find(UserQuery {
+                email_domain: Some("example.com".to_string()),
+                status: Some(UserStatus::Unverified),
+                ..UserQuery::default()
+            })
+            .await,
+            vec!["janexdoe@example.com"]
+        );
+        assert_eq!(
+            find(UserQuery {
+                status: Some(UserStatus::Locked),
+                now: 100,
+                ..UserQuery::default()
+            })
+            .await,
+            vec!["john@other.org"]
+        );
+    }
+
+    fn emails(users: &[User]) -> Vec<String> {
+        users.iter().map(|u| u.email()).collect()
+    }
+
     fn create_user_by_id(id: Id) -> User {
         let email = Email::new("test@example.com".to_string()).unwrap();
         let password = Password::new("SafePass123_".to_string()).unwrap();
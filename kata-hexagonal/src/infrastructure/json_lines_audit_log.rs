@@ -0,0 +1,145 @@
+use std::{
+    cmp::Reverse,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+
+use crate::application::ports::audit_log::{AuditEntry, AuditLog, AuditLogError, AuditQuery};
+
+/// Appends one JSON object per line to a file that is never rewritten, so
+/// it can be shipped to log storage as is. Every entry is synced to disk
+/// before `record` returns. Queries scan the whole file.
+#[derive(Debug)]
+pub struct JsonLinesAuditLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonLinesAuditLog {
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        Ok(JsonLinesAuditLog {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl AuditLog for JsonLinesAuditLog {
+    async fn record(&self, entry: AuditEntry) -> Result<(), AuditLogError> {
+        let mut line = serde_json::to_string(&entry).map_err(|e| AuditLogError(e.to_string()))?;
+        line.push('\n');
+
+        let mut file = match self.file.lock() {
+            Ok(lock) => lock,
+            _ => return Err(AuditLogError("Could not unlock".to_string())),
+        };
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| AuditLogError(e.to_string()))
+    }
+
+    async fn query(&self, query: AuditQuery) -> Result<Vec<AuditEntry>, AuditLogError> {
+        let file = File::open(&self.path).map_err(|e| AuditLogError(e.to_string()))?;
+        let mut found = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| AuditLogError(e.to_string()))?;
+            match serde_json::from_str::<AuditEntry>(&line) {
+                Ok(entry) if entry.matches(&query) => found.push(entry),
+                Ok(_) => {}
+                // A line torn by a crash mid-write must not hide the rest.
+                Err(error) => log::warn!("skipping unreadable audit log line: {}", error),
+            }
+        }
+
+        found.reverse();
+        found.sort_by_key(|entry| Reverse(entry.occurred_at));
+        found.truncate(query.limit);
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::application::ports::audit_log::{AuditEntry, AuditEventType, AuditLog, AuditQuery};
+
+    use super::JsonLinesAuditLog;
+
+    fn entry(event_type: AuditEventType, occurred_at: u64) -> AuditEntry {
+        AuditEntry::new(
+            event_type,
+            "test@example.com".to_string(),
+            Some("127.0.0.1".to_string()),
+            occurred_at,
+        )
+        .for_user("id".to_string())
+    }
+
+    fn everything() -> AuditQuery {
+        AuditQuery {
+            user_id: None,
+            event_type: None,
+            from: None,
+            until: None,
+            limit: 100,
+        }
+    }
+
+    #[tokio::test]
+    async fn appends_one_json_line_per_entry() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("audit.log");
+        let log = JsonLinesAuditLog::open(&path).unwrap();
+
+        let _ = log.record(entry(AuditEventType::Registered, 10)).await;
+        let _ = log.record(entry(AuditEventType::LoginSucceeded, 20)).await;
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""event_type":"registered""#));
+        assert!(lines[1].contains(r#""ip":"127.0.0.1""#));
+    }
+
+    #[tokio::test]
+    async fn keeps_existing_entries_when_reopened() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("audit.log");
+        let first = entry(AuditEventType::Registered, 10);
+        let _ = JsonLinesAuditLog::open(&path)
+            .unwrap()
+            .record(first.clone())
+            .await;
+
+        let log = JsonLinesAuditLog::open(&path).unwrap();
+        let second = entry(AuditEventType::LoginFailed, 20);
+        let _ = log.record(second.clone()).await;
+
+        assert_eq!(log.query(everything()).await, Ok(vec![second, first]));
+    }
+
+    #[tokio::test]
+    async fn skips_unreadable_lines() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("audit.log");
+        let log = JsonLinesAuditLog::open(&path).unwrap();
+        let recorded = entry(AuditEventType::PasswordChanged, 10);
+        let _ = log.record(recorded.clone()).await;
+        fs::write(
+            &path,
+            fs::read_to_string(&path).unwrap() + r#"{"event_type":"log"#,
+        )
+        .unwrap();
+
+        assert_eq!(log.query(everything()).await, Ok(vec![recorded]));
+    }
+}
@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use jsonwebtoken::{
+    decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::ports::token_issuer::{AccessToken, TokenError, TokenIssuer},
+    domain::{common::time, entities::user::User, value_objects::id::Id},
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    iat: u64,
+    exp: u64,
+}
+
+/// Issues and verifies HS256-signed JWT access tokens.
+pub struct JwtTokenIssuer {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    time_to_live: Duration,
+}
+
+impl JwtTokenIssuer {
+    pub fn new(secret: &[u8], time_to_live: Duration) -> Self {
+        JwtTokenIssuer {
+            encoding_key: EncodingKey::from_secret(secret),
+            decoding_key: DecodingKey::from_secret(secret),
+            time_to_live,
+        }
+    }
+}
+
+impl TokenIssuer for JwtTokenIssuer {
+    fn issue(&self, user: &User) -> Result<AccessToken, TokenError> {
+        let now = time::now();
+        let claims = Claims {
+            sub: user.id(),
+            iat: now,
+            exp: now + self.time_to_live.as_secs(),
+        };
+
+        let token = encode(&Header::new(Algorithm::HS256), &claims, &self.encoding_key)
+            .map_err(|e| TokenError::Issuing(e.to_string()))?;
+
+        Ok(AccessToken {
+            token,
+            expires_in: self.time_to_live.as_secs(),
+        })
+    }
+
+    fn verify(&self, token: &str) -> Result<Id, TokenError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.leeway = 0;
+
+        let data = decode::<Claims>(token, &self.decoding_key, &validation).map_err(|e| match e
+            .kind()
+        {
+            ErrorKind::ExpiredSignature => TokenError::Expired,
+            _ => TokenError::Invalid,
+        })?;
+
+        Id::from(data.claims.sub).map_err(|_| TokenError::Invalid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    use crate::{
+        application::ports::token_issuer::{TokenError, TokenIssuer},
+        domain::{
+            entities::user::User,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+    };
+
+    use super::{Claims, JwtTokenIssuer};
+
+    #[test]
+    fn verifies_an_issued_token() {
+        let issuer = JwtTokenIssuer::new(b"secret", Duration::from_secs(60));
+        let user = create_user();
+
+        let token = issuer.issue(&user).unwrap();
+
+        assert_eq!(token.expires_in, 60);
+        assert_eq!(
+            issuer.verify(&token.token),
+            Ok(Id::from(user.id()).unwrap())
+        );
+    }
+
+    #[test]
+    fn rejects_a_token_signed_with_another_key() {
+        let issuer = JwtTokenIssuer::new(b"secret", Duration::from_secs(60));
+        let another_issuer = JwtTokenIssuer::new(b"another secret", Duration::from_secs(60));
+
+        let token = another_issuer.issue(&create_user()).unwrap();
+
+        assert_eq!(issuer.verify(&token.token), Err(TokenError::Invalid));
+    }
+
+    #[test]
+    fn rejects_an_expired_token() {
+        let issuer = JwtTokenIssuer::new(b"secret", Duration::from_secs(60));
+        let claims = Claims {
+            sub: Id::generate_unique_identifier().to_string(),
+            iat: 1,
+            exp: 2,
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+
+        assert_eq!(issuer.verify(&token), Err(TokenError::Expired));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        let issuer = JwtTokenIssuer::new(b"secret", Duration::from_secs(60));
+
+        assert_eq!(issuer.verify("not.a.token"), Err(TokenError::Invalid));
+    }
+
+    fn create_user() -> User {
+        User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::from_hash("hash".to_string()),
+        )
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    application::ports::mailer::{EmailMessage, Mailer, MailerError},
+    domain::common::{time, uuid::generate_uuid},
+};
+
+/// Delivers messages into a local Maildir so they can be read with any mail
+/// client during development. Each message is written to `tmp/` and then
+/// moved to `new/`, as the Maildir format requires.
+#[derive(Debug)]
+pub struct MaildirMailer {
+    root: PathBuf,
+    from: String,
+}
+
+impl MaildirMailer {
+    pub fn new(root: impl AsRef<Path>, from: &str) -> std::io::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        for directory in ["tmp", "new", "cur"] {
+            fs::create_dir_all(root.join(directory))?;
+        }
+
+        Ok(MaildirMailer {
+            root,
+            from: from.to_string(),
+        })
+    }
+
+    fn format(&self, message: &EmailMessage) -> String {
+        format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.from, message.to, message.subject, message.body
+        )
+    }
+}
+
+#[async_trait]
+impl Mailer for MaildirMailer {
+    async fn send(&self, message: EmailMessage) -> Result<(), MailerError> {
+        let file_name = format!("{}.{}.kata-hexagonal", time::now(), generate_uuid());
+        let tmp = self.root.join("tmp").join(&file_name);
+        let new = self.root.join("new").join(&file_name);
+
+        fs::write(&tmp, self.format(&message)).map_err(|e| MailerError(e.to_string()))?;
+        fs::rename(&tmp, &new).map_err(|e| MailerError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::application::ports::mailer::{EmailMessage, Mailer};
+
+    use super::MaildirMailer;
+
+    #[tokio::test]
+    async fn delivers_messages_into_the_new_folder() {
+        let root = tempfile::tempdir().unwrap();
+        let mailer = MaildirMailer::new(root.path(), "noreply@example.com").unwrap();
+
+        mailer
+            .send(EmailMessage {
+                to: "test@example.com".to_string(),
+                subject: "Hello".to_string(),
+                body: "World".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let delivered: Vec<_> = fs::read_dir(root.path().join("new"))
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        assert_eq!(delivered.len(), 1);
+        assert!(delivered[0].contains("To: test@example.com\r\n"));
+        assert!(delivered[0].contains("Subject: Hello\r\n"));
+        assert!(delivered[0].ends_with("World\r\n"));
+        assert_eq!(fs::read_dir(root.path().join("tmp")).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,6 @@
+create table if not exists users
+(
+    id TEXT PRIMARY KEY NOT NULL,
+    email TEXT NOT NULL,
+    password TEXT NOT NULL
+);
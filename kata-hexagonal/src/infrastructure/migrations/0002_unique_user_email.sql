@@ -0,0 +1,5 @@
+-- Emails used to be inserted without a uniqueness check. Only the first
+-- account with an email could ever log in, so keep that one and drop the
+-- unreachable duplicates before enforcing uniqueness.
+delete from users where rowid not in (select min(rowid) from users group by email);
+create unique index if not exists users_email on users (email);
@@ -0,0 +1,9 @@
+create table if not exists password_reset_tokens
+(
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at INTEGER NOT NULL,
+    used INTEGER NOT NULL DEFAULT 0
+);
+create index if not exists password_reset_tokens_user on password_reset_tokens (user_id);
@@ -0,0 +1,13 @@
+alter table users add column email_verified INTEGER NOT NULL DEFAULT 0;
+-- Accounts created before verification existed were already active.
+update users set email_verified = 1;
+create table if not exists email_verification_tokens
+(
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    issued_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    used INTEGER NOT NULL DEFAULT 0
+);
+create index if not exists email_verification_tokens_user on email_verification_tokens (user_id);
@@ -0,0 +1,2 @@
+alter table users add column failed_login_attempts INTEGER NOT NULL DEFAULT 0;
+alter table users add column locked_until INTEGER;
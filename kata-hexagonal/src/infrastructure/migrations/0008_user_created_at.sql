@@ -0,0 +1,2 @@
+alter table users add column created_at INTEGER NOT NULL DEFAULT 0;
+create index users_created_at on users (created_at, id);
@@ -0,0 +1 @@
+alter table users add column role TEXT NOT NULL DEFAULT 'user';
@@ -0,0 +1,11 @@
+create table if not exists audit_log
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL,
+    user_id TEXT,
+    email TEXT NOT NULL,
+    ip TEXT,
+    occurred_at INTEGER NOT NULL
+);
+create index if not exists audit_log_occurred_at on audit_log (occurred_at);
+create index if not exists audit_log_user_id on audit_log (user_id, occurred_at);
@@ -1,6 +1,45 @@
+pub mod account_delete_controller;
+pub mod account_restore_controller;
 pub mod actix;
+pub mod admin_cli;
+pub mod audit_log_query_controller;
+pub mod config;
+pub mod container;
+pub mod email_verification_resend_controller;
+pub mod email_verify_controller;
 pub mod http;
+pub mod in_memory_audit_log;
+pub mod in_memory_email_verification_token_repository;
+pub mod in_memory_event_bus;
+pub mod in_memory_login_challenge_repository;
+pub mod in_memory_mailer;
+pub mod in_memory_password_reset_token_repository;
+pub mod in_memory_rate_limit_store;
+pub mod in_memory_refresh_token_repository;
+pub mod in_memory_two_factor_repository;
 pub mod in_memory_user_repository;
+pub mod json_lines_audit_log;
+pub mod jwt_token_issuer;
+pub mod maildir_mailer;
+pub mod outbox_relay;
+pub mod password_forgot_controller;
+pub mod password_reset_controller;
+pub mod sqlite_audit_log;
+pub mod sqlite_email_verification_token_repository;
+pub mod sqlite_login_challenge_repository;
+pub mod sqlite_migrations;
+pub mod sqlite_outbox_repository;
+pub mod sqlite_password_reset_token_repository;
+pub mod sqlite_refresh_token_repository;
+pub mod sqlite_two_factor_repository;
 pub mod sqlite_user_repository;
+pub mod token_refresh_controller;
+pub mod two_factor_confirm_controller;
+pub mod two_factor_enroll_controller;
+pub mod user_change_password_controller;
+pub mod user_delete_controller;
+pub mod user_find_controller;
+pub mod user_list_controller;
 pub mod user_login_controller;
 pub mod user_register_controller;
+pub mod user_role_assign_controller;
@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::Notify;
+
+use crate::{
+    application::{
+        outbox_dispatcher::OutboxDispatcher,
+        ports::event_publisher::{EventPublisher, PublishError},
+    },
+    domain::{common::time, events::user_event::UserEvent},
+};
+
+/// How often the outbox is swept for entries that failed or were written by
+/// another process, such as `users-admin`.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often dispatched entries past their retention are deleted.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The publisher of the use cases when storage keeps an outbox. The events
+/// are already stored with the user by then, so publishing only wakes the
+/// background task that delivers them.
+pub struct OutboxRelay {
+    dispatcher: OutboxDispatcher,
+    wake: Notify,
+}
+
+impl OutboxRelay {
+    pub fn new(dispatcher: OutboxDispatcher) -> Self {
+        OutboxRelay {
+            dispatcher,
+            wake: Notify::new(),
+        }
+    }
+
+    /// Delivers the outbox whenever events are published, and periodically.
+    /// Prunes what was delivered long ago now and then. Runs until the task
+    /// is dropped.
+    pub async fn run(&self) {
+        let mut pruned_at: Option<Instant> = None;
+        loop {
+            if let Err(error) = self.dispatcher.dispatch_pending().await {
+                log::error!("could not dispatch the outbox: {}", error);
+            }
+            if pruned_at.is_none_or(|at| at.elapsed() >= PRUNE_INTERVAL) {
+                if let Err(error) = self.dispatcher.prune_dispatched(time::now()).await {
+                    log::error!("could not prune the outbox: {}", error);
+                }
+                pruned_at = Some(Instant::now());
+            }
+            let _ = tokio::time::timeout(SWEEP_INTERVAL, self.wake.notified()).await;
+        }
+    }
+}
+
+#[async_trait]
+impl EventPublisher for OutboxRelay {
+    async fn publish(&self, _events: Vec<UserEvent>) -> Result<(), PublishError> {
+        self.wake.notify_one();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        application::{
+            outbox_dispatcher::OutboxDispatcher, ports::event_publisher::EventPublisher,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            in_memory_event_bus::InMemoryEventBus, outbox_relay::OutboxRelay,
+            sqlite_user_repository::Sqlite,
+        },
+    };
+
+    #[tokio::test]
+    async fn delivers_the_outbox_when_woken() {
+        let repo = Arc::new(Sqlite::new(":memory:").await.unwrap());
+        let bus = Arc::new(InMemoryEventBus::new());
+        let relay = Arc::new(OutboxRelay::new(OutboxDispatcher::new(
+            repo.clone(),
+            bus.clone(),
+        )));
+        let task = tokio::spawn({
+            let relay = relay.clone();
+            async move { relay.run().await }
+        });
+        let mut user = User::register(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+
+        let _ = repo.save(user.clone()).await;
+        let _ = relay.publish(user.pull_events()).await;
+
+        let delivered = tokio::time::timeout(Duration::from_secs(1), async {
+            while bus.published().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        task.abort();
+        assert!(delivered.is_ok());
+        assert_eq!(bus.published()[0].name(), "UserRegistered");
+    }
+}
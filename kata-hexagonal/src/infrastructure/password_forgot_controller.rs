@@ -0,0 +1,119 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{MessageResponse, PasswordForgotRequest},
+    password_forgot_service::PasswordForgotService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct PasswordForgotController {
+    service: PasswordForgotService,
+}
+
+impl PasswordForgotController {
+    pub fn new(service: PasswordForgotService) -> Self {
+        PasswordForgotController { service }
+    }
+
+    pub async fn forgot<T: HttpResponse<Result<MessageResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<PasswordForgotRequest>,
+        response: &mut T,
+    ) {
+        match self.service.forgot(request.body).await {
+            Ok(forgot_response) => response.status(200).json(Ok(forgot_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            dtos::{MessageResponse, PasswordForgotRequest},
+            password_forgot_service::PasswordForgotService,
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::PasswordForgotController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<MessageResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<MessageResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<MessageResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    fn create_controller() -> PasswordForgotController {
+        PasswordForgotController::new(PasswordForgotService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn accepts_unknown_emails() {
+        let controller = create_controller();
+        let mut response = MockResponse {
+            status: 0,
+            data: None,
+        };
+
+        controller
+            .forgot(
+                HttpRequest {
+                    body: PasswordForgotRequest {
+                        email: "unknown@example.com".to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 200);
+        assert!(response.data.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_a_malformed_email() {
+        let controller = create_controller();
+        let mut response = MockResponse {
+            status: 0,
+            data: None,
+        };
+
+        controller
+            .forgot(
+                HttpRequest {
+                    body: PasswordForgotRequest {
+                        email: "not-an-email".to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 422);
+        assert!(response.data.unwrap().is_err());
+    }
+}
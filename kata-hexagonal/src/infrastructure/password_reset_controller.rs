@@ -0,0 +1,119 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{MessageResponse, PasswordResetRequest},
+    password_reset_service::PasswordResetService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct PasswordResetController {
+    service: PasswordResetService,
+}
+
+impl PasswordResetController {
+    pub fn new(service: PasswordResetService) -> Self {
+        PasswordResetController { service }
+    }
+
+    pub async fn reset<T: HttpResponse<Result<MessageResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<PasswordResetRequest>,
+        response: &mut T,
+    ) {
+        match self.service.reset(request.body).await {
+            Ok(reset_response) => response.status(200).json(Ok(reset_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            dtos::{MessageResponse, PasswordResetRequest},
+            password_reset_service::PasswordResetService,
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_audit_log::InMemoryAuditLog,
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_password_reset_token_repository::InMemoryPasswordResetTokenRepository,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::PasswordResetController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<MessageResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<MessageResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<MessageResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    fn create_controller() -> PasswordResetController {
+        PasswordResetController::new(PasswordResetService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            Arc::new(InMemoryPasswordResetTokenRepository::new()),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        ))
+    }
+
+    async fn reset(new_password: &str) -> MockResponse {
+        let mut response = MockResponse {
+            status: 0,
+            data: None,
+        };
+
+        create_controller()
+            .reset(
+                HttpRequest {
+                    body: PasswordResetRequest {
+                        token: "unknown".to_string(),
+                        new_password: new_password.to_string(),
+                        client_ip: None,
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unknown_token_as_bad_request() {
+        let response = reset("NewPass123_").await;
+
+        assert_eq!(response.status, 400);
+        assert!(matches!(
+            response.data.unwrap().unwrap_err(),
+            ApplicationError::InvalidResetToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_weak_password_as_unprocessable() {
+        let response = reset("weak").await;
+
+        assert_eq!(response.status, 422);
+    }
+}
@@ -0,0 +1,154 @@
+use async_trait::async_trait;
+use rusqlite::{named_params, types::Type, Row};
+
+use crate::{
+    application::ports::audit_log::{AuditEntry, AuditLog, AuditLogError, AuditQuery},
+    infrastructure::sqlite_user_repository::Sqlite,
+};
+
+fn audit_entry_from_row(row: &Row) -> rusqlite::Result<AuditEntry> {
+    let event_type: String = row.get("event_type")?;
+    let event_type = event_type
+        .parse()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))?;
+
+    Ok(AuditEntry {
+        event_type,
+        user_id: row.get("user_id")?,
+        email: row.get("email")?,
+        ip: row.get("ip")?,
+        occurred_at: row.get("occurred_at")?,
+    })
+}
+
+#[async_trait]
+impl AuditLog for Sqlite {
+    async fn record(&self, entry: AuditEntry) -> Result<(), AuditLogError> {
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT INTO audit_log (event_type, user_id, email, ip, occurred_at)
+                VALUES (:event_type, :user_id, :email, :ip, :occurred_at)",
+                named_params! {
+                    ":event_type": entry.event_type.as_str(),
+                    ":user_id": entry.user_id,
+                    ":email": entry.email,
+                    ":ip": entry.ip,
+                    ":occurred_at": entry.occurred_at,
+                },
+            )
+        })
+        .await
+        .map_err(|e| AuditLogError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn query(&self, query: AuditQuery) -> Result<Vec<AuditEntry>, AuditLogError> {
+        self.run(move |connection| {
+            connection
+                .prepare(
+                    "SELECT event_type, user_id, email, ip, occurred_at FROM audit_log
+                    WHERE (:user_id IS NULL OR user_id = :user_id)
+                    AND (:event_type IS NULL OR event_type = :event_type)
+                    AND (:from IS NULL OR occurred_at >= :from)
+                    AND (:until IS NULL OR occurred_at < :until)
+                    ORDER BY occurred_at DESC, id DESC LIMIT :limit",
+                )?
+                .query_map(
+                    named_params! {
+                        ":user_id": query.user_id,
+                        ":event_type": query.event_type.map(|event_type| event_type.as_str()),
+                        ":from": query.from,
+                        ":until": query.until,
+                        ":limit": query.limit,
+                    },
+                    audit_entry_from_row,
+                )?
+                .collect()
+        })
+        .await
+        .map_err(|e| AuditLogError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        application::ports::audit_log::{AuditEntry, AuditEventType, AuditLog, AuditQuery},
+        infrastructure::sqlite_user_repository::Sqlite,
+    };
+
+    fn entry(event_type: AuditEventType, user_id: &str, occurred_at: u64) -> AuditEntry {
+        AuditEntry::new(
+            event_type,
+            "test@example.com".to_string(),
+            Some("127.0.0.1".to_string()),
+            occurred_at,
+        )
+        .for_user(user_id.to_string())
+    }
+
+    fn everything() -> AuditQuery {
+        AuditQuery {
+            user_id: None,
+            event_type: None,
+            from: None,
+            until: None,
+            limit: 100,
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_recorded_entries_newest_first() {
+        let log = Sqlite::new(":memory:").await.unwrap();
+        let first = entry(AuditEventType::Registered, "a", 10);
+        let second = entry(AuditEventType::LoginSucceeded, "a", 20);
+
+        let _ = log.record(first.clone()).await;
+        let _ = log.record(second.clone()).await;
+
+        assert_eq!(log.query(everything()).await, Ok(vec![second, first]));
+    }
+
+    #[tokio::test]
+    async fn filters_by_user_event_type_and_time_range() {
+        let log = Sqlite::new(":memory:").await.unwrap();
+        let matching = entry(AuditEventType::LoginFailed, "a", 20);
+        for entry in [
+            entry(AuditEventType::LoginFailed, "a", 10),
+            matching.clone(),
+            entry(AuditEventType::LoginFailed, "a", 30),
+            entry(AuditEventType::LoginSucceeded, "a", 20),
+            entry(AuditEventType::LoginFailed, "b", 20),
+        ] {
+            let _ = log.record(entry).await;
+        }
+
+        let found = log
+            .query(AuditQuery {
+                user_id: Some("a".to_string()),
+                event_type: Some(AuditEventType::LoginFailed),
+                from: Some(15),
+                until: Some(30),
+                limit: 100,
+            })
+            .await;
+
+        assert_eq!(found, Ok(vec![matching]));
+    }
+
+    #[tokio::test]
+    async fn keeps_entries_of_unknown_users() {
+        let log = Sqlite::new(":memory:").await.unwrap();
+        let unknown = AuditEntry::new(
+            AuditEventType::LoginFailed,
+            "nobody@example.com".to_string(),
+            None,
+            10,
+        );
+
+        let _ = log.record(unknown.clone()).await;
+
+        assert_eq!(log.query(everything()).await, Ok(vec![unknown]));
+    }
+}
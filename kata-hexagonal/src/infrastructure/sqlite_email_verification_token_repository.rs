@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+use rusqlite::{named_params, types::Type, OptionalExtension, Row};
+
+use crate::{
+    domain::{
+        entities::email_verification_token::EmailVerificationToken,
+        repositories::{
+            email_verification_token_repository::EmailVerificationTokenRepository,
+            repository_error::RepositoryError,
+        },
+        value_objects::id::Id,
+    },
+    infrastructure::sqlite_user_repository::Sqlite,
+};
+
+fn email_verification_token_from_row(row: &Row) -> rusqlite::Result<EmailVerificationToken> {
+    let id = |index: usize, value: String| {
+        Id::from(value)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+    };
+
+    Ok(EmailVerificationToken::new(
+        id(0, row.get("id")?)?,
+        id(1, row.get("user_id")?)?,
+        row.get("token_hash")?,
+        row.get("issued_at")?,
+        row.get("expires_at")?,
+        row.get("used")?,
+    ))
+}
+
+#[async_trait]
+impl EmailVerificationTokenRepository for Sqlite {
+    async fn save(&self, token: EmailVerificationToken) -> Result<(), RepositoryError> {
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT INTO email_verification_tokens
+                (id, user_id, token_hash, issued_at, expires_at, used)
+                VALUES (:id, :user_id, :token_hash, :issued_at, :expires_at, :used)
+                ON CONFLICT (id) DO UPDATE SET used = excluded.used",
+                named_params! {
+                    ":id": token.id().to_string(),
+                    ":user_id": token.user_id().to_string(),
+                    ":token_hash": token.token_hash(),
+                    ":issued_at": token.issued_at(),
+                    ":expires_at": token.expires_at(),
+                    ":used": token.is_used(),
+                },
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<EmailVerificationToken>, RepositoryError> {
+        let token_hash = token_hash.to_string();
+
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT id, user_id, token_hash, issued_at, expires_at, used
+                    FROM email_verification_tokens WHERE token_hash = :token_hash",
+                    named_params! { ":token_hash": token_hash },
+                    email_verification_token_from_row,
+                )
+                .optional()
+        })
+        .await
+    }
+
+    async fn find_latest_for_user(
+        &self,
+        user_id: &Id,
+    ) -> Result<Option<EmailVerificationToken>, RepositoryError> {
+        let user_id = user_id.to_string();
+
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT id, user_id, token_hash, issued_at, expires_at, used
+                    FROM email_verification_tokens WHERE user_id = :user_id
+                    ORDER BY issued_at DESC, rowid DESC LIMIT 1",
+                    named_params! { ":user_id": user_id },
+                    email_verification_token_from_row,
+                )
+                .optional()
+        })
+        .await
+    }
+
+    async fn mark_as_used(&self, token: &EmailVerificationToken) -> Result<bool, RepositoryError> {
+        let id = token.id().to_string();
+
+        let updated = self
+            .run(move |connection| {
+                connection.execute(
+                    "UPDATE email_verification_tokens SET used = 1 WHERE id = :id AND used = 0",
+                    named_params! { ":id": id },
+                )
+            })
+            .await?;
+
+        Ok(updated == 1)
+    }
+
+    async fn invalidate_for_user(&self, user_id: &Id) -> Result<(), RepositoryError> {
+        let user_id = user_id.to_string();
+
+        self.run(move |connection| {
+            connection.execute(
+                "UPDATE email_verification_tokens SET used = 1 WHERE user_id = :user_id",
+                named_params! { ":user_id": user_id },
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::{
+            entities::email_verification_token::EmailVerificationToken,
+            repositories::email_verification_token_repository::EmailVerificationTokenRepository,
+            value_objects::id::Id,
+        },
+        infrastructure::sqlite_user_repository::Sqlite,
+    };
+
+    #[tokio::test]
+    async fn finds_a_token_by_hash() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let (token, plaintext) =
+            EmailVerificationToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = EmailVerificationTokenRepository::save(&repo, token.clone()).await;
+
+        let found = repo
+            .find_by_hash(&EmailVerificationToken::hash(&plaintext))
+            .await;
+
+        assert_eq!(found, Ok(Some(token)));
+    }
+
+    #[tokio::test]
+    async fn marks_a_token_as_used_only_once() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let (token, _) = EmailVerificationToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = EmailVerificationTokenRepository::save(&repo, token.clone()).await;
+
+        assert_eq!(repo.mark_as_used(&token).await, Ok(true));
+        assert_eq!(repo.mark_as_used(&token).await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn finds_the_latest_token_of_a_user() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user_id = Id::generate_unique_identifier();
+        let (older, _) = EmailVerificationToken::issue(user_id.clone(), 60, 10);
+        let (latest, _) = EmailVerificationToken::issue(user_id.clone(), 60, 20);
+        let _ = EmailVerificationTokenRepository::save(&repo, latest.clone()).await;
+        let _ = EmailVerificationTokenRepository::save(&repo, older).await;
+
+        assert_eq!(repo.find_latest_for_user(&user_id).await, Ok(Some(latest)));
+    }
+
+    #[tokio::test]
+    async fn invalidates_every_token_of_a_user() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user_id = Id::generate_unique_identifier();
+        let (a_token, a_plaintext) = EmailVerificationToken::issue(user_id.clone(), 60, 0);
+        let (another_token, another_plaintext) =
+            EmailVerificationToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = EmailVerificationTokenRepository::save(&repo, a_token).await;
+        let _ = EmailVerificationTokenRepository::save(&repo, another_token).await;
+
+        let _ = repo.invalidate_for_user(&user_id).await;
+
+        let a_token = repo
+            .find_by_hash(&EmailVerificationToken::hash(&a_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        let another_token = repo
+            .find_by_hash(&EmailVerificationToken::hash(&another_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(a_token.is_used());
+        assert!(!another_token.is_used());
+    }
+}
@@ -0,0 +1,181 @@
+use async_trait::async_trait;
+use rusqlite::{named_params, types::Type, OptionalExtension, Row};
+
+use crate::{
+    domain::{
+        entities::login_challenge::{LoginChallenge, MAX_LOGIN_CHALLENGE_ATTEMPTS},
+        repositories::{
+            login_challenge_repository::LoginChallengeRepository, repository_error::RepositoryError,
+        },
+        value_objects::id::Id,
+    },
+    infrastructure::sqlite_user_repository::Sqlite,
+};
+
+fn login_challenge_from_row(row: &Row) -> rusqlite::Result<LoginChallenge> {
+    let id = |index: usize, value: String| {
+        Id::from(value)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+    };
+
+    Ok(LoginChallenge::new(
+        id(0, row.get("id")?)?,
+        id(1, row.get("user_id")?)?,
+        row.get("token_hash")?,
+        row.get("expires_at")?,
+        row.get("attempts")?,
+    ))
+}
+
+#[async_trait]
+impl LoginChallengeRepository for Sqlite {
+    async fn save(&self, challenge: LoginChallenge) -> Result<(), RepositoryError> {
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT INTO login_challenges (id, user_id, token_hash, expires_at, attempts)
+                VALUES (:id, :user_id, :token_hash, :expires_at, :attempts)",
+                named_params! {
+                    ":id": challenge.id().to_string(),
+                    ":user_id": challenge.user_id().to_string(),
+                    ":token_hash": challenge.token_hash(),
+                    ":expires_at": challenge.expires_at(),
+                    ":attempts": challenge.attempts(),
+                },
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<LoginChallenge>, RepositoryError> {
+        let token_hash = token_hash.to_string();
+
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT id, user_id, token_hash, expires_at, attempts
+                    FROM login_challenges WHERE token_hash = :token_hash",
+                    named_params! { ":token_hash": token_hash },
+                    login_challenge_from_row,
+                )
+                .optional()
+        })
+        .await
+    }
+
+    async fn claim_attempt(&self, id: &Id) -> Result<bool, RepositoryError> {
+        let id = id.to_string();
+
+        let claimed = self
+            .run(move |connection| {
+                connection.execute(
+                    "UPDATE login_challenges SET attempts = attempts + 1
+                    WHERE id = :id AND attempts < :max_attempts",
+                    named_params! { ":id": id, ":max_attempts": MAX_LOGIN_CHALLENGE_ATTEMPTS },
+                )
+            })
+            .await?;
+
+        Ok(claimed == 1)
+    }
+
+    async fn remove(&self, id: &Id) -> Result<bool, RepositoryError> {
+        let id = id.to_string();
+
+        let deleted = self
+            .run(move |connection| {
+                connection.execute(
+                    "DELETE FROM login_challenges WHERE id = :id",
+                    named_params! { ":id": id },
+                )
+            })
+            .await?;
+
+        Ok(deleted == 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::{
+            entities::login_challenge::{LoginChallenge, MAX_LOGIN_CHALLENGE_ATTEMPTS},
+            repositories::login_challenge_repository::LoginChallengeRepository,
+            value_objects::id::Id,
+        },
+        infrastructure::sqlite_user_repository::Sqlite,
+    };
+
+    #[tokio::test]
+    async fn claims_attempts_up_to_the_limit() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let (challenge, plaintext) = LoginChallenge::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = LoginChallengeRepository::save(&repo, challenge.clone()).await;
+
+        for _ in 0..MAX_LOGIN_CHALLENGE_ATTEMPTS {
+            assert_eq!(repo.claim_attempt(challenge.id()).await, Ok(true));
+        }
+        assert_eq!(repo.claim_attempt(challenge.id()).await, Ok(false));
+
+        let found = repo
+            .find_by_hash(&LoginChallenge::hash(&plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.attempts(), MAX_LOGIN_CHALLENGE_ATTEMPTS);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn claims_no_more_attempts_than_the_limit_in_parallel() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let repo = Sqlite::new(file.path().to_str().unwrap()).await.unwrap();
+        let (challenge, _) = LoginChallenge::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = LoginChallengeRepository::save(&repo, challenge.clone()).await;
+
+        let claims = (0..16).map(|_| {
+            let repo = repo.clone();
+            let id = challenge.id().clone();
+            tokio::spawn(async move { repo.claim_attempt(&id).await })
+        });
+        let mut claimed = 0;
+        for claim in claims.collect::<Vec<_>>() {
+            if claim.await.unwrap() == Ok(true) {
+                claimed += 1;
+            }
+        }
+
+        assert_eq!(claimed, MAX_LOGIN_CHALLENGE_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn does_not_bring_back_a_removed_challenge() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let (challenge, plaintext) = LoginChallenge::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = LoginChallengeRepository::save(&repo, challenge.clone()).await;
+
+        assert_eq!(repo.remove(challenge.id()).await, Ok(true));
+        assert_eq!(repo.claim_attempt(challenge.id()).await, Ok(false));
+        assert_eq!(
+            repo.find_by_hash(&LoginChallenge::hash(&plaintext)).await,
+            Ok(None)
+        );
+    }
+
+    #[tokio::test]
+    async fn removes_a_challenge_once() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let (challenge, plaintext) = LoginChallenge::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = LoginChallengeRepository::save(&repo, challenge.clone()).await;
+
+        assert_eq!(repo.remove(challenge.id()).await, Ok(true));
+        assert_eq!(repo.remove(challenge.id()).await, Ok(false));
+        assert_eq!(
+            repo.find_by_hash(&LoginChallenge::hash(&plaintext)).await,
+            Ok(None)
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use rusqlite::{named_params, Connection, OptionalExtension};
+
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    sql: &'static str,
+}
+
+/// Ordered schema migrations, embedded at compile time. Append only: never edit
+/// or reorder a migration that has already shipped.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_users",
+        sql: include_str!("migrations/0001_create_users.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "unique_user_email",
+        sql: include_str!("migrations/0002_unique_user_email.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "create_refresh_tokens",
+        sql: include_str!("migrations/0003_create_refresh_tokens.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "create_password_reset_tokens",
+        sql: include_str!("migrations/0004_create_password_reset_tokens.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "email_verification",
+        sql: include_str!("migrations/0005_email_verification.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "login_attempts",
+        sql: include_str!("migrations/0006_login_attempts.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "soft_delete_users",
+        sql: include_str!("migrations/0007_soft_delete_users.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "user_created_at",
+        sql: include_str!("migrations/0008_user_created_at.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "user_roles",
+        sql: include_str!("migrations/0009_user_roles.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "outbox",
+        sql: include_str!("migrations/0010_outbox.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "audit_log",
+        sql: include_str!("migrations/0011_audit_log.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "two_factor",
+        sql: include_str!("migrations/0012_two_factor.sql"),
+    },
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum MigrationError {
+    #[error("Database schema version {found} is newer than the latest known version {latest}")]
+    UnknownVersion { found: u32, latest: u32 },
+    #[error("Migration {version} ({name}) failed: {source}")]
+    Failed {
+        version: u32,
+        name: &'static str,
+        source: rusqlite::Error,
+    },
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Returns the highest applied migration version, or 0 for an unmanaged
+/// database. Only reads, so it can inspect a database without altering it.
+pub fn current_version(connection: &Connection) -> Result<u32, MigrationError> {
+    let managed = connection
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
+            (),
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !managed {
+        return Ok(0);
+    }
+
+    let version = connection
+        .query_row("SELECT MAX(version) FROM schema_migrations", (), |row| {
+            row.get::<_, Option<u32>>(0)
+        })
+        .optional()?
+        .flatten();
+
+    Ok(version.unwrap_or(0))
+}
+
+/// Applies every pending migration in order, each in its own transaction.
+/// Returns the resulting schema version.
+pub fn migrate(connection: &mut Connection) -> Result<u32, MigrationError> {
+    ensure_migrations_table(connection)?;
+    let current = current_version(connection)?;
+    let latest = latest_version();
+
+    if current > latest {
+        return Err(MigrationError::UnknownVersion {
+            found: current,
+            latest,
+        });
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        apply(connection, migration).map_err(|source| MigrationError::Failed {
+            version: migration.version,
+            name: migration.name,
+            source,
+        })?;
+        log::info!(
+            "applied migration {} ({})",
+            migration.version,
+            migration.name
+        );
+    }
+
+    current_version(connection)
+}
+
+fn apply(connection: &mut Connection, migration: &Migration) -> rusqlite::Result<()> {
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(migration.sql)?;
+    transaction.execute(
+        "INSERT INTO schema_migrations (version, name) VALUES (:version, :name)",
+        named_params! {
+            ":version": migration.version,
+            ":name": migration.name,
+        },
+    )?;
+    transaction.commit()
+}
+
+fn ensure_migrations_table(connection: &Connection) -> rusqlite::Result<()> {
+    connection.execute_batch(
+        "create table if not exists schema_migrations
+        (
+            version INTEGER PRIMARY KEY NOT NULL,
+            name TEXT NOT NULL,
+            applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use rusqlite::Connection;
+
+    use super::{current_version, latest_version, migrate, MigrationError, MIGRATIONS};
+
+    #[test]
+    fn migrations_are_strictly_ordered() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+        assert_eq!(MIGRATIONS[0].version, 1);
+    }
+
+    #[test]
+    fn reports_version_zero_for_an_empty_database() {
+        let connection = Connection::open_in_memory().unwrap();
+
+        assert_eq!(current_version(&connection).unwrap(), 0);
+        let tables: u32 = connection
+            .query_row("SELECT COUNT(*) FROM sqlite_master", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(tables, 0);
+    }
+
+    #[test]
+    fn applies_all_migrations_to_an_empty_database() {
+        let mut connection = Connection::open_in_memory().unwrap();
+
+        assert_eq!(migrate(&mut connection).unwrap(), latest_version());
+        assert_eq!(current_version(&connection).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn running_migrations_twice_is_a_no_op() {
+        let mut connection = Connection::open_in_memory().unwrap();
+
+        migrate(&mut connection).unwrap();
+        assert_eq!(migrate(&mut connection).unwrap(), latest_version());
+
+        let applied: u32 = connection
+            .query_row("SELECT COUNT(*) FROM schema_migrations", (), |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(applied as usize, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn keeps_the_first_user_of_a_duplicated_email() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "create table users
+                (
+                    id TEXT PRIMARY KEY NOT NULL,
+                    email TEXT NOT NULL,
+                    password TEXT NOT NULL
+                );
+                INSERT INTO users VALUES ('first', 'test@example.com', 'hash');
+                INSERT INTO users VALUES ('second', 'test@example.com', 'hash');
+                INSERT INTO users VALUES ('other', 'other@example.com', 'hash');",
+            )
+            .unwrap();
+
+        assert_eq!(migrate(&mut connection).unwrap(), latest_version());
+
+        let ids: Vec<String> = connection
+            .prepare("SELECT id FROM users ORDER BY rowid")
+            .unwrap()
+            .query_map((), |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(ids, vec!["first", "other"]);
+    }
+
+    #[test]
+    fn refuses_a_database_from_a_newer_release() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        connection
+            .execute(
+                "INSERT INTO schema_migrations (version, name) VALUES (?1, 'future')",
+                (latest_version() + 1,),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            migrate(&mut connection),
+            Err(MigrationError::UnknownVersion { .. })
+        ));
+    }
+}
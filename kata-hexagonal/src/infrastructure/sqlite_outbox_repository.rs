@@ -0,0 +1,280 @@
+use async_trait::async_trait;
+use rusqlite::{named_params, Connection};
+
+use crate::{
+    domain::{
+        events::user_event::UserEvent,
+        repositories::{
+            outbox_repository::{OutboxEntry, OutboxRepository},
+            repository_error::RepositoryError,
+        },
+    },
+    infrastructure::sqlite_user_repository::Sqlite,
+};
+
+/// Stores `events` through `connection`, which is expected to be the
+/// transaction that saves the user who recorded them.
+pub(crate) fn append_to_outbox(
+    connection: &Connection,
+    events: &[UserEvent],
+) -> rusqlite::Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let mut statement = connection.prepare_cached(
+        "INSERT INTO outbox (event_type, user_id, payload, occurred_at)
+        VALUES (:event_type, :user_id, :payload, :occurred_at)",
+    )?;
+    for event in events {
+        let payload = serde_json::to_string(event)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        statement.execute(named_params! {
+            ":event_type": event.name(),
+            ":user_id": event.user_id(),
+            ":payload": payload,
+            ":occurred_at": event.occurred_at(),
+        })?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl OutboxRepository for Sqlite {
+    async fn find_pending(
+        &self,
+        limit: usize,
+        max_attempts: u32,
+    ) -> Result<Vec<OutboxEntry>, RepositoryError> {
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let rows = transaction
+                .prepare(
+                    "SELECT id, payload, attempts FROM outbox WHERE dispatched_at IS NULL
+                    AND attempts < :max_attempts ORDER BY id LIMIT :limit",
+                )?
+                .query_map(
+                    named_params! { ":limit": limit, ":max_attempts": max_attempts },
+                    |row| {
+                        Ok((
+                            row.get::<_, u64>("id")?,
+                            row.get::<_, String>("payload")?,
+                            row.get::<_, u32>("attempts")?,
+                        ))
+                    },
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut entries = Vec::with_capacity(rows.len());
+            for (id, payload, attempts) in rows {
+                match serde_json::from_str(&payload) {
+                    Ok(event) => entries.push(OutboxEntry {
+                        id,
+                        event,
+                        attempts,
+                    }),
+                    Err(error) => {
+                        transaction.execute(
+                            "UPDATE outbox SET attempts = :max_attempts, last_error = :error
+                            WHERE id = :id",
+                            named_params! {
+                                ":id": id,
+                                ":max_attempts": max_attempts,
+                                ":error": format!("unreadable payload: {}", error),
+                            },
+                        )?;
+                    }
+                }
+            }
+            transaction.commit()?;
+            Ok(entries)
+        })
+        .await
+    }
+
+    async fn mark_dispatched(&self, id: u64, now: u64) -> Result<(), RepositoryError> {
+        self.run(move |connection| {
+            connection.execute(
+                "UPDATE outbox SET dispatched_at = :now WHERE id = :id",
+                named_params! { ":id": id, ":now": now },
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn record_failure(&self, id: u64, error: String) -> Result<(), RepositoryError> {
+        self.run(move |connection| {
+            connection.execute(
+                "UPDATE outbox SET attempts = attempts + 1, last_error = :error WHERE id = :id",
+                named_params! { ":id": id, ":error": error },
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn prune_dispatched_before(&self, cutoff: u64) -> Result<u64, RepositoryError> {
+        let pruned = self
+            .run(move |connection| {
+                connection.execute(
+                    "DELETE FROM outbox WHERE dispatched_at IS NOT NULL AND dispatched_at < :cutoff",
+                    named_params! { ":cutoff": cutoff },
+                )
+            })
+            .await?;
+
+        Ok(pruned as u64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        application::outbox_dispatcher::MAX_DELIVERY_ATTEMPTS,
+        domain::{
+            entities::user::User,
+            repositories::{outbox_repository::OutboxRepository, user_repository::UserRepository},
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::sqlite_user_repository::Sqlite,
+    };
+
+    fn registered_user() -> User {
+        User::register(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn stores_the_events_of_a_saved_user() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user = registered_user();
+
+        let _ = repo.save(user.clone()).await;
+
+        let pending = repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event, user.events()[0]);
+        assert_eq!(pending[0].attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn stores_no_events_when_the_user_is_not_saved() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let _ = repo.save(registered_user()).await;
+        let pending = repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await.unwrap();
+        let _ = repo.mark_dispatched(pending[0].id, 100).await;
+
+        let duplicate = repo.save(registered_user()).await;
+
+        assert!(duplicate.is_err());
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await,
+            Ok(vec![])
+        );
+    }
+
+    #[tokio::test]
+    async fn counts_failed_deliveries() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let _ = repo.save(registered_user()).await;
+        let id = repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await.unwrap()[0].id;
+
+        let _ = repo.record_failure(id, "down".to_string()).await;
+        let _ = repo.record_failure(id, "down".to_string()).await;
+
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await.unwrap()[0].attempts,
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn leaves_out_entries_out_of_attempts() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let _ = repo.save(registered_user()).await;
+        let id = repo.find_pending(10, 2).await.unwrap()[0].id;
+
+        let _ = repo.record_failure(id, "down".to_string()).await;
+        let _ = repo.record_failure(id, "down".to_string()).await;
+
+        assert_eq!(repo.find_pending(10, 2).await, Ok(vec![]));
+        assert_eq!(repo.find_pending(10, 3).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn parks_an_unreadable_entry_without_holding_back_the_others() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let _ = repo
+            .run(|connection| {
+                connection.execute(
+                    "INSERT INTO outbox (event_type, user_id, payload, occurred_at)
+                    VALUES ('UserRegistered', 'someone', 'not json', 100)",
+                    (),
+                )
+            })
+            .await;
+        let user = registered_user();
+        let _ = repo.save(user.clone()).await;
+
+        let pending = repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await.unwrap();
+        let attempts: u32 = repo
+            .run(|connection| {
+                connection.query_row(
+                    "SELECT attempts FROM outbox WHERE payload = 'not json'",
+                    (),
+                    |row| row.get(0),
+                )
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event, user.events()[0]);
+        assert_eq!(attempts, MAX_DELIVERY_ATTEMPTS);
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await,
+            Ok(pending)
+        );
+    }
+
+    #[tokio::test]
+    async fn prunes_entries_dispatched_before_the_cutoff() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let _ = repo.save(registered_user()).await;
+        let _ = repo
+            .save(User::register(
+                Id::generate_unique_identifier(),
+                Email::new("other@example.com".to_string()).unwrap(),
+                Password::new("TestPass123_".to_string()).unwrap(),
+            ))
+            .await;
+        let _ = repo
+            .save(User::register(
+                Id::generate_unique_identifier(),
+                Email::new("pending@example.com".to_string()).unwrap(),
+                Password::new("TestPass123_".to_string()).unwrap(),
+            ))
+            .await;
+        let pending = repo.find_pending(10, MAX_DELIVERY_ATTEMPTS).await.unwrap();
+        let _ = repo.mark_dispatched(pending[0].id, 100).await;
+        let _ = repo.mark_dispatched(pending[1].id, 200).await;
+
+        let pruned = repo.prune_dispatched_before(200).await;
+
+        let remaining = repo
+            .run(|connection| {
+                connection.query_row("SELECT COUNT(*) FROM outbox", (), |row| {
+                    row.get::<_, u32>(0)
+                })
+            })
+            .await;
+        assert_eq!(pruned, Ok(1));
+        assert_eq!(remaining, Ok(2));
+    }
+}
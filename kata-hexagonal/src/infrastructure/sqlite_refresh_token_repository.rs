@@ -0,0 +1,212 @@
+use async_trait::async_trait;
+use rusqlite::{named_params, types::Type, OptionalExtension, Row};
+
+use crate::{
+    domain::{
+        entities::refresh_token::RefreshToken,
+        repositories::{
+            refresh_token_repository::RefreshTokenRepository, repository_error::RepositoryError,
+        },
+        value_objects::id::Id,
+    },
+    infrastructure::sqlite_user_repository::Sqlite,
+};
+
+fn refresh_token_from_row(row: &Row) -> rusqlite::Result<RefreshToken> {
+    let id = |index: usize, value: String| {
+        Id::from(value)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+    };
+
+    Ok(RefreshToken::new(
+        id(0, row.get("id")?)?,
+        id(1, row.get("family_id")?)?,
+        id(2, row.get("user_id")?)?,
+        row.get("token_hash")?,
+        row.get("expires_at")?,
+        row.get("used")?,
+        row.get("revoked")?,
+    ))
+}
+
+#[async_trait]
+impl RefreshTokenRepository for Sqlite {
+    async fn save(&self, token: RefreshToken) -> Result<(), RepositoryError> {
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT INTO refresh_tokens
+                (id, family_id, user_id, token_hash, expires_at, used, revoked)
+                VALUES (:id, :family_id, :user_id, :token_hash, :expires_at, :used, :revoked)
+                ON CONFLICT (id) DO UPDATE SET used = excluded.used, revoked = excluded.revoked",
+                named_params! {
+                    ":id": token.id().to_string(),
+                    ":family_id": token.family_id().to_string(),
+                    ":user_id": token.user_id().to_string(),
+                    ":token_hash": token.token_hash(),
+                    ":expires_at": token.expires_at(),
+                    ":used": token.is_used(),
+                    ":revoked": token.is_revoked(),
+                },
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<RefreshToken>, RepositoryError> {
+        let token_hash = token_hash.to_string();
+
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT id, family_id, user_id, token_hash, expires_at, used, revoked
+                    FROM refresh_tokens WHERE token_hash = :token_hash",
+                    named_params! { ":token_hash": token_hash },
+                    refresh_token_from_row,
+                )
+                .optional()
+        })
+        .await
+    }
+
+    async fn mark_as_used(&self, token: &RefreshToken) -> Result<bool, RepositoryError> {
+        let id = token.id().to_string();
+
+        let updated = self
+            .run(move |connection| {
+                connection.execute(
+                    "UPDATE refresh_tokens SET used = 1 WHERE id = :id AND used = 0",
+                    named_params! { ":id": id },
+                )
+            })
+            .await?;
+
+        Ok(updated == 1)
+    }
+
+    async fn revoke_family(&self, family_id: &Id) -> Result<(), RepositoryError> {
+        let family_id = family_id.to_string();
+
+        self.run(move |connection| {
+            connection.execute(
+                "UPDATE refresh_tokens SET revoked = 1 WHERE family_id = :family_id",
+                named_params! { ":family_id": family_id },
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: &Id) -> Result<(), RepositoryError> {
+        let user_id = user_id.to_string();
+
+        self.run(move |connection| {
+            connection.execute(
+                "UPDATE refresh_tokens SET revoked = 1 WHERE user_id = :user_id",
+                named_params! { ":user_id": user_id },
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::{
+            entities::refresh_token::RefreshToken,
+            repositories::refresh_token_repository::RefreshTokenRepository, value_objects::id::Id,
+        },
+        infrastructure::sqlite_user_repository::Sqlite,
+    };
+
+    #[tokio::test]
+    async fn finds_a_token_by_hash() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let (token, plaintext) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = RefreshTokenRepository::save(&repo, token.clone()).await;
+
+        let found = repo.find_by_hash(&RefreshToken::hash(&plaintext)).await;
+
+        assert_eq!(found, Ok(Some(token)));
+    }
+
+    #[tokio::test]
+    async fn does_not_find_an_unknown_token() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        assert_eq!(repo.find_by_hash("unknown").await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn marks_a_token_as_used_only_once() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let (token, _) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = RefreshTokenRepository::save(&repo, token.clone()).await;
+
+        assert_eq!(repo.mark_as_used(&token).await, Ok(true));
+        assert_eq!(repo.mark_as_used(&token).await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn revokes_every_token_in_a_family() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let (token, _) = RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let (rotated, rotated_plaintext) = token.rotate(60, 0);
+        let (unrelated, unrelated_plaintext) =
+            RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = RefreshTokenRepository::save(&repo, token.clone()).await;
+        let _ = RefreshTokenRepository::save(&repo, rotated).await;
+        let _ = RefreshTokenRepository::save(&repo, unrelated).await;
+
+        let _ = repo.revoke_family(token.family_id()).await;
+
+        let rotated = repo
+            .find_by_hash(&RefreshToken::hash(&rotated_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        let unrelated = repo
+            .find_by_hash(&RefreshToken::hash(&unrelated_plaintext))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(rotated.is_revoked());
+        assert!(!unrelated.is_revoked());
+    }
+
+    #[tokio::test]
+    async fn revokes_every_token_of_a_user() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user_id = Id::generate_unique_identifier();
+        let (token, plaintext) = RefreshToken::issue(user_id.clone(), 60, 0);
+        let (other_session, other_plaintext) = RefreshToken::issue(user_id.clone(), 60, 0);
+        let (unrelated, unrelated_plaintext) =
+            RefreshToken::issue(Id::generate_unique_identifier(), 60, 0);
+        let _ = RefreshTokenRepository::save(&repo, token).await;
+        let _ = RefreshTokenRepository::save(&repo, other_session).await;
+        let _ = RefreshTokenRepository::save(&repo, unrelated).await;
+
+        let _ = repo.revoke_all_for_user(&user_id).await;
+
+        for (plaintext, revoked) in [
+            (plaintext, true),
+            (other_plaintext, true),
+            (unrelated_plaintext, false),
+        ] {
+            let stored = repo
+                .find_by_hash(&RefreshToken::hash(&plaintext))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.is_revoked(), revoked);
+        }
+    }
+}
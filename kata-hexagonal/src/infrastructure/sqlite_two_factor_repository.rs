@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use rusqlite::{named_params, types::Type, OptionalExtension};
+
+use crate::{
+    domain::{
+        common::totp,
+        entities::two_factor::TwoFactor,
+        repositories::{
+            repository_error::RepositoryError, two_factor_repository::TwoFactorRepository,
+        },
+        value_objects::id::Id,
+    },
+    infrastructure::sqlite_user_repository::Sqlite,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("Stored TOTP secret is not valid base32")]
+struct InvalidSecretError;
+
+#[async_trait]
+impl TwoFactorRepository for Sqlite {
+    async fn save(&self, two_factor: TwoFactor) -> Result<(), RepositoryError> {
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            transaction.execute(
+                "INSERT INTO two_factor (user_id, secret, confirmed, last_used_step)
+                VALUES (:user_id, :secret, :confirmed, :last_used_step)
+                ON CONFLICT (user_id) DO UPDATE SET secret = excluded.secret,
+                confirmed = excluded.confirmed, last_used_step = excluded.last_used_step",
+                named_params! {
+                    ":user_id": two_factor.user_id().to_string(),
+                    ":secret": totp::encode_secret(two_factor.secret()),
+                    ":confirmed": two_factor.is_confirmed(),
+                    ":last_used_step": two_factor.last_used_step(),
+                },
+            )?;
+            transaction.execute(
+                "DELETE FROM recovery_codes WHERE user_id = :user_id",
+                named_params! { ":user_id": two_factor.user_id().to_string() },
+            )?;
+            for code_hash in two_factor.recovery_code_hashes() {
+                transaction.execute(
+                    "INSERT INTO recovery_codes (user_id, code_hash) VALUES (:user_id, :code_hash)",
+                    named_params! {
+                        ":user_id": two_factor.user_id().to_string(),
+                        ":code_hash": code_hash,
+                    },
+                )?;
+            }
+            transaction.commit()
+        })
+        .await
+    }
+
+    async fn find_by_user_id(&self, user_id: &Id) -> Result<Option<TwoFactor>, RepositoryError> {
+        let user_id = user_id.clone();
+
+        self.run(move |connection| {
+            let row = connection
+                .query_row(
+                    "SELECT secret, confirmed, last_used_step FROM two_factor
+                    WHERE user_id = :user_id",
+                    named_params! { ":user_id": user_id.to_string() },
+                    |row| {
+                        let secret: String = row.get("secret")?;
+                        let secret = totp::decode_secret(&secret).ok_or_else(|| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                0,
+                                Type::Text,
+                                Box::new(InvalidSecretError),
+                            )
+                        })?;
+                        Ok((secret, row.get("confirmed")?, row.get("last_used_step")?))
+                    },
+                )
+                .optional()?;
+            let Some((secret, confirmed, last_used_step)) = row else {
+                return Ok(None);
+            };
+
+            let recovery_code_hashes = connection
+                .prepare("SELECT code_hash FROM recovery_codes WHERE user_id = :user_id")?
+                .query_map(named_params! { ":user_id": user_id.to_string() }, |row| {
+                    row.get("code_hash")
+                })?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+
+            Ok(Some(TwoFactor::new(
+                user_id,
+                secret,
+                confirmed,
+                last_used_step,
+                recovery_code_hashes,
+            )))
+        })
+        .await
+    }
+
+    async fn claim_step(&self, user_id: &Id, step: u64) -> Result<bool, RepositoryError> {
+        let user_id = user_id.to_string();
+
+        let updated = self
+            .run(move |connection| {
+                connection.execute(
+                    "UPDATE two_factor SET last_used_step = :step
+                    WHERE user_id = :user_id AND (last_used_step IS NULL OR last_used_step < :step)",
+                    named_params! { ":user_id": user_id, ":step": step },
+                )
+            })
+            .await?;
+
+        Ok(updated == 1)
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        user_id: &Id,
+        code_hash: &str,
+    ) -> Result<bool, RepositoryError> {
+        let user_id = user_id.to_string();
+        let code_hash = code_hash.to_string();
+
+        let deleted = self
+            .run(move |connection| {
+                connection.execute(
+                    "DELETE FROM recovery_codes WHERE user_id = :user_id AND code_hash = :code_hash",
+                    named_params! { ":user_id": user_id, ":code_hash": code_hash },
+                )
+            })
+            .await?;
+
+        Ok(deleted == 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::{
+            entities::two_factor::TwoFactor,
+            repositories::two_factor_repository::TwoFactorRepository, value_objects::id::Id,
+        },
+        infrastructure::sqlite_user_repository::Sqlite,
+    };
+
+    fn confirmed(user_id: &Id) -> TwoFactor {
+        TwoFactor::new(
+            user_id.clone(),
+            b"12345678901234567890".to_vec(),
+            true,
+            Some(7),
+            vec![
+                TwoFactor::hash("abcde-fghij"),
+                TwoFactor::hash("klmno-pqrst"),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn stores_the_secret_and_recovery_codes() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user_id = Id::generate_unique_identifier();
+        let two_factor = confirmed(&user_id);
+
+        let _ = TwoFactorRepository::save(&repo, two_factor.clone()).await;
+
+        let mut found = repo.find_by_user_id(&user_id).await.unwrap().unwrap();
+        assert_eq!(found.secret(), two_factor.secret());
+        assert!(found.is_confirmed());
+        assert_eq!(found.last_used_step(), Some(7));
+        assert!(found.remove_recovery_code(&TwoFactor::hash("klmno-pqrst")));
+        assert_eq!(found.recovery_code_hashes().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn replaces_an_unconfirmed_enrollment() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user_id = Id::generate_unique_identifier();
+        let _ = TwoFactorRepository::save(&repo, TwoFactor::enroll(user_id.clone())).await;
+
+        let _ = TwoFactorRepository::save(&repo, confirmed(&user_id)).await;
+
+        let found = repo.find_by_user_id(&user_id).await.unwrap().unwrap();
+        assert_eq!(found, confirmed(&user_id));
+    }
+
+    #[tokio::test]
+    async fn claims_each_step_once() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user_id = Id::generate_unique_identifier();
+        let _ = TwoFactorRepository::save(&repo, confirmed(&user_id)).await;
+
+        assert_eq!(repo.claim_step(&user_id, 7).await, Ok(false));
+        assert_eq!(repo.claim_step(&user_id, 8).await, Ok(true));
+        assert_eq!(repo.claim_step(&user_id, 8).await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn consumes_each_recovery_code_once() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user_id = Id::generate_unique_identifier();
+        let _ = TwoFactorRepository::save(&repo, confirmed(&user_id)).await;
+        let code_hash = TwoFactor::hash("abcde-fghij");
+
+        assert_eq!(
+            repo.consume_recovery_code(&user_id, &code_hash).await,
+            Ok(true)
+        );
+        assert_eq!(
+            repo.consume_recovery_code(&user_id, &code_hash).await,
+            Ok(false)
+        );
+    }
+}
@@ -1,88 +1,1265 @@
-use std::sync::Mutex;
+use std::time::Duration;
 
 use async_trait::async_trait;
-use rusqlite::{params, Connection};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{
+    named_params, types::Type, Connection, ErrorCode, OptionalExtension, Row, ToSql, Transaction,
+};
 
-use crate::domain::{
-    entities::user::User,
-    repositories::user_repository::UserRepository,
-    value_objects::{id::Id, password::Password},
+use crate::{
+    domain::{
+        entities::user::User,
+        repositories::{
+            repository_error::RepositoryError,
+            user_query::{SortDirection, UserPage, UserQuery, UserSort, UserStatus},
+            user_repository::UserRepository,
+        },
+        value_objects::{
+            email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password, role::Role,
+        },
+    },
+    infrastructure::{sqlite_migrations, sqlite_outbox_repository::append_to_outbox},
 };
 
-#[derive(Debug)]
+const IN_MEMORY: &str = ":memory:";
+
+/// Tables holding rows that belong to a user, deleted along with the user.
+const USER_OWNED_TABLES: [&str; 6] = [
+    "refresh_tokens",
+    "password_reset_tokens",
+    "email_verification_tokens",
+    "two_factor",
+    "recovery_codes",
+    "login_challenges",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolOptions {
+    pub max_connections: u32,
+    /// How long a connection waits on a write lock held by another one
+    /// before giving up with `SQLITE_BUSY`.
+    pub busy_timeout: Duration,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        PoolOptions {
+            max_connections: 8,
+            busy_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// A pool of connections in WAL mode, so readers do not wait on the writer.
+/// Queries run on tokio's blocking threads rather than on the async workers.
+#[derive(Debug, Clone)]
 pub struct Sqlite {
-    connection: Mutex<rusqlite::Connection>,
+    pool: Pool<SqliteConnectionManager>,
 }
 
 impl Sqlite {
     pub async fn new(path: &str) -> anyhow::Result<Sqlite> {
-        let connection = Connection::open(path)?;
+        Self::with_pool(path, PoolOptions::default()).await
+    }
+
+    pub async fn with_pool(path: &str, options: PoolOptions) -> anyhow::Result<Sqlite> {
+        let busy_timeout = options.busy_timeout;
+        let manager = SqliteConnectionManager::file(path).with_init(move |connection| {
+            connection.busy_timeout(busy_timeout)?;
+            connection.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+            connection.pragma_update(None, "synchronous", "NORMAL")
+        });
+
+        // Every connection to `:memory:` is a database of its own, so an
+        // in-memory pool is a single connection that is never recycled.
+        let builder = if path == IN_MEMORY {
+            Pool::builder()
+                .max_size(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+        } else {
+            Pool::builder().max_size(options.max_connections)
+        };
 
-        let create_schema = "create table if not exists users
-        (
-            id TEXT PRIMARY KEY NOT NULL,
-            email TEXT NOT NULL,
-            password TEXT NOT NULL
-        );";
+        let sqlite = tokio::task::spawn_blocking(move || -> anyhow::Result<Sqlite> {
+            let pool = builder.build(manager)?;
+            let mut connection = pool.get()?;
+            let version = sqlite_migrations::migrate(&mut connection)?;
+            log::info!("database schema at version {}", version);
+            Ok(Sqlite { pool })
+        })
+        .await??;
+
+        Ok(sqlite)
+    }
 
-        connection.execute(create_schema, ())?;
+    pub fn schema_version(&self) -> anyhow::Result<u32> {
+        let connection = self.pool.get()?;
+        Ok(sqlite_migrations::current_version(&connection)?)
+    }
 
-        Ok(Sqlite {
-            connection: Mutex::new(connection),
+    /// Runs `query` on a pooled connection off the async executor.
+    pub(crate) async fn run<T, F>(&self, query: F) -> Result<T, RepositoryError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut connection = pool
+                .get()
+                .map_err(|e| RepositoryError::Unavailable(e.to_string()))?;
+            query(&mut connection).map_err(to_repository_error)
         })
+        .await
+        .map_err(|e| RepositoryError::Unavailable(e.to_string()))?
+    }
+
+    fn user_from_row(row: &Row) -> rusqlite::Result<User> {
+        let id: String = row.get("id")?;
+        let email: String = row.get("email")?;
+        let password: String = row.get("password")?;
+        let email_verified: bool = row.get("email_verified")?;
+        let failed_login_attempts: u32 = row.get("failed_login_attempts")?;
+        let locked_until: Option<u64> = row.get("locked_until")?;
+        let deleted_at: Option<u64> = row.get("deleted_at")?;
+        let created_at: u64 = row.get("created_at")?;
+        let role: String = row.get("role")?;
+
+        let id = Id::from(id)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))?;
+        let email = Email::new(email)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, Type::Text, Box::new(e)))?;
+        let role: Role = role
+            .parse()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(9, Type::Text, Box::new(e)))?;
+
+        let mut user = User::new(id, email, Password::from_hash(password))
+            .with_login_attempts(failed_login_attempts, locked_until)
+            .with_deleted_at(deleted_at)
+            .with_created_at(created_at)
+            .with_role(role);
+        if email_verified {
+            user.verify_email();
+        }
+        Ok(user)
     }
 }
 
 #[async_trait]
 impl UserRepository for Sqlite {
-    async fn save(&self, user: crate::domain::entities::user::User) -> Result<(), String> {
-        self.connection
-            .lock()
-            .map_err(|e| e.to_string())?
+    async fn save(&self, mut user: User) -> Result<(), RepositoryError> {
+        let events = user.pull_events();
+
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            transaction.execute(
+                "INSERT INTO users
+                (id, email, password, email_verified, failed_login_attempts, locked_until, deleted_at,
+                created_at, role)
+                VALUES (:id, :email, :password, :email_verified, :failed_login_attempts,
+                :locked_until, :deleted_at, :created_at, :role)
+                ON CONFLICT (id) DO UPDATE SET email = excluded.email, password = excluded.password,
+                email_verified = excluded.email_verified,
+                failed_login_attempts = excluded.failed_login_attempts,
+                locked_until = excluded.locked_until, deleted_at = excluded.deleted_at,
+                role = excluded.role",
+                named_params! {
+                    ":id": user.id(),
+                    ":email": user.email(),
+                    ":password": user.password(),
+                    ":email_verified": user.is_email_verified(),
+                    ":failed_login_attempts": user.failed_login_attempts(),
+                    ":locked_until": user.locked_until(),
+                    ":deleted_at": user.deleted_at(),
+                    ":created_at": user.created_at(),
+                    ":role": user.role().as_str(),
+                },
+            )?;
+            append_to_outbox(&transaction, &events)?;
+            transaction.commit()
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: Id) -> Result<Option<User>, RepositoryError> {
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT id, email, password, email_verified, failed_login_attempts, locked_until,
+                    deleted_at, created_at, role FROM users WHERE id = :id AND deleted_at IS NULL",
+                    named_params! { ":id": id.to_string() },
+                    Self::user_from_row,
+                )
+                .optional()
+        })
+        .await
+    }
+
+    async fn find_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError> {
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT id, email, password, email_verified, failed_login_attempts, locked_until,
+                    deleted_at, created_at, role FROM users WHERE email = :email AND deleted_at IS NULL",
+                    named_params! { ":email": email.to_string() },
+                    Self::user_from_row,
+                )
+                .optional()
+        })
+        .await
+    }
+
+    async fn find_deleted_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError> {
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT id, email, password, email_verified, failed_login_attempts, locked_until,
+                    deleted_at, created_at, role FROM users
+                    WHERE email = :email AND deleted_at IS NOT NULL",
+                    named_params! { ":email": email.to_string() },
+                    Self::user_from_row,
+                )
+                .optional()
+        })
+        .await
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+        self.run(|connection| {
+            connection
+                .prepare(
+                    "SELECT id, email, password, email_verified, failed_login_attempts, locked_until,
+                    deleted_at, created_at, role FROM users WHERE deleted_at IS NULL ORDER BY rowid",
+                )?
+                .query_map((), Self::user_from_row)?
+                .collect()
+        })
+        .await
+    }
+
+    async fn find_page(&self, query: UserQuery) -> Result<UserPage, RepositoryError> {
+        let limit = query.limit;
+        let users = self
+            .run(move |connection| {
+                let (sql, params) = page_statement(&query);
+                let params: Vec<(&str, &dyn ToSql)> = params
+                    .iter()
+                    .map(|(name, value)| (*name, value.as_ref()))
+                    .collect();
+
+                connection
+                    .prepare(&sql)?
+                    .query_map(params.as_slice(), Self::user_from_row)?
+                    .collect()
+            })
+            .await?;
+
+        Ok(UserPage::from_overfetched(users, limit))
+    }
+
+    async fn remove(&self, mut user: User) -> Result<(), RepositoryError> {
+        let events = user.pull_events();
+
+        let removed = self
+            .run(move |connection| {
+                let transaction = connection.transaction()?;
+                let removed =
+                    delete_users(&transaction, "id = :id", named_params! { ":id": user.id() })?;
+                if removed > 0 {
+                    append_to_outbox(&transaction, &events)?;
+                }
+                transaction.commit()?;
+                Ok(removed)
+            })
+            .await?;
+
+        if removed == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn register_failed_login(
+        &self,
+        id: Id,
+        policy: LockoutPolicy,
+        now: u64,
+    ) -> Result<(), RepositoryError> {
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let failed_login_attempts: Option<u32> = transaction
+                .query_row(
+                    "UPDATE users SET failed_login_attempts = failed_login_attempts + 1
+                    WHERE id = :id AND deleted_at IS NULL RETURNING failed_login_attempts",
+                    named_params! { ":id": id.to_string() },
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(duration) = failed_login_attempts.and_then(|a| policy.lockout_duration(a)) {
+                transaction.execute(
+                    "UPDATE users SET locked_until = :locked_until WHERE id = :id",
+                    named_params! { ":id": id.to_string(), ":locked_until": now + duration },
+                )?;
+            }
+            transaction.commit()
+        })
+        .await
+    }
+
+    async fn register_successful_login(&self, mut user: User) -> Result<bool, RepositoryError> {
+        let events = user.pull_events();
+
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let updated = transaction.execute(
+                "UPDATE users SET failed_login_attempts = 0, locked_until = NULL
+                WHERE id = :id AND password = :password AND deleted_at IS NULL",
+                named_params! { ":id": user.id(), ":password": user.password() },
+            )?;
+            if updated > 0 {
+                append_to_outbox(&transaction, &events)?;
+            }
+            transaction.commit()?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn upgrade_password_hash(
+        &self,
+        id: Id,
+        previous_hash: String,
+        upgraded_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        self.run(move |connection| {
+            let updated = connection.execute(
+                "UPDATE users SET password = :upgraded WHERE id = :id
+                AND password = :previous AND deleted_at IS NULL",
+                named_params! {
+                    ":id": id.to_string(),
+                    ":previous": previous_hash,
+                    ":upgraded": upgraded_hash,
+                },
+            )?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn replace_password(
+        &self,
+        mut user: User,
+        previous_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        let events = user.pull_events();
+
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let updated = transaction.execute(
+                "UPDATE users SET password = :password, failed_login_attempts = 0,
+                locked_until = NULL WHERE id = :id AND password = :previous AND deleted_at IS NULL",
+                named_params! {
+                    ":id": user.id(),
+                    ":previous": previous_hash,
+                    ":password": user.password(),
+                },
+            )?;
+            if updated > 0 {
+                append_to_outbox(&transaction, &events)?;
+            }
+            transaction.commit()?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn mark_deleted(&self, mut user: User) -> Result<bool, RepositoryError> {
+        let events = user.pull_events();
+
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let updated = transaction.execute(
+                "UPDATE users SET deleted_at = :deleted_at WHERE id = :id AND deleted_at IS NULL",
+                named_params! { ":id": user.id(), ":deleted_at": user.deleted_at() },
+            )?;
+            if updated > 0 {
+                append_to_outbox(&transaction, &events)?;
+            }
+            transaction.commit()?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn assign_role(&self, id: Id, role: Role) -> Result<bool, RepositoryError> {
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            // One statement, so two admins demoting each other at once
+            // cannot both see the other one still in place.
+            let assigned = transaction.execute(
+                "UPDATE users SET role = :role WHERE id = :id AND deleted_at IS NULL
+                AND (:role = 'admin' OR role != 'admin' OR EXISTS (SELECT 1 FROM users AS other
+                WHERE other.role = 'admin' AND other.deleted_at IS NULL AND other.id != :id))",
+                named_params! { ":id": id.to_string(), ":role": role.as_str() },
+            )?;
+            if assigned == 0 {
+                transaction.query_row(
+                    "SELECT 1 FROM users WHERE id = :id AND deleted_at IS NULL",
+                    named_params! { ":id": id.to_string() },
+                    |_| Ok(()),
+                )?;
+            }
+            transaction.commit()?;
+            Ok(assigned > 0)
+        })
+        .await
+    }
+
+    async fn purge_deleted_before(&self, cutoff: u64) -> Result<u64, RepositoryError> {
+        let purged = self
+            .run(move |connection| {
+                let transaction = connection.transaction()?;
+                let purged = delete_users(
+                    &transaction,
+                    "deleted_at IS NOT NULL AND deleted_at < :cutoff",
+                    named_params! { ":cutoff": cutoff },
+                )?;
+                transaction.commit()?;
+                Ok(purged)
+            })
+            .await?;
+
+        Ok(purged as u64)
+    }
+}
+
+/// Deletes the users matching `condition` together with the rows they own,
+/// so none are left pointing at a user that no longer exists.
+fn delete_users(
+    transaction: &Transaction,
+    condition: &str,
+    params: &[(&str, &dyn ToSql)],
+) -> rusqlite::Result<usize> {
+    for table in USER_OWNED_TABLES {
+        transaction.execute(
+            &format!(
+                "DELETE FROM {} WHERE user_id IN (SELECT id FROM users WHERE {})",
+                table, condition
+            ),
+            params,
+        )?;
+    }
+    transaction.execute(&format!("DELETE FROM users WHERE {}", condition), params)
+}
+
+type NamedParams = Vec<(&'static str, Box<dyn ToSql>)>;
+
+/// Builds the keyset query for a page, fetching one user more than the limit
+/// to know whether there is a next page.
+fn page_statement(query: &UserQuery) -> (String, NamedParams) {
+    let mut conditions = vec!["deleted_at IS NULL".to_string()];
+    let mut params: NamedParams = Vec::new();
+
+    if let Some(part) = &query.email_contains {
+        conditions.push("email LIKE :contains ESCAPE '\\'".to_string());
+        params.push((":contains", Box::new(format!("%{}%", escape_like(part)))));
+    }
+    if let Some(domain) = &query.email_domain {
+        conditions.push("email LIKE :domain ESCAPE '\\'".to_string());
+        params.push((":domain", Box::new(format!("%@{}", escape_like(domain)))));
+    }
+    match query.status {
+        Some(UserStatus::Verified) => conditions.push("email_verified = 1".to_string()),
+        Some(UserStatus::Unverified) => conditions.push("email_verified = 0".to_string()),
+        Some(UserStatus::Locked) => {
+            conditions.push("locked_until > :now".to_string());
+            params.push((":now", Box::new(query.now)));
+        }
+        None => {}
+    }
+
+    let column = match query.sort {
+        UserSort::CreatedAt => "created_at",
+        UserSort::Email => "email",
+    };
+    let (operator, order) = match query.direction {
+        SortDirection::Ascending => (">", "ASC"),
+        SortDirection::Descending => ("<", "DESC"),
+    };
+    if let Some(after) = &query.after {
+        conditions.push(format!(
+            "({}, id) {} (:after_key, :after_id)",
+            column, operator
+        ));
+        match query.sort {
+            UserSort::CreatedAt => params.push((":after_key", Box::new(after.created_at))),
+            UserSort::Email => params.push((":after_key", Box::new(after.email.clone()))),
+        }
+        params.push((":after_id", Box::new(after.id.clone())));
+    }
+    params.push((":limit", Box::new(query.limit + 1)));
+
+    let sql = format!(
+        "SELECT id, email, password, email_verified, failed_login_attempts, locked_until,
+        deleted_at, created_at, role FROM users WHERE {} ORDER BY {} {}, id {} LIMIT :limit",
+        conditions.join(" AND "),
+        column,
+        order,
+        order
+    );
+    (sql, params)
+}
+
+/// Escapes the `LIKE` wildcards, which are common in emails (`_`).
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub(crate) fn to_repository_error(error: rusqlite::Error) -> RepositoryError {
+    match error {
+        rusqlite::Error::QueryReturnedNoRows => RepositoryError::NotFound,
+        rusqlite::Error::SqliteFailure(failure, message)
+            if failure.code == ErrorCode::ConstraintViolation =>
+        {
+            RepositoryError::ConstraintViolation(message.unwrap_or_else(|| failure.to_string()))
+        }
+        rusqlite::Error::FromSqlConversionFailure(..)
+        | rusqlite::Error::InvalidColumnType(..)
+        | rusqlite::Error::IntegralValueOutOfRange(..) => {
+            RepositoryError::CorruptData(error.to_string())
+        }
+        other => RepositoryError::Unavailable(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::{
+        entities::{
+            email_verification_token::EmailVerificationToken, login_challenge::LoginChallenge,
+            password_reset_token::PasswordResetToken, refresh_token::RefreshToken,
+            two_factor::TwoFactor, user::User,
+        },
+        repositories::{
+            outbox_repository::OutboxRepository,
+            user_query::{SortDirection, UserQuery, UserSort, UserStatus},
+            user_repository::UserRepository,
+        },
+        value_objects::{
+            email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password, role::Role,
+        },
+    };
+
+    use super::{Sqlite, USER_OWNED_TABLES};
+    use crate::application::outbox_dispatcher::MAX_DELIVERY_ATTEMPTS;
+    use crate::domain::repositories::repository_error::RepositoryError;
+    use crate::infrastructure::sqlite_migrations;
+
+    #[tokio::test]
+    async fn find_user_by_id() {
+        let id = Id::generate_unique_identifier();
+        let user = User::new(
+            id.clone(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("SafePass123_".to_string()).unwrap(),
+        );
+
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let _res = repo.save(user.clone()).await;
+
+        let found_user = repo.find_by_id(id.clone()).await;
+
+        assert_eq!(found_user, Ok(Some(user)));
+    }
+
+    #[tokio::test]
+    async fn does_not_find_non_existing_user_by_id() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        let found_user = repo.find_by_id(Id::generate_unique_identifier()).await;
+
+        assert_eq!(found_user, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn find_user_by_email() {
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let user = create_user_by_email(email.clone());
+
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let _res = repo.save(user.clone()).await;
+
+        let found_user = repo.find_by_email(email.clone()).await;
+
+        assert_eq!(found_user, Ok(Some(user)));
+    }
+
+    #[tokio::test]
+    async fn does_not_find_non_existing_user_by_email() {
+        let email = Email::new("test@example.com".to_string()).unwrap();
+
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        let found_user = repo.find_by_email(email.clone()).await;
+
+        assert_eq!(found_user, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn finds_all_users() {
+        let a_user = create_user_by_email(Email::new("test1@example.com".to_string()).unwrap());
+        let another_user =
+            create_user_by_email(Email::new("test2@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        let _ = repo.save(a_user.clone()).await;
+        let _ = repo.save(another_user.clone()).await;
+
+        let users = repo.find_all().await;
+
+        assert_eq!(users, Ok(vec![a_user.clone(), another_user.clone()]));
+    }
+
+    #[tokio::test]
+    async fn finds_no_users_when_empty() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let users = repo.find_all().await;
+
+        assert_eq!(users.as_ref().unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn removes_a_user() {
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let user = create_user_by_email(email.clone());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        let _ = repo.save(user.clone()).await;
+        let _ = repo.remove(user.clone()).await;
+
+        let found_user = repo.find_by_email(email.clone()).await;
+
+        assert_eq!(found_user, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn does_not_remove_non_existing_user() {
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        assert_eq!(repo.remove(user).await, Err(RepositoryError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn update_user_when_exists() {
+        let mut a_user = create_user_by_email(Email::new("test1@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        let _ = repo.save(a_user.clone()).await;
+        let _ = a_user.change_password("AnotherSafePass123_".to_string());
+        let res = repo.save(a_user.clone()).await;
+
+        let users = repo.find_all().await.unwrap();
+
+        assert_eq!(res, Ok(()));
+        assert_eq!(users, vec![a_user.clone()]);
+        assert!(users[0].is_matching_password("AnotherSafePass123_"));
+    }
+
+    #[tokio::test]
+    async fn persists_email_verification() {
+        let mut user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        let _ = repo.save(user.clone()).await;
+        assert!(!repo.find_all().await.unwrap()[0].is_email_verified());
+
+        user.verify_email();
+        let _ = repo.save(user.clone()).await;
+        assert!(repo.find_all().await.unwrap()[0].is_email_verified());
+    }
+
+    #[tokio::test]
+    async fn persists_the_role() {
+        let mut user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        let _ = repo.save(user.clone()).await;
+        assert_eq!(repo.find_all().await.unwrap()[0].role(), Role::User);
+
+        user.assign_role(Role::Admin);
+        let _ = repo.save(user.clone()).await;
+        assert_eq!(repo.find_all().await.unwrap()[0].role(), Role::Admin);
+    }
+
+    #[tokio::test]
+    async fn registers_a_successful_login_without_writing_back_the_user() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap())
+            .with_login_attempts(3, None);
+        let _ = repo.save(user.clone()).await;
+        let mut logging_in = user.clone();
+        logging_in.record_login(100);
+        let mut demoted = user.clone();
+        demoted.assign_role(Role::Admin);
+        let _ = repo.save(demoted).await;
+
+        assert_eq!(repo.register_successful_login(logging_in).await, Ok(true));
+
+        let stored = repo.find_all().await.unwrap().remove(0);
+        assert_eq!(stored.failed_login_attempts(), 0);
+        assert_eq!(stored.role(), Role::Admin);
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn replaces_only_the_password_of_a_user_loaded_earlier() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap())
+            .with_login_attempts(3, Some(100));
+        let _ = repo.save(user.clone()).await;
+        let previous_hash = user.password();
+        let mut changing = user.clone();
+        let _ = changing.change_password("AnotherSafePass123_".to_string());
+        let mut promoted = user.clone();
+        promoted.assign_role(Role::Admin);
+        let _ = repo.save(promoted).await;
+
+        let replaced = repo.replace_password(changing, previous_hash.clone()).await;
+        let mut again = user.clone();
+        let _ = again.change_password("ThirdSafePass123_".to_string());
+        let stale = repo.replace_password(again, previous_hash).await;
+
+        let stored = repo.find_all().await.unwrap().remove(0);
+        assert_eq!(replaced, Ok(true));
+        assert_eq!(stale, Ok(false));
+        assert!(stored.is_matching_password("AnotherSafePass123_"));
+        assert_eq!(stored.role(), Role::Admin);
+        assert_eq!(stored.failed_login_attempts(), 0);
+        assert_eq!(stored.locked_until(), None);
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn does_not_replace_the_password_of_a_deleted_user() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let _ = repo.save(user.clone()).await;
+        let mut changing = user.clone();
+        let _ = changing.change_password("AnotherSafePass123_".to_string());
+        let mut deleted = user.clone();
+        deleted.delete(100);
+        let _ = repo.save(deleted).await;
+
+        let replaced = repo.replace_password(changing, user.password()).await;
+
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let stored = repo.find_deleted_by_email(email).await.unwrap().unwrap();
+        assert_eq!(replaced, Ok(false));
+        assert_eq!(stored.password(), user.password());
+        assert_eq!(stored.deleted_at(), Some(100));
+    }
+
+    #[tokio::test]
+    async fn marks_a_user_deleted_without_writing_back_the_user() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let _ = repo.save(user.clone()).await;
+        let mut deleting = user.clone();
+        deleting.delete(100);
+        let mut promoted = user.clone();
+        promoted.assign_role(Role::Admin);
+        let _ = repo.save(promoted).await;
+
+        let deleted = repo.mark_deleted(deleting.clone()).await;
+        let again = repo.mark_deleted(deleting).await;
+
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let stored = repo.find_deleted_by_email(email).await.unwrap().unwrap();
+        assert_eq!(deleted, Ok(true));
+        assert_eq!(again, Ok(false));
+        assert_eq!(stored.deleted_at(), Some(100));
+        assert_eq!(stored.role(), Role::Admin);
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_a_password_reset_made_during_a_login() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let _ = repo.save(user.clone()).await;
+        let mut logging_in = user.clone();
+        let mut reset = user.clone();
+        reset.reset_password(Password::new("AnotherSafePass123_".to_string()).unwrap());
+        let _ = repo.save(reset.clone()).await;
+        let pending = repo
+            .find_pending(10, MAX_DELIVERY_ATTEMPTS)
+            .await
+            .unwrap()
+            .len();
+
+        logging_in.record_login(100);
+        assert_eq!(repo.register_successful_login(logging_in).await, Ok(false));
+        assert_eq!(
+            repo.upgrade_password_hash(
+                Id::from(user.id()).unwrap(),
+                user.password(),
+                "upgraded".to_string()
+            )
+            .await,
+            Ok(false)
+        );
+
+        let stored = repo.find_all().await.unwrap().remove(0);
+        assert_eq!(stored.password(), reset.password());
+        assert_eq!(
+            repo.find_pending(10, MAX_DELIVERY_ATTEMPTS)
+                .await
+                .unwrap()
+                .len(),
+            pending
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_to_demote_the_last_admin() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let mut admin = create_user_by_email(Email::new("admin@example.com".to_string()).unwrap());
+        admin.assign_role(Role::Admin);
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let _ = repo.save(admin.clone()).await;
+        let _ = repo.save(user.clone()).await;
+        let admin_id = Id::from(admin.id()).unwrap();
+        let user_id = Id::from(user.id()).unwrap();
+
+        assert_eq!(
+            repo.assign_role(admin_id.clone(), Role::User).await,
+            Ok(false)
+        );
+        assert_eq!(
+            repo.assign_role(user_id.clone(), Role::User).await,
+            Ok(true)
+        );
+        assert_eq!(repo.assign_role(user_id, Role::Admin).await, Ok(true));
+        assert_eq!(
+            repo.assign_role(admin_id.clone(), Role::User).await,
+            Ok(true)
+        );
+        assert_eq!(
+            repo.find_by_id(admin_id).await.unwrap().unwrap().role(),
+            Role::User
+        );
+        assert_eq!(
+            repo.assign_role(Id::generate_unique_identifier(), Role::User)
+                .await,
+            Err(RepositoryError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn persists_failed_login_attempts() {
+        let mut user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let policy = LockoutPolicy::default();
+        for _ in 0..policy.max_attempts {
+            user.register_failed_login(&policy, 100);
+        }
+
+        let _ = repo.save(user.clone()).await;
+        let stored = repo.find_all().await.unwrap().remove(0);
+
+        assert_eq!(stored.failed_login_attempts(), policy.max_attempts);
+        assert_eq!(stored.locked_until(), user.locked_until());
+        assert!(stored.is_locked(100));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn counts_concurrent_failed_logins_atomically() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let repo = Sqlite::new(file.path().to_str().unwrap()).await.unwrap();
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let _ = repo.save(user.clone()).await;
+        let policy = LockoutPolicy::default();
+
+        let failures = (0..16).map(|_| {
+            let repo = repo.clone();
+            let id = Id::from(user.id()).unwrap();
+            tokio::spawn(async move { repo.register_failed_login(id, policy, 100).await })
+        });
+        for failure in failures.collect::<Vec<_>>() {
+            assert_eq!(failure.await.unwrap(), Ok(()));
+        }
+        let stored = repo.find_all().await.unwrap().remove(0);
+
+        assert_eq!(stored.failed_login_attempts(), 16);
+        assert_eq!(
+            stored.locked_until(),
+            policy.lockout_duration(16).map(|d| 100 + d)
+        );
+    }
+
+    #[tokio::test]
+    async fn hides_deleted_users_from_the_finders() {
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let mut user = create_user_by_email(email.clone());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        user.delete(100);
+
+        let _ = repo.save(user.clone()).await;
+
+        assert_eq!(repo.find_by_email(email.clone()).await, Ok(None));
+        assert_eq!(
+            repo.find_by_id(Id::from(user.id()).unwrap()).await,
+            Ok(None)
+        );
+        assert_eq!(repo.find_all().await, Ok(vec![]));
+        let deleted = repo.find_deleted_by_email(email).await.unwrap().unwrap();
+        assert_eq!(deleted.deleted_at(), Some(100));
+    }
+
+    #[tokio::test]
+    async fn purges_only_users_deleted_before_the_cutoff() {
+        let mut old = create_user_by_email(Email::new("old@example.com".to_string()).unwrap());
+        let mut recent =
+            create_user_by_email(Email::new("recent@example.com".to_string()).unwrap());
+        let active = create_user_by_email(Email::new("active@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        old.delete(100);
+        recent.delete(200);
+        for user in [&old, &recent, &active] {
+            let _ = repo.save(user.clone()).await;
+        }
+
+        let purged = repo.purge_deleted_before(150).await;
+
+        assert_eq!(purged, Ok(1));
+        assert_eq!(
+            repo.find_deleted_by_email(Email::new("old@example.com".to_string()).unwrap())
+                .await,
+            Ok(None)
+        );
+        assert!(repo
+            .find_deleted_by_email(Email::new("recent@example.com".to_string()).unwrap())
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(repo.find_all().await, Ok(vec![active]));
+    }
+
+    #[tokio::test]
+    async fn purges_the_rows_owned_by_purged_users() {
+        let mut old = create_user_by_email(Email::new("old@example.com".to_string()).unwrap());
+        let active = create_user_by_email(Email::new("active@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        old.delete(100);
+        for user in [&old, &active] {
+            let _ = repo.save(user.clone()).await;
+            store_owned_rows(&repo, Id::from(user.id()).unwrap()).await;
+        }
+
+        let _ = repo.purge_deleted_before(150).await;
+
+        assert_eq!(owned_row_count(&repo, old.id()).await, 0);
+        assert_eq!(
+            owned_row_count(&repo, active.id()).await,
+            USER_OWNED_TABLES.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn removes_the_rows_owned_by_a_removed_user() {
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let _ = repo.save(user.clone()).await;
+        store_owned_rows(&repo, Id::from(user.id()).unwrap()).await;
+
+        let _ = repo.remove(user.clone()).await;
+
+        assert_eq!(owned_row_count(&repo, user.id()).await, 0);
+    }
+
+    #[tokio::test]
+    async fn does_not_allow_two_users_with_same_email() {
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        let _ = repo.save(create_user_by_email(email.clone())).await;
+        let res = repo.save(create_user_by_email(email.clone())).await;
+
+        assert!(matches!(res, Err(RepositoryError::ConstraintViolation(_))));
+    }
+
+    #[tokio::test]
+    async fn reports_corrupt_rows_as_errors() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        repo.pool
+            .get()
+            .unwrap()
+            .execute(
+                "INSERT INTO users (id, email, password) VALUES ('not-an-id', 'test@example.com', 'x')",
+                (),
+            )
+            .unwrap();
+
+        let found_user = repo
+            .find_by_email(Email::new("test@example.com".to_string()).unwrap())
+            .await;
+
+        assert!(matches!(found_user, Err(RepositoryError::CorruptData(_))));
+        assert!(matches!(
+            repo.find_all().await,
+            Err(RepositoryError::CorruptData(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_latest_schema_version() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+
+        assert_eq!(
+            repo.schema_version().unwrap(),
+            sqlite_migrations::latest_version()
+        );
+    }
+
+    #[tokio::test]
+    async fn upgrades_a_database_created_before_migrations() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        let user = create_user_by_email(Email::new("test@example.com".to_string()).unwrap());
+
+        let legacy = rusqlite::Connection::open(path).unwrap();
+        legacy
+            .execute(
+                "create table if not exists users
+                (
+                    id TEXT PRIMARY KEY NOT NULL,
+                    email TEXT NOT NULL,
+                    password TEXT NOT NULL
+                );",
+                (),
+            )
+            .unwrap();
+        legacy
             .execute(
                 "INSERT INTO users (id, email, password) VALUES (?1, ?2, ?3)",
                 (&user.id(), &user.email(), &user.password()),
             )
-            .map_err(|e| e.to_string())?;
+            .unwrap();
+        drop(legacy);
 
-        Ok(())
+        let repo = Sqlite::new(path).await.unwrap();
+
+        assert_eq!(
+            repo.schema_version().unwrap(),
+            sqlite_migrations::latest_version()
+        );
+        assert_eq!(repo.find_all().await, Ok(vec![user.clone()]));
+        assert!(repo.find_all().await.unwrap()[0].is_email_verified());
+        assert!(repo
+            .save(create_user_by_email(
+                Email::new("test@example.com".to_string()).unwrap()
+            ))
+            .await
+            .is_err());
     }
 
-    async fn find_by_email(
-        &self,
-        email: crate::domain::value_objects::email::Email,
-    ) -> Result<Option<crate::domain::entities::user::User>, String> {
-        let user = self
-            .connection
-            .lock()
-            .map_err(|e| e.to_string())?
-            .query_row(
-                "SELECT * FROM users where email=:email",
-                params![email.to_string()],
-                |row| {
-                    let id: String = row.get(0)?;
-                    let email: String = row.get(1)?;
-                    let password: String = row.get(2)?;
-
-                    Ok(User::new(
-                        id.try_into().unwrap(),
-                        email.try_into().unwrap(),
-                        Password::from_hash(password),
-                    ))
-                },
-            );
+    #[tokio::test]
+    async fn pages_through_users_in_sort_order() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        for (email, created_at) in [
+            ("a@example.com", 3),
+            ("b@example.com", 1),
+            ("c@example.com", 2),
+        ] {
+            let user = create_user_by_email(Email::new(email.to_string()).unwrap())
+                .with_created_at(created_at);
+            let _ = repo.save(user).await;
+        }
+        let mut deleted = create_user_by_email(Email::new("d@example.com".to_string()).unwrap());
+        deleted.delete(100);
+        let _ = repo.save(deleted).await;
+        let by_email = UserQuery {
+            sort: UserSort::Email,
+            limit: 2,
+            ..UserQuery::default()
+        };
 
-        match user {
-            Ok(u) => Ok(Some(u)),
-            Err(error) => Ok(None),
+        let first = repo.find_page(by_email.clone()).await.unwrap();
+        let second = repo
+            .find_page(UserQuery {
+                after: first.next.clone(),
+                ..by_email
+            })
+            .await
+            .unwrap();
+        let newest = repo
+            .find_page(UserQuery {
+                direction: SortDirection::Descending,
+                ..UserQuery::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(emails(&first.users), vec!["a@example.com", "b@example.com"]);
+        assert_eq!(emails(&second.users), vec!["c@example.com"]);
+        assert_eq!(second.next, None);
+        assert_eq!(
+            emails(&newest.users),
+            vec!["a@example.com", "c@example.com", "b@example.com"]
+        );
+    }
+
+    #[tokio::test]
+    async fn filters_users_by_email_and_status() {
+        let repo = Sqlite::new(":memory:").await.unwrap();
+        let mut jane =
+            create_user_by_email(Email::new("jane_doe@example.com".to_string()).unwrap());
+        jane.verify_email();
+        let janex = create_user_by_email(Email::new("janexdoe@example.com".to_string()).unwrap());
+        let john = create_user_by_email(Email::new("john@other.org".to_string()).unwrap())
+            .with_login_attempts(5, Some(200));
+        for user in [jane, janex, john] {
+            let _ = repo.save(user).await;
         }
+        let find = |query: UserQuery| {
+            let repo = &repo;
+            async move { emails(&repo.find_page(query).await.unwrap().users) }
+        };
+
+        assert_eq!(
+            find(UserQuery {
+                email_contains: Some("E_D".to_string()),
+                ..UserQuery::default()
+            })
+            .await,
+            vec!["jane_doe@example.com"]
+        );
+        assert_eq!(
+            find(UserQuery {
+                email_domain: Some("example.com".to_string()),
+                status: Some(UserStatus::Unverified),
+                ..UserQuery::default()
+            })
+            .await,
+            vec!["janexdoe@example.com"]
+        );
+        assert_eq!(
+            find(UserQuery {
+                status: Some(UserStatus::Locked),
+                now: 100,
+                ..UserQuery::default()
+            })
+            .await,
+            vec!["john@other.org"]
+        );
     }
 
-    async fn find_all(&self) -> Result<Vec<crate::domain::entities::user::User>, String> {
-        todo!()
+    #[tokio::test(flavor = "multi_thread")]
+    async fn saves_concurrently_through_the_pool() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let repo = Sqlite::new(file.path().to_str().unwrap()).await.unwrap();
+
+        let saves = (0..32).map(|i| {
+            let repo = repo.clone();
+            tokio::spawn(async move {
+                let email = Email::new(format!("user{}@example.com", i)).unwrap();
+                repo.save(create_user_by_email(email)).await
+            })
+        });
+        for save in saves.collect::<Vec<_>>() {
+            assert_eq!(save.await.unwrap(), Ok(()));
+        }
+
+        assert_eq!(repo.find_all().await.unwrap().len(), 32);
+        let journal_mode: String = repo
+            .pool
+            .get()
+            .unwrap()
+            .query_row("PRAGMA journal_mode", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+    }
+
+    fn emails(users: &[User]) -> Vec<String> {
+        users.iter().map(|u| u.email()).collect()
+    }
+
+    /// Stores one row in every table of `USER_OWNED_TABLES`.
+    async fn store_owned_rows(repo: &Sqlite, user_id: Id) {
+        use crate::domain::repositories::{
+            email_verification_token_repository::EmailVerificationTokenRepository,
+            login_challenge_repository::LoginChallengeRepository,
+            password_reset_token_repository::PasswordResetTokenRepository,
+            refresh_token_repository::RefreshTokenRepository,
+            two_factor_repository::TwoFactorRepository,
+        };
+
+        let (refresh_token, _) = RefreshToken::issue(user_id.clone(), 60, 0);
+        let (reset_token, _) = PasswordResetToken::issue(user_id.clone(), 60, 0);
+        let (verification_token, _) = EmailVerificationToken::issue(user_id.clone(), 60, 0);
+        let (challenge, _) = LoginChallenge::issue(user_id.clone(), 60, 0);
+        let two_factor = TwoFactor::new(user_id, vec![1; 20], true, None, vec!["code".to_string()]);
+        RefreshTokenRepository::save(repo, refresh_token)
+            .await
+            .unwrap();
+        PasswordResetTokenRepository::save(repo, reset_token)
+            .await
+            .unwrap();
+        EmailVerificationTokenRepository::save(repo, verification_token)
+            .await
+            .unwrap();
+        LoginChallengeRepository::save(repo, challenge)
+            .await
+            .unwrap();
+        TwoFactorRepository::save(repo, two_factor).await.unwrap();
+    }
+
+    async fn owned_row_count(repo: &Sqlite, user_id: String) -> usize {
+        repo.run(move |connection| {
+            USER_OWNED_TABLES
+                .iter()
+                .map(|table| {
+                    connection.query_row(
+                        &format!("SELECT COUNT(*) FROM {} WHERE user_id = ?1", table),
+                        [&user_id],
+                        |row| row.get::<_, usize>(0),
+                    )
+                })
+                .sum()
+        })
+        .await
+        .unwrap()
     }
 
-    async fn remove(&self, user: crate::domain::entities::user::User) -> Result<(), String> {
-        todo!()
+    fn create_user_by_email(email: Email) -> User {
+        let id = Id::generate_unique_identifier();
+        let password = Password::new("SafePass123_".to_string()).unwrap();
+        User::new(id, email, password)
     }
 }
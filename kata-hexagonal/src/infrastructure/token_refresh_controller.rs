@@ -0,0 +1,141 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{TokenRefreshRequest, TokenRefreshResponse},
+    token_refresh_service::TokenRefreshService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct TokenRefreshController {
+    service: TokenRefreshService,
+}
+
+impl TokenRefreshController {
+    pub fn new(service: TokenRefreshService) -> Self {
+        TokenRefreshController { service }
+    }
+
+    pub async fn refresh<T: HttpResponse<Result<TokenRefreshResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<TokenRefreshRequest>,
+        response: &mut T,
+    ) {
+        match self.service.refresh(request.body).await {
+            Ok(refresh_response) => response.status(200).json(Ok(refresh_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            dtos::{TokenRefreshRequest, TokenRefreshResponse},
+            token_refresh_service::TokenRefreshService,
+        },
+        domain::{
+            common::time,
+            entities::{refresh_token::RefreshToken, user::User},
+            repositories::{
+                refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+            },
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+            jwt_token_issuer::JwtTokenIssuer,
+        },
+    };
+
+    use super::TokenRefreshController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<TokenRefreshResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<TokenRefreshResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<TokenRefreshResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn refreshes_a_valid_token() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let tokens = Arc::new(InMemoryRefreshTokenRepository::new());
+        let user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::from_hash("hash".to_string()),
+        );
+        let _ = users.save(user.clone()).await;
+        let (token, refresh_token) =
+            RefreshToken::issue(Id::from(user.id()).unwrap(), 60, time::now());
+        let _ = tokens.save(token).await;
+        let controller = TokenRefreshController::new(TokenRefreshService::new(
+            users,
+            tokens,
+            Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60))),
+        ));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .refresh(
+                HttpRequest {
+                    body: TokenRefreshRequest { refresh_token },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 200);
+        assert!(response.data.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_an_invalid_token_as_unauthorized() {
+        let controller = TokenRefreshController::new(TokenRefreshService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60))),
+        ));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .refresh(
+                HttpRequest {
+                    body: TokenRefreshRequest {
+                        refresh_token: "unknown".to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 401);
+        assert!(response.data.unwrap().is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{TwoFactorConfirmRequest, TwoFactorConfirmResponse},
+    two_factor_confirm_service::TwoFactorConfirmService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct TwoFactorConfirmController {
+    service: TwoFactorConfirmService,
+}
+
+impl TwoFactorConfirmController {
+    pub fn new(service: TwoFactorConfirmService) -> Self {
+        TwoFactorConfirmController { service }
+    }
+
+    pub async fn confirm<T: HttpResponse<Result<TwoFactorConfirmResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<TwoFactorConfirmRequest>,
+        response: &mut T,
+    ) {
+        match self.service.confirm(request.body).await {
+            Ok(confirm_response) => response.status(200).json(Ok(confirm_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            dtos::{TwoFactorConfirmRequest, TwoFactorConfirmResponse},
+            two_factor_confirm_service::TwoFactorConfirmService,
+        },
+        domain::value_objects::id::Id,
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_two_factor_repository::InMemoryTwoFactorRepository,
+        },
+    };
+
+    use super::TwoFactorConfirmController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<TwoFactorConfirmResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<TwoFactorConfirmResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<TwoFactorConfirmResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_a_missing_enrollment_as_conflict() {
+        let controller = TwoFactorConfirmController::new(TwoFactorConfirmService::new(Arc::new(
+            InMemoryTwoFactorRepository::new(),
+        )));
+        let mut response = MockResponse {
+            status: 0,
+            data: None,
+        };
+
+        controller
+            .confirm(
+                HttpRequest {
+                    body: TwoFactorConfirmRequest {
+                        user_id: Id::generate_unique_identifier().to_string(),
+                        code: "123456".to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 409);
+        assert!(matches!(
+            response.data.unwrap().unwrap_err(),
+            ApplicationError::TwoFactorNotEnrolled(_)
+        ));
+    }
+}
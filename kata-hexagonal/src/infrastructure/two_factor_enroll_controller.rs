@@ -0,0 +1,94 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{TwoFactorEnrollRequest, TwoFactorEnrollResponse},
+    two_factor_enroll_service::TwoFactorEnrollService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct TwoFactorEnrollController {
+    service: TwoFactorEnrollService,
+}
+
+impl TwoFactorEnrollController {
+    pub fn new(service: TwoFactorEnrollService) -> Self {
+        TwoFactorEnrollController { service }
+    }
+
+    pub async fn enroll<T: HttpResponse<Result<TwoFactorEnrollResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<TwoFactorEnrollRequest>,
+        response: &mut T,
+    ) {
+        match self.service.enroll(request.body).await {
+            Ok(enroll_response) => response.status(200).json(Ok(enroll_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            dtos::{TwoFactorEnrollRequest, TwoFactorEnrollResponse},
+            two_factor_enroll_service::TwoFactorEnrollService,
+        },
+        domain::value_objects::id::Id,
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_two_factor_repository::InMemoryTwoFactorRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::TwoFactorEnrollController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<TwoFactorEnrollResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<TwoFactorEnrollResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<TwoFactorEnrollResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unknown_user_as_not_found() {
+        let controller = TwoFactorEnrollController::new(TwoFactorEnrollService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            "Kata".to_string(),
+        ));
+        let mut response = MockResponse {
+            status: 0,
+            data: None,
+        };
+
+        controller
+            .enroll(
+                HttpRequest {
+                    body: TwoFactorEnrollRequest {
+                        user_id: Id::generate_unique_identifier().to_string(),
+                        password: "TestPass123_".to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 404);
+        assert!(response.data.unwrap().is_err());
+    }
+}
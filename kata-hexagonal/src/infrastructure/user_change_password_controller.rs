@@ -0,0 +1,145 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{UserChangePasswordRequest, UserChangePasswordResponse},
+    user_change_password_service::UserChangePasswordService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct UserChangePasswordController {
+    service: UserChangePasswordService,
+}
+
+impl UserChangePasswordController {
+    pub fn new(service: UserChangePasswordService) -> Self {
+        UserChangePasswordController { service }
+    }
+
+    pub async fn change_password<
+        T: HttpResponse<Result<UserChangePasswordResponse, ApplicationError>>,
+    >(
+        &self,
+        request: HttpRequest<UserChangePasswordRequest>,
+        response: &mut T,
+    ) {
+        match self.service.change_password(request.body).await {
+            Ok(change_response) => response.status(200).json(Ok(change_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            dtos::{UserChangePasswordRequest, UserChangePasswordResponse},
+            user_change_password_service::UserChangePasswordService,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_audit_log::InMemoryAuditLog,
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::UserChangePasswordController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<UserChangePasswordResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<UserChangePasswordResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(
+            &mut self,
+            data: Result<UserChangePasswordResponse, ApplicationError>,
+        ) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    async fn change_password(current: &str, new: &str) -> MockResponse {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        let _ = repo.save(user.clone()).await;
+        let controller = UserChangePasswordController::new(UserChangePasswordService::new(
+            repo.clone(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        ));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .change_password(
+                HttpRequest {
+                    body: UserChangePasswordRequest {
+                        requester_id: user.id(),
+                        user_id: user.id(),
+                        current_password: current.to_string(),
+                        new_password: new.to_string(),
+                        client_ip: None,
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn changes_the_password() {
+        let response = change_password("TestPass123_", "AnotherPass123_").await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.data.unwrap().unwrap().email, "test@example.com");
+    }
+
+    #[tokio::test]
+    async fn responds_unauthorized_for_a_wrong_current_password() {
+        let response = change_password("WrongPass123_", "AnotherPass123_").await;
+
+        assert_eq!(response.status, 401);
+    }
+
+    #[tokio::test]
+    async fn responds_unprocessable_for_a_weak_password() {
+        let response = change_password("TestPass123_", "weak").await;
+
+        assert_eq!(response.status, 422);
+    }
+
+    #[tokio::test]
+    async fn responds_conflict_for_the_same_password() {
+        let response = change_password("TestPass123_", "TestPass123_").await;
+
+        assert_eq!(response.status, 409);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{MessageResponse, UserDeleteRequest},
+    user_delete_service::UserDeleteService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct UserDeleteController {
+    service: UserDeleteService,
+}
+
+impl UserDeleteController {
+    pub fn new(service: UserDeleteService) -> Self {
+        UserDeleteController { service }
+    }
+
+    pub async fn delete<T: HttpResponse<Result<MessageResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<UserDeleteRequest>,
+        response: &mut T,
+    ) {
+        match self.service.delete(request.body).await {
+            Ok(delete_response) => response.status(200).json(Ok(delete_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            authorizer::Requester,
+            dtos::{MessageResponse, UserDeleteRequest},
+            user_delete_service::UserDeleteService,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::UserDeleteController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<MessageResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<MessageResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<MessageResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    async fn delete(requester_role: Role) -> MockResponse {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut requester = create_user("admin@example.com");
+        requester.assign_role(requester_role);
+        let user = create_user("test@example.com");
+        let _ = repo.save(requester.clone()).await;
+        let _ = repo.save(user.clone()).await;
+        let controller = UserDeleteController::new(UserDeleteService::new(
+            repo,
+            Arc::new(InMemoryEventBus::new()),
+        ));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .delete(
+                HttpRequest {
+                    body: UserDeleteRequest {
+                        requester: Requester::User(requester.id()),
+                        id: user.id(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn lets_an_admin_delete_another_user() {
+        let response = delete(Role::Admin).await;
+
+        assert_eq!(response.status, 200);
+        assert!(response.data.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn responds_forbidden_to_regular_users() {
+        let response = delete(Role::User).await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    fn create_user(email: &str) -> User {
+        User::new(
+            Id::generate_unique_identifier(),
+            Email::new(email.to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        )
+    }
+}
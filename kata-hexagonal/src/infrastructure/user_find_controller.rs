@@ -0,0 +1,158 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{UserFindRequest, UserFindResponse},
+    user_find_service::UserFindService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct UserFindController {
+    service: UserFindService,
+}
+
+impl UserFindController {
+    pub fn new(service: UserFindService) -> Self {
+        UserFindController { service }
+    }
+
+    pub async fn find_by_id<T: HttpResponse<Result<UserFindResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<UserFindRequest>,
+        response: &mut T,
+    ) {
+        match self.service.find_by_id(request.body).await {
+            Ok(find_response) => response.status(200).json(Ok(find_response)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            authorizer::Requester,
+            dtos::{UserFindRequest, UserFindResponse},
+            user_find_service::UserFindService,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::UserFindController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<UserFindResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<UserFindResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<UserFindResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn finds_a_user() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let controller = UserFindController::new(UserFindService::new(repo.clone()));
+        let user = create_user();
+        let _ = repo.save(user.clone()).await;
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .find_by_id(
+                HttpRequest {
+                    body: UserFindRequest {
+                        requester: Requester::User(user.id()),
+                        id: user.id(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.data.unwrap().unwrap().email, "test@example.com");
+    }
+
+    #[tokio::test]
+    async fn responds_not_found_when_user_does_not_exist() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let controller = UserFindController::new(UserFindService::new(repo.clone()));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .find_by_id(
+                HttpRequest {
+                    body: UserFindRequest {
+                        requester: Requester::System,
+                        id: Id::generate_unique_identifier().to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 404);
+        assert!(response.data.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn responds_not_found_when_id_is_malformed() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let controller = UserFindController::new(UserFindService::new(repo.clone()));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .find_by_id(
+                HttpRequest {
+                    body: UserFindRequest {
+                        requester: Requester::System,
+                        id: "invalid-id".to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 404);
+    }
+
+    fn create_user() -> User {
+        let id = Id::generate_unique_identifier();
+        let email = Email::new("test@example.com".to_string()).unwrap();
+        let password = Password::new("TestPass123_".to_string()).unwrap();
+
+        User::new(id, email, password)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{UserPageResponse, UserSearchRequest},
+    user_list_service::UserListService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct UserListController {
+    service: UserListService,
+}
+
+impl UserListController {
+    pub fn new(service: UserListService) -> Self {
+        UserListController { service }
+    }
+
+    pub async fn search<T: HttpResponse<Result<UserPageResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<UserSearchRequest>,
+        response: &mut T,
+    ) {
+        match self.service.search(request.body).await {
+            Ok(page) => response.status(200).json(Ok(page)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            authorizer::Requester,
+            dtos::{UserPageResponse, UserSearchRequest},
+            user_list_service::UserListService,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::UserListController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<UserPageResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<UserPageResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<UserPageResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    async fn search(requester_role: Role, status: Option<&str>) -> MockResponse {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut user = User::new(
+            Id::generate_unique_identifier(),
+            Email::new("test@example.com".to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        );
+        user.assign_role(requester_role);
+        let _ = repo.save(user.clone()).await;
+        let controller = UserListController::new(UserListService::new(repo));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .search(
+                HttpRequest {
+                    body: UserSearchRequest {
+                        status: status.map(str::to_string),
+                        ..UserSearchRequest::new(Requester::User(user.id()))
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn returns_a_page_of_users() {
+        let response = search(Role::Admin, None).await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.data.unwrap().unwrap().users.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn responds_forbidden_to_other_users() {
+        let response = search(Role::User, None).await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    #[tokio::test]
+    async fn responds_bad_request_for_an_invalid_query() {
+        let response = search(Role::Admin, Some("banned")).await;
+
+        assert_eq!(response.status, 400);
+    }
+}
@@ -1,11 +1,10 @@
-use std::error::Error;
-
 use crate::application::{
-    dtos::{UserLoginRequest, UserLoginResponse},
+    application_error::ApplicationError,
+    dtos::{TwoFactorLoginRequest, UserLoginOutcome, UserLoginRequest, UserLoginResponse},
     user_login_service::UserLoginService,
 };
 
-use super::http::{HttpRequest, HttpResponse};
+use super::http::{status_code, HttpRequest, HttpResponse};
 
 pub struct UserLoginController {
     service: UserLoginService,
@@ -16,14 +15,27 @@ impl UserLoginController {
         UserLoginController { service }
     }
 
-    pub async fn login<T: HttpResponse<Result<UserLoginResponse, Box<dyn Error>>>>(
+    pub async fn login<T: HttpResponse<Result<UserLoginOutcome, ApplicationError>>>(
         &self,
         request: HttpRequest<UserLoginRequest>,
         response: &mut T,
     ) {
         match self.service.login(request.body).await {
+            Ok(outcome) => response.status(200).json(Ok(outcome)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+
+    pub async fn login_second_factor<
+        T: HttpResponse<Result<UserLoginResponse, ApplicationError>>,
+    >(
+        &self,
+        request: HttpRequest<TwoFactorLoginRequest>,
+        response: &mut T,
+    ) {
+        match self.service.login_second_factor(request.body).await {
             Ok(login_response) => response.status(200).json(Ok(login_response)),
-            Err(error) => response.status(400).json(Err(error)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
         };
     }
 }
@@ -32,10 +44,12 @@ impl UserLoginController {
 mod test {
     use std::error::Error;
     use std::sync::Arc;
+    use std::time::Duration;
 
     use crate::{
         application::{
-            dtos::{UserLoginRequest, UserLoginResponse},
+            application_error::ApplicationError,
+            dtos::{TwoFactorLoginRequest, UserLoginOutcome, UserLoginRequest},
             user_login_service::UserLoginService,
         },
         domain::{
@@ -45,24 +59,30 @@ mod test {
         },
         infrastructure::{
             http::{HttpRequest, HttpResponse},
+            in_memory_audit_log::InMemoryAuditLog,
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_login_challenge_repository::InMemoryLoginChallengeRepository,
+            in_memory_refresh_token_repository::InMemoryRefreshTokenRepository,
+            in_memory_two_factor_repository::InMemoryTwoFactorRepository,
             in_memory_user_repository::InMemoryUserRepository,
+            jwt_token_issuer::JwtTokenIssuer,
         },
     };
 
     use super::UserLoginController;
 
-    struct MockResponse {
+    struct MockResponse<D> {
         status: u16,
-        data: Option<Result<UserLoginResponse, Box<dyn Error>>>,
+        data: Option<Result<D, ApplicationError>>,
     }
 
-    impl HttpResponse<Result<UserLoginResponse, Box<dyn Error>>> for MockResponse {
+    impl<D> HttpResponse<Result<D, ApplicationError>> for MockResponse<D> {
         fn status(&mut self, code: u16) -> &mut Self {
             self.status = code;
             self
         }
 
-        fn json(&mut self, data: Result<UserLoginResponse, Box<dyn Error>>) -> &mut Self {
+        fn json(&mut self, data: Result<D, ApplicationError>) -> &mut Self {
             self.data = Some(data);
             self
         }
@@ -74,10 +94,18 @@ mod test {
         let password = "TestPass123_".to_string();
 
         let repo = Arc::new(InMemoryUserRepository::new());
-        let login_service = UserLoginService::new(repo.clone());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
         let controller = UserLoginController::new(login_service);
 
-        repo.as_ref().save(create_user().unwrap()).await;
+        let _ = repo.as_ref().save(create_user().unwrap()).await;
 
         let mut response = MockResponse {
             status: 200,
@@ -87,14 +115,21 @@ mod test {
         controller
             .login(
                 HttpRequest {
-                    body: UserLoginRequest { email, password },
+                    body: UserLoginRequest {
+                        email,
+                        password,
+                        client_ip: None,
+                    },
                 },
                 &mut response,
             )
             .await;
 
         assert_eq!(response.status, 200);
-        assert_eq!(response.data.unwrap().unwrap().email, "test@example.com");
+        let Ok(UserLoginOutcome::Authenticated(login)) = response.data.unwrap() else {
+            panic!("expected tokens");
+        };
+        assert_eq!(login.email, "test@example.com");
     }
 
     #[tokio::test]
@@ -103,9 +138,56 @@ mod test {
         let password = "SecurePass123_".to_string();
 
         let repo = Arc::new(InMemoryUserRepository::new());
-        let login_service = UserLoginService::new(repo.clone());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserLoginController::new(login_service);
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .login(
+                HttpRequest {
+                    body: UserLoginRequest {
+                        email,
+                        password,
+                        client_ip: None,
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 422);
+        assert!(response.data.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unverified_user_as_forbidden() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        )
+        .require_verified_email(true);
         let controller = UserLoginController::new(login_service);
 
+        let _ = repo.as_ref().save(create_user().unwrap()).await;
+
         let mut response = MockResponse {
             status: 200,
             data: None,
@@ -114,16 +196,174 @@ mod test {
         controller
             .login(
                 HttpRequest {
-                    body: UserLoginRequest { email, password },
+                    body: UserLoginRequest {
+                        email: "test@example.com".to_string(),
+                        password: "TestPass123_".to_string(),
+                        client_ip: None,
+                    },
                 },
                 &mut response,
             )
             .await;
 
-        assert_eq!(response.status, 400);
+        assert_eq!(response.status, 403);
         assert!(response.data.unwrap().is_err());
     }
 
+    #[tokio::test]
+    async fn rejects_a_locked_account_as_too_many_requests() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserLoginController::new(login_service);
+
+        let locked = create_user()
+            .unwrap()
+            .with_login_attempts(5, Some(u64::MAX));
+        let _ = repo.as_ref().save(locked).await;
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .login(
+                HttpRequest {
+                    body: UserLoginRequest {
+                        email: "test@example.com".to_string(),
+                        password: "TestPass123_".to_string(),
+                        client_ip: None,
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 429);
+        assert!(response.data.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_a_wrong_password_as_unauthorized() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let login_service = UserLoginService::new(
+            repo.clone(),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserLoginController::new(login_service);
+
+        let _ = repo.as_ref().save(create_user().unwrap()).await;
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .login(
+                HttpRequest {
+                    body: UserLoginRequest {
+                        email: "test@example.com".to_string(),
+                        password: "WrongPass123_".to_string(),
+                        client_ip: None,
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 401);
+        assert!(matches!(
+            response.data.unwrap().unwrap_err(),
+            ApplicationError::InvalidCredentials(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unknown_user_as_unauthorized() {
+        let login_service = UserLoginService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserLoginController::new(login_service);
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .login(
+                HttpRequest {
+                    body: UserLoginRequest {
+                        email: "nobody@example.com".to_string(),
+                        password: "TestPass123_".to_string(),
+                        client_ip: None,
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 401);
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unknown_login_challenge_as_unauthorized() {
+        let login_service = UserLoginService::new(
+            Arc::new(InMemoryUserRepository::new()),
+            token_issuer(),
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryTwoFactorRepository::new()),
+            Arc::new(InMemoryLoginChallengeRepository::new()),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserLoginController::new(login_service);
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .login_second_factor(
+                HttpRequest {
+                    body: TwoFactorLoginRequest {
+                        challenge_token: "unknown".to_string(),
+                        code: "123456".to_string(),
+                        client_ip: None,
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 401);
+        assert!(matches!(
+            response.data.unwrap().unwrap_err(),
+            ApplicationError::InvalidLoginChallenge(_)
+        ));
+    }
+
     fn create_user() -> Result<User, Box<dyn Error>> {
         let id = Id::generate_unique_identifier();
         let email = Email::new("test@example.com".to_string())?;
@@ -131,4 +371,8 @@ mod test {
 
         Ok(User::new(id, email, password))
     }
+
+    fn token_issuer() -> Arc<JwtTokenIssuer> {
+        Arc::new(JwtTokenIssuer::new(b"secret", Duration::from_secs(60)))
+    }
 }
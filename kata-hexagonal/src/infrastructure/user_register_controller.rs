@@ -1,11 +1,10 @@
-use std::error::Error;
-
 use crate::application::{
+    application_error::ApplicationError,
     dtos::{UserRegisterRequest, UserRegisterResponse},
     user_register_service::UserRegisterService,
 };
 
-use super::http::{HttpRequest, HttpResponse};
+use super::http::{status_code, HttpRequest, HttpResponse};
 
 pub struct UserRegisterController {
     service: UserRegisterService,
@@ -16,48 +15,55 @@ impl UserRegisterController {
         UserRegisterController { service }
     }
 
-    pub async fn register<T: HttpResponse<Result<UserRegisterResponse, Box<dyn Error>>>>(
+    pub async fn register<T: HttpResponse<Result<UserRegisterResponse, ApplicationError>>>(
         &self,
         request: HttpRequest<UserRegisterRequest>,
         response: &mut T,
     ) {
         match self.service.register(request.body).await {
             Ok(register_response) => response.status(201).json(Ok(register_response)),
-            Err(error) => response.status(400).json(Err(error)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
         };
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::error::Error;
+
     use std::sync::Arc;
 
     use crate::{
         application::{
+            application_error::ApplicationError,
             dtos::{UserRegisterRequest, UserRegisterResponse},
+            email_verification_sender::EmailVerificationSender,
             user_register_service::UserRegisterService,
         },
         infrastructure::{
             http::{HttpRequest, HttpResponse},
+            in_memory_audit_log::InMemoryAuditLog,
+            in_memory_email_verification_token_repository::InMemoryEmailVerificationTokenRepository,
+            in_memory_event_bus::InMemoryEventBus,
+            in_memory_mailer::InMemoryMailer,
             in_memory_user_repository::InMemoryUserRepository,
         },
+        test_support::FailingUserRepository,
     };
 
     use super::UserRegisterController;
 
     struct MockResponse {
         status: u16,
-        data: Option<Result<UserRegisterResponse, Box<dyn Error>>>,
+        data: Option<Result<UserRegisterResponse, ApplicationError>>,
     }
 
-    impl HttpResponse<Result<UserRegisterResponse, Box<dyn Error>>> for MockResponse {
+    impl HttpResponse<Result<UserRegisterResponse, ApplicationError>> for MockResponse {
         fn status(&mut self, code: u16) -> &mut Self {
             self.status = code;
             self
         }
 
-        fn json(&mut self, data: Result<UserRegisterResponse, Box<dyn Error>>) -> &mut Self {
+        fn json(&mut self, data: Result<UserRegisterResponse, ApplicationError>) -> &mut Self {
             self.data = Some(data);
             self
         }
@@ -69,8 +75,13 @@ mod test {
         let password = "SecurePass123_".to_string();
 
         let repo = Arc::new(InMemoryUserRepository::new());
-        let register_service = UserRegisterService::new(repo.clone());
-        let mut controller = UserRegisterController::new(register_service);
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserRegisterController::new(register_service);
 
         let mut response = MockResponse {
             status: 200,
@@ -80,7 +91,11 @@ mod test {
         controller
             .register(
                 HttpRequest {
-                    body: UserRegisterRequest { email, password },
+                    body: UserRegisterRequest {
+                        email,
+                        password,
+                        client_ip: None,
+                    },
                 },
                 &mut response,
             )
@@ -96,8 +111,13 @@ mod test {
         let password = "SecurePass123_".to_string();
 
         let repo = Arc::new(InMemoryUserRepository::new());
-        let register_service = UserRegisterService::new(repo.clone());
-        let mut controller = UserRegisterController::new(register_service);
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserRegisterController::new(register_service);
 
         let mut response = MockResponse {
             status: 200,
@@ -107,13 +127,126 @@ mod test {
         controller
             .register(
                 HttpRequest {
-                    body: UserRegisterRequest { email, password },
+                    body: UserRegisterRequest {
+                        email,
+                        password,
+                        client_ip: None,
+                    },
                 },
                 &mut response,
             )
             .await;
 
-        assert_eq!(response.status, 400);
+        assert_eq!(response.status, 422);
         assert!(response.data.unwrap().is_err());
     }
+
+    #[tokio::test]
+    async fn rejects_an_existing_email_as_conflict() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserRegisterController::new(register_service);
+
+        let mut first = MockResponse {
+            status: 200,
+            data: None,
+        };
+        let mut second = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller.register(request(), &mut first).await;
+        controller.register(request(), &mut second).await;
+
+        assert_eq!(first.status, 201);
+        assert_eq!(second.status, 409);
+        assert!(matches!(
+            second.data.unwrap().unwrap_err(),
+            ApplicationError::ExistingUser(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_weak_password_as_unprocessable() {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let register_service = UserRegisterService::new(
+            repo.clone(),
+            verification_sender(),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserRegisterController::new(register_service);
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .register(
+                HttpRequest {
+                    body: UserRegisterRequest {
+                        email: "test@example.com".to_string(),
+                        password: "weak".to_string(),
+                        client_ip: None,
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        assert_eq!(response.status, 422);
+        assert!(matches!(
+            response.data.unwrap().unwrap_err(),
+            ApplicationError::InvalidPassword(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_repository_failures_as_server_errors() {
+        let register_service = UserRegisterService::new(
+            Arc::new(FailingUserRepository {}),
+            verification_sender(),
+            Arc::new(InMemoryEventBus::new()),
+            Arc::new(InMemoryAuditLog::new()),
+        );
+        let controller = UserRegisterController::new(register_service);
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller.register(request(), &mut response).await;
+
+        assert_eq!(response.status, 500);
+        assert!(matches!(
+            response.data.unwrap().unwrap_err(),
+            ApplicationError::Repository(_)
+        ));
+    }
+
+    fn request() -> HttpRequest<UserRegisterRequest> {
+        HttpRequest {
+            body: UserRegisterRequest {
+                email: "test@example.com".to_string(),
+                password: "SecurePass123_".to_string(),
+                client_ip: None,
+            },
+        }
+    }
+
+    fn verification_sender() -> EmailVerificationSender {
+        EmailVerificationSender::new(
+            Arc::new(InMemoryEmailVerificationTokenRepository::new()),
+            Arc::new(InMemoryMailer::new()),
+            "http://localhost/verify".to_string(),
+        )
+    }
 }
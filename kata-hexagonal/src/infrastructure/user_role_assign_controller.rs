@@ -0,0 +1,131 @@
+use crate::application::{
+    application_error::ApplicationError,
+    dtos::{UserFindResponse, UserRoleAssignRequest},
+    user_role_assign_service::UserRoleAssignService,
+};
+
+use super::http::{status_code, HttpRequest, HttpResponse};
+
+pub struct UserRoleAssignController {
+    service: UserRoleAssignService,
+}
+
+impl UserRoleAssignController {
+    pub fn new(service: UserRoleAssignService) -> Self {
+        UserRoleAssignController { service }
+    }
+
+    pub async fn assign<T: HttpResponse<Result<UserFindResponse, ApplicationError>>>(
+        &self,
+        request: HttpRequest<UserRoleAssignRequest>,
+        response: &mut T,
+    ) {
+        match self.service.assign(request.body).await {
+            Ok(user) => response.status(200).json(Ok(user)),
+            Err(error) => response.status(status_code(&error)).json(Err(error)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+
+    use crate::{
+        application::{
+            application_error::ApplicationError,
+            authorizer::Requester,
+            dtos::{UserFindResponse, UserRoleAssignRequest},
+            user_role_assign_service::UserRoleAssignService,
+        },
+        domain::{
+            entities::user::User,
+            repositories::user_repository::UserRepository,
+            value_objects::{email::Email, id::Id, password::Password, role::Role},
+        },
+        infrastructure::{
+            http::{HttpRequest, HttpResponse},
+            in_memory_user_repository::InMemoryUserRepository,
+        },
+    };
+
+    use super::UserRoleAssignController;
+
+    struct MockResponse {
+        status: u16,
+        data: Option<Result<UserFindResponse, ApplicationError>>,
+    }
+
+    impl HttpResponse<Result<UserFindResponse, ApplicationError>> for MockResponse {
+        fn status(&mut self, code: u16) -> &mut Self {
+            self.status = code;
+            self
+        }
+
+        fn json(&mut self, data: Result<UserFindResponse, ApplicationError>) -> &mut Self {
+            self.data = Some(data);
+            self
+        }
+    }
+
+    async fn assign(requester_role: Role, role: &str) -> MockResponse {
+        let repo = Arc::new(InMemoryUserRepository::new());
+        let mut requester = create_user("admin@example.com");
+        requester.assign_role(requester_role);
+        let user = create_user("test@example.com");
+        let _ = repo.save(requester.clone()).await;
+        let _ = repo.save(user.clone()).await;
+        let controller = UserRoleAssignController::new(UserRoleAssignService::new(repo));
+
+        let mut response = MockResponse {
+            status: 200,
+            data: None,
+        };
+
+        controller
+            .assign(
+                HttpRequest {
+                    body: UserRoleAssignRequest {
+                        requester: Requester::User(requester.id()),
+                        user_id: user.id(),
+                        role: role.to_string(),
+                    },
+                },
+                &mut response,
+            )
+            .await;
+
+        response
+    }
+
+    #[tokio::test]
+    async fn returns_the_user_with_the_new_role() {
+        let response = assign(Role::Admin, "admin").await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.data.unwrap().unwrap().role, "admin");
+    }
+
+    #[tokio::test]
+    async fn responds_forbidden_to_regular_users() {
+        let response = assign(Role::User, "admin").await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    #[tokio::test]
+    async fn responds_unprocessable_for_an_unknown_role() {
+        let response = assign(Role::Admin, "root").await;
+
+        assert_eq!(response.status, 422);
+    }
+
+    fn create_user(email: &str) -> User {
+        User::new(
+            Id::generate_unique_identifier(),
+            Email::new(email.to_string()).unwrap(),
+            Password::new("TestPass123_".to_string()).unwrap(),
+        )
+    }
+}
@@ -1,3 +1,5 @@
-mod application;
-mod domain;
-mod infrastructure;
+pub mod application;
+pub mod domain;
+pub mod infrastructure;
+#[cfg(test)]
+mod test_support;
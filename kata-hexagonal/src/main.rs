@@ -1,10 +1,18 @@
-mod application;
-mod domain;
-mod infrastructure;
-
-use infrastructure::actix::server::create_server;
+use clap::Parser;
+use kata_hexagonal::infrastructure::{
+    actix::server::create_server,
+    config::{Cli, Config},
+};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    create_server("127.0.0.1", 8080).await
+    let config = match Config::load(&Cli::parse()) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            std::process::exit(2);
+        }
+    };
+
+    create_server(config).await
 }
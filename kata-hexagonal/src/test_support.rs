@@ -0,0 +1,193 @@
+//! Setup shared by the unit tests of several modules.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        entities::user::User,
+        repositories::{
+            repository_error::RepositoryError,
+            user_query::{UserPage, UserQuery},
+            user_repository::UserRepository,
+        },
+        value_objects::{
+            email::Email, id::Id, lockout_policy::LockoutPolicy, password::Password, role::Role,
+        },
+    },
+    infrastructure::in_memory_user_repository::InMemoryUserRepository,
+};
+
+/// Stores a user with a placeholder password hash, for tests that never log in.
+pub async fn create_stored_user(users: &InMemoryUserRepository) -> User {
+    let user = User::new(
+        Id::generate_unique_identifier(),
+        Email::new("test@example.com".to_string()).unwrap(),
+        Password::from_hash("hash".to_string()),
+    );
+    users.save(user.clone()).await.unwrap();
+    user
+}
+
+/// A user repository whose every call fails, as when the database is down.
+pub struct FailingUserRepository {}
+
+#[async_trait]
+impl UserRepository for FailingUserRepository {
+    async fn save(&self, _user: User) -> Result<(), RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn find_by_id(&self, _id: Id) -> Result<Option<User>, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn find_by_email(&self, _email: Email) -> Result<Option<User>, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn find_deleted_by_email(&self, _email: Email) -> Result<Option<User>, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn remove(&self, _user: User) -> Result<(), RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn find_page(&self, _query: UserQuery) -> Result<UserPage, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn register_failed_login(
+        &self,
+        _id: Id,
+        _policy: LockoutPolicy,
+        _now: u64,
+    ) -> Result<(), RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn register_successful_login(&self, _user: User) -> Result<bool, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn upgrade_password_hash(
+        &self,
+        _id: Id,
+        _previous_hash: String,
+        _upgraded_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn replace_password(
+        &self,
+        _user: User,
+        _previous_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn mark_deleted(&self, _user: User) -> Result<bool, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn assign_role(&self, _id: Id, _role: Role) -> Result<bool, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+
+    async fn purge_deleted_before(&self, _cutoff: u64) -> Result<u64, RepositoryError> {
+        Err(RepositoryError::Unavailable("database is down".to_string()))
+    }
+}
+
+/// Hands out `stale` from `find_by_id`, as a use case that loaded the user
+/// before a concurrent change landed would see it. Everything else goes to
+/// `users`, which holds the current state.
+pub struct StaleUserRepository {
+    pub users: Arc<InMemoryUserRepository>,
+    pub stale: User,
+}
+
+#[async_trait]
+impl UserRepository for StaleUserRepository {
+    async fn save(&self, user: User) -> Result<(), RepositoryError> {
+        self.users.save(user).await
+    }
+
+    async fn find_by_id(&self, id: Id) -> Result<Option<User>, RepositoryError> {
+        if self.stale.is_matching_id(&id) {
+            return Ok(Some(self.stale.clone()));
+        }
+        self.users.find_by_id(id).await
+    }
+
+    async fn find_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError> {
+        self.users.find_by_email(email).await
+    }
+
+    async fn find_deleted_by_email(&self, email: Email) -> Result<Option<User>, RepositoryError> {
+        self.users.find_deleted_by_email(email).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+        self.users.find_all().await
+    }
+
+    async fn remove(&self, user: User) -> Result<(), RepositoryError> {
+        self.users.remove(user).await
+    }
+
+    async fn find_page(&self, query: UserQuery) -> Result<UserPage, RepositoryError> {
+        self.users.find_page(query).await
+    }
+
+    async fn register_failed_login(
+        &self,
+        id: Id,
+        policy: LockoutPolicy,
+        now: u64,
+    ) -> Result<(), RepositoryError> {
+        self.users.register_failed_login(id, policy, now).await
+    }
+
+    async fn register_successful_login(&self, user: User) -> Result<bool, RepositoryError> {
+        self.users.register_successful_login(user).await
+    }
+
+    async fn upgrade_password_hash(
+        &self,
+        id: Id,
+        previous_hash: String,
+        upgraded_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        self.users
+            .upgrade_password_hash(id, previous_hash, upgraded_hash)
+            .await
+    }
+
+    async fn replace_password(
+        &self,
+        user: User,
+        previous_hash: String,
+    ) -> Result<bool, RepositoryError> {
+        self.users.replace_password(user, previous_hash).await
+    }
+
+    async fn mark_deleted(&self, user: User) -> Result<bool, RepositoryError> {
+        self.users.mark_deleted(user).await
+    }
+
+    async fn assign_role(&self, id: Id, role: Role) -> Result<bool, RepositoryError> {
+        self.users.assign_role(id, role).await
+    }
+
+    async fn purge_deleted_before(&self, cutoff: u64) -> Result<u64, RepositoryError> {
+        self.users.purge_deleted_before(cutoff).await
+    }
+}